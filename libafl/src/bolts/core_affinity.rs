@@ -135,13 +135,24 @@ impl Cores {
 
             // ./fuzzer --cores 1,2-4,6 -> clients run in cores 1,2,3,4,6
             for csv in core_args {
-                let core_range: Vec<&str> = csv.split('-').collect();
+                let core_range: Vec<&str> = csv.trim().split('-').collect();
                 if core_range.len() == 1 {
                     cores.push(core_range[0].parse::<usize>()?.into());
                 } else if core_range.len() == 2 {
-                    for x in core_range[0].parse::<usize>()?..=(core_range[1].parse::<usize>()?) {
+                    let start = core_range[0].parse::<usize>()?;
+                    let end = core_range[1].parse::<usize>()?;
+                    if start > end {
+                        return Err(Error::illegal_argument(format!(
+                            "Invalid core range {csv}: start is larger than end"
+                        )));
+                    }
+                    for x in start..=end {
                         cores.push(x.into());
                     }
+                } else {
+                    return Err(Error::illegal_argument(format!(
+                        "Invalid core range {csv}, expected a core or a range like 2-4"
+                    )));
                 }
             }
         }
@@ -919,4 +930,18 @@ mod tests {
 
         ids[0].set_affinity().unwrap();
     }
+
+    #[test]
+    fn test_cores_from_cmdline() {
+        let cores = Cores::from_cmdline("0,2-5, 7").unwrap();
+        assert_eq!(cores.cmdline, "0,2-5, 7");
+        assert_eq!(cores.ids, [0, 2, 3, 4, 5, 7].map(CoreId).to_vec());
+        assert!(cores.contains(CoreId(3)));
+        assert!(!cores.contains(CoreId(6)));
+        assert_eq!(cores.position(CoreId(7)), Some(5));
+
+        assert!(Cores::from_cmdline("5-2").is_err());
+        assert!(Cores::from_cmdline("1-2-3").is_err());
+        assert!(Cores::from_cmdline("a").is_err());
+    }
 }
@@ -44,7 +44,7 @@ use crate::{
     bolts::{core_affinity::Cores, shmem::ShMemProvider},
     events::{EventConfig, LlmpRestartingEventManager, ManagerKind, RestartingMgr},
     monitors::Monitor,
-    state::{HasClientPerfMonitor, HasExecutions},
+    state::{HasClientPerfMonitor, HasExecutions, State},
     Error,
 };
 
@@ -120,7 +120,7 @@ impl<'a, CF, MT, S, SP> Launcher<'a, CF, MT, S, SP>
 where
     CF: FnOnce(Option<S>, LlmpRestartingEventManager<S, SP>, CoreId) -> Result<(), Error>,
     MT: Monitor + Clone,
-    S: DeserializeOwned + UsesInput + HasExecutions + HasClientPerfMonitor,
+    S: State + HasExecutions + HasClientPerfMonitor,
     SP: ShMemProvider + 'static,
 {
     /// Launch the broker and the clients and fuzz
@@ -139,16 +139,14 @@ where
             ));
         }
 
-        let core_ids = get_core_ids().unwrap();
+        let core_ids = get_core_ids()?;
         let num_cores = core_ids.len();
         let mut handles = vec![];
 
         log::info!("spawning on cores: {:?}", self.cores);
 
         #[cfg(feature = "std")]
-        let stdout_file = self
-            .stdout_file
-            .map(|filename| File::create(filename).unwrap());
+        let stdout_file = self.stdout_file.map(File::create).transpose()?;
 
         #[cfg(feature = "std")]
         let debug_output = std::env::var("LIBAFL_DEBUG_OUTPUT").is_ok();
@@ -244,6 +242,12 @@ where
     pub fn launch(&mut self) -> Result<(), Error> {
         use crate::bolts::core_affinity;
 
+        if self.run_client.is_none() {
+            return Err(Error::illegal_argument(
+                "No client callback provided".to_string(),
+            ));
+        }
+
         let is_client = std::env::var(_AFL_LAUNCHER_CLIENT);
 
         let mut handles = match is_client {
@@ -275,7 +279,7 @@ where
                     log::info!("Child process file stdio is not supported on Windows yet. Dumping to stdout instead...");
                 }
 
-                let core_ids = core_affinity::get_core_ids().unwrap();
+                let core_ids = core_affinity::get_core_ids()?;
                 let num_cores = core_ids.len();
                 let mut handles = vec![];
 
@@ -81,7 +81,7 @@ use std::os::unix::io::AsRawFd;
 #[cfg(feature = "std")]
 use std::{
     env,
-    io::{ErrorKind, Read, Write},
+    io::{self, ErrorKind, Read, Write},
     net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs},
     sync::mpsc::channel,
     thread,
@@ -485,7 +485,32 @@ where
     Ok(())
 }
 
-/// Receive one message of `u32` len and `[u8; len]` bytes
+/// Whether `err` is the timeout of a read on a [`TcpStream`]
+#[cfg(feature = "std")]
+fn is_read_timeout(err: &io::Error) -> bool {
+    matches!(err.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut)
+}
+
+/// Fills all of `buf` from the `stream`, like [`Read::read_exact`], but keeps reading after timeouts.
+/// Unless `in_frame` is set, a timeout before the first byte is returned, so the caller can do something else.
+#[cfg(feature = "std")]
+fn read_exact_in_frame(stream: &mut TcpStream, buf: &mut [u8], in_frame: bool) -> io::Result<()> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        match stream.read(&mut buf[filled..]) {
+            Ok(0) => return Err(ErrorKind::UnexpectedEof.into()),
+            Ok(read) => filled += read,
+            Err(e) if e.kind() == ErrorKind::Interrupted => {}
+            // Giving up partway through a frame would throw off the framing of all following messages
+            Err(e) if is_read_timeout(&e) && (in_frame || filled > 0) => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(())
+}
+
+/// Receive one message of `u32` len and `[u8; len]` bytes.
+/// A read timeout is only returned if it hits before the message started.
 #[cfg(feature = "std")]
 fn recv_tcp_msg(stream: &mut TcpStream) -> Result<Vec<u8>, Error> {
     // Always receive one be u32 of size, then the command.
@@ -497,7 +522,7 @@ fn recv_tcp_msg(stream: &mut TcpStream) -> Result<Vec<u8>, Error> {
     );
 
     let mut size_bytes = [0_u8; 4];
-    stream.read_exact(&mut size_bytes)?;
+    read_exact_in_frame(stream, &mut size_bytes, false)?;
     let size = u32::from_be_bytes(size_bytes);
     let mut bytes = vec![];
     bytes.resize(size as usize, 0_u8);
@@ -505,9 +530,7 @@ fn recv_tcp_msg(stream: &mut TcpStream) -> Result<Vec<u8>, Error> {
     #[cfg(feature = "llmp_debug")]
     log::trace!("LLMP TCP: Receiving payload of size {size}");
 
-    stream
-        .read_exact(&mut bytes)
-        .expect("Failed to read message body");
+    read_exact_in_frame(stream, &mut bytes, true)?;
     Ok(bytes)
 }
 
@@ -739,10 +762,12 @@ where
 
     /// Describe this in a reproducable fashion, if it's a client
     pub fn describe(&self) -> Result<LlmpClientDescription, Error> {
-        Ok(match self {
-            LlmpConnection::IsClient { client } => client.describe()?,
-            LlmpConnection::IsBroker { .. } => todo!("Only client can be described atm."),
-        })
+        match self {
+            LlmpConnection::IsClient { client } => client.describe(),
+            LlmpConnection::IsBroker { .. } => {
+                Err(Error::not_implemented("Only client can be described atm."))
+            }
+        }
     }
 
     /// Recreate an existing client from the stored description
@@ -2065,9 +2090,11 @@ where
         log::info!("B2B: We are broker {broker_id:?}");
 
         // TODO: handle broker_ids properly/at all.
+        // The proxy thread must use the id `register_client` will hand out below,
+        // else it may collide with a live client and drop its messages as its own.
         let map_description = Self::b2b_thread_on(
             stream,
-            ClientId(self.llmp_clients.len() as u32),
+            ClientId(self.num_clients_total.try_into().unwrap()),
             &self
                 .llmp_out
                 .out_shmems
@@ -2352,7 +2379,7 @@ where
             // Crete a new ShMemProvider for this background thread
             let shmem_provider_bg = SP::new().unwrap();
 
-            #[cfg(fature = "llmp_debug")]
+            #[cfg(feature = "llmp_debug")]
             log::info!("B2b: Spawned proxy thread");
 
             // The background thread blocks on the incoming connection for 15 seconds (if no data is available), then checks if it should forward own messages, then blocks some more.
@@ -2430,8 +2457,7 @@ where
                 // We set a timeout on the receive earlier.
                 // This makes sure we will still forward our own stuff.
                 // Forwarding happens between each recv, too, as simplification.
-                // We ignore errors completely as they may be timeout, or stream closings.
-                // Instead, we catch stream close when/if we next try to send.
+                // Timeouts only hit between messages, any other error ends the connection.
                 match recv_tcp_msg(&mut stream) {
                     Ok(val) => {
                         let msg: TcpRemoteNewMessage = val.try_into().expect(
@@ -2454,18 +2480,17 @@ where
                             )
                             .expect("B2B: Error forwarding message. Exiting.");
                     }
-                    Err(e) => {
-                        if let Error::File(e, _) = e {
-                            if e.kind() == ErrorKind::UnexpectedEof {
-                                log::info!(
-                                    "Broker {peer_address} seems to have disconnected, exiting"
-                                );
-                                return;
-                            }
-                        }
-
+                    Err(Error::File(e, _)) if is_read_timeout(&e) => {
                         #[cfg(feature = "llmp_debug")]
-                        log::info!("Received no input, timeout or closed. Looping back up :)");
+                        log::info!("Received no input, timeout. Looping back up :)");
+                    }
+                    Err(Error::File(e, _)) if e.kind() == ErrorKind::UnexpectedEof => {
+                        log::info!("Broker {peer_address} seems to have disconnected, exiting");
+                        return;
+                    }
+                    Err(e) => {
+                        log::error!("Error receiving from broker {peer_address}: {e:?}, exiting");
+                        return;
                     }
                 }
             }
@@ -3086,17 +3111,53 @@ where
 #[cfg(all(unix, feature = "std"))]
 mod tests {
 
-    use std::{thread::sleep, time::Duration};
+    use std::{
+        io::Write,
+        net::{TcpListener, TcpStream},
+        thread::sleep,
+        time::Duration,
+    };
 
     use serial_test::serial;
 
     use super::{
-        LlmpClient,
+        is_read_timeout, recv_tcp_msg, LlmpClient,
         LlmpConnection::{self, IsBroker, IsClient},
         LlmpMsgHookResult::ForwardToClients,
         Tag,
     };
-    use crate::bolts::shmem::{ShMemProvider, StdShMemProvider};
+    use crate::{
+        bolts::shmem::{ShMemProvider, StdShMemProvider},
+        Error,
+    };
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn test_recv_tcp_msg_timeout() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let mut sender = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+        let (mut receiver, _) = listener.accept().unwrap();
+        receiver
+            .set_read_timeout(Some(Duration::from_millis(10)))
+            .unwrap();
+
+        // Nothing sent yet, the timeout is returned
+        assert!(matches!(
+            recv_tcp_msg(&mut receiver),
+            Err(Error::File(e, _)) if is_read_timeout(&e)
+        ));
+
+        // A timeout partway through a message keeps reading the rest of it
+        sender.write_all(&4_u32.to_be_bytes()).unwrap();
+        sender.write_all(&[1, 2]).unwrap();
+        let writer = std::thread::spawn(move || {
+            sleep(Duration::from_millis(50));
+            sender.write_all(&[3, 4]).unwrap();
+            sender
+        });
+        assert_eq!(recv_tcp_msg(&mut receiver).unwrap(), vec![1, 2, 3, 4]);
+        drop(writer.join().unwrap());
+    }
 
     #[test]
     #[serial]
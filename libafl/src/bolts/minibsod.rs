@@ -3,7 +3,7 @@
 //! You may use the [`crate::bolts::os::unix_signals::ucontext`]
 //! function to get a [`ucontext_t`].
 
-use std::io::{BufWriter, Write};
+use std::io::Write;
 
 use libc::siginfo_t;
 
@@ -13,7 +13,7 @@ use crate::bolts::os::unix_signals::{ucontext_t, Signal};
 #[cfg(all(target_os = "linux", target_arch = "x86_64"))]
 #[allow(clippy::similar_names)]
 pub fn dump_registers<W: Write>(
-    writer: &mut BufWriter<W>,
+    writer: &mut W,
     ucontext: &ucontext_t,
 ) -> Result<(), std::io::Error> {
     use libc::{
@@ -51,7 +51,7 @@ pub fn dump_registers<W: Write>(
     target_arch = "aarch64"
 ))]
 pub fn dump_registers<W: Write>(
-    writer: &mut BufWriter<W>,
+    writer: &mut W,
     ucontext: &ucontext_t,
 ) -> Result<(), std::io::Error> {
     for reg in 0..31 {
@@ -72,7 +72,7 @@ pub fn dump_registers<W: Write>(
 /// Write the content of all important registers
 #[cfg(all(target_os = "linux", target_arch = "arm"))]
 pub fn dump_registers<W: Write>(
-    writer: &mut BufWriter<W>,
+    writer: &mut W,
     ucontext: &ucontext_t,
 ) -> Result<(), std::io::Error> {
     write!(writer, "r0 : {:#016x}, ", ucontext.uc_mcontext.arm_r0)?;
@@ -101,7 +101,7 @@ pub fn dump_registers<W: Write>(
 #[cfg(all(target_vendor = "freebsd", target_arch = "aarch64"))]
 #[allow(clippy::similar_names)]
 pub fn dump_registers<W: Write>(
-    writer: &mut BufWriter<W>,
+    writer: &mut W,
     ucontext: &ucontext_t,
 ) -> Result<(), std::io::Error> {
     let mcontext = unsafe { &*ucontext.uc_mcontext };
@@ -125,7 +125,7 @@ pub fn dump_registers<W: Write>(
 #[cfg(all(target_vendor = "apple", target_arch = "aarch64"))]
 #[allow(clippy::similar_names)]
 pub fn dump_registers<W: Write>(
-    writer: &mut BufWriter<W>,
+    writer: &mut W,
     ucontext: &ucontext_t,
 ) -> Result<(), std::io::Error> {
     let mcontext = unsafe { &*ucontext.uc_mcontext };
@@ -150,7 +150,7 @@ pub fn dump_registers<W: Write>(
 #[allow(clippy::unnecessary_wraps, clippy::similar_names)]
 #[cfg(all(target_vendor = "apple", target_arch = "x86_64"))]
 pub fn dump_registers<W: Write>(
-    writer: &mut BufWriter<W>,
+    writer: &mut W,
     ucontext: &ucontext_t,
 ) -> Result<(), std::io::Error> {
     let mcontext = unsafe { *ucontext.uc_mcontext };
@@ -182,7 +182,7 @@ pub fn dump_registers<W: Write>(
 #[cfg(all(target_os = "freebsd", target_arch = "x86_64"))]
 #[allow(clippy::similar_names)]
 pub fn dump_registers<W: Write>(
-    writer: &mut BufWriter<W>,
+    writer: &mut W,
     ucontext: &ucontext_t,
 ) -> Result<(), std::io::Error> {
     let mcontext = &ucontext.uc_mcontext;
@@ -212,7 +212,7 @@ pub fn dump_registers<W: Write>(
 #[cfg(all(target_os = "netbsd", target_arch = "x86_64"))]
 #[allow(clippy::similar_names)]
 pub fn dump_registers<W: Write>(
-    writer: &mut BufWriter<W>,
+    writer: &mut W,
     ucontext: &ucontext_t,
 ) -> Result<(), std::io::Error> {
     use libc::{
@@ -322,7 +322,7 @@ pub fn dump_registers<W: Write>(
 #[cfg(all(target_os = "openbsd", target_arch = "x86_64"))]
 #[allow(clippy::similar_names)]
 pub fn dump_registers<W: Write>(
-    writer: &mut BufWriter<W>,
+    writer: &mut W,
     ucontext: &ucontext_t,
 ) -> Result<(), std::io::Error> {
     write!(writer, "r8 : {:#016x}, ", ucontext.sc_r8)?;
@@ -353,7 +353,7 @@ pub fn dump_registers<W: Write>(
 ))]
 #[allow(clippy::similar_names)]
 pub fn dump_registers<W: Write>(
-    writer: &mut BufWriter<W>,
+    writer: &mut W,
     ucontext: &ucontext_t,
 ) -> Result<(), std::io::Error> {
     use libc::{
@@ -395,10 +395,7 @@ pub fn dump_registers<W: Write>(
     target_os = "openbsd",
     any(target_os = "solaris", target_os = "illumos"),
 )))]
-fn dump_registers<W: Write>(
-    writer: &mut BufWriter<W>,
-    _ucontext: &ucontext_t,
-) -> Result<(), std::io::Error> {
+fn dump_registers<W: Write>(writer: &mut W, _ucontext: &ucontext_t) -> Result<(), std::io::Error> {
     // TODO: Implement dump registers
     writeln!(
         writer,
@@ -410,7 +407,7 @@ fn dump_registers<W: Write>(
 
 #[cfg(all(target_os = "linux", target_arch = "x86_64"))]
 fn write_crash<W: Write>(
-    writer: &mut BufWriter<W>,
+    writer: &mut W,
     signal: Signal,
     ucontext: &ucontext_t,
 ) -> Result<(), std::io::Error> {
@@ -430,7 +427,7 @@ fn write_crash<W: Write>(
     target_arch = "aarch64"
 ))]
 fn write_crash<W: Write>(
-    writer: &mut BufWriter<W>,
+    writer: &mut W,
     signal: Signal,
     ucontext: &ucontext_t,
 ) -> Result<(), std::io::Error> {
@@ -445,7 +442,7 @@ fn write_crash<W: Write>(
 
 #[cfg(all(target_os = "linux", target_arch = "arm"))]
 fn write_crash<W: Write>(
-    writer: &mut BufWriter<W>,
+    writer: &mut W,
     signal: Signal,
     ucontext: &ucontext_t,
 ) -> Result<(), std::io::Error> {
@@ -460,7 +457,7 @@ fn write_crash<W: Write>(
 
 #[cfg(all(target_os = "freebsd", target_arch = "aarch64"))]
 fn write_crash<W: Write>(
-    writer: &mut BufWriter<W>,
+    writer: &mut W,
     signal: Signal,
     ucontext: &ucontext_t,
 ) -> Result<(), std::io::Error> {
@@ -476,7 +473,7 @@ fn write_crash<W: Write>(
 #[cfg(all(target_vendor = "apple", target_arch = "aarch64"))]
 #[allow(clippy::similar_names)]
 fn write_crash<W: Write>(
-    writer: &mut BufWriter<W>,
+    writer: &mut W,
     signal: Signal,
     ucontext: &ucontext_t,
 ) -> Result<(), std::io::Error> {
@@ -493,7 +490,7 @@ fn write_crash<W: Write>(
 #[cfg(all(target_vendor = "apple", target_arch = "x86_64"))]
 #[allow(clippy::similar_names)]
 fn write_crash<W: Write>(
-    writer: &mut BufWriter<W>,
+    writer: &mut W,
     signal: Signal,
     ucontext: &ucontext_t,
 ) -> Result<(), std::io::Error> {
@@ -515,7 +512,7 @@ fn write_crash<W: Write>(
 #[cfg(target_os = "freebsd")]
 #[allow(clippy::similar_names)]
 fn write_crash<W: Write>(
-    writer: &mut BufWriter<W>,
+    writer: &mut W,
     signal: Signal,
     ucontext: &ucontext_t,
 ) -> Result<(), std::io::Error> {
@@ -531,7 +528,7 @@ fn write_crash<W: Write>(
 #[cfg(target_os = "openbsd")]
 #[allow(clippy::similar_names)]
 fn write_crash<W: Write>(
-    writer: &mut BufWriter<W>,
+    writer: &mut W,
     signal: Signal,
     ucontext: &ucontext_t,
 ) -> Result<(), std::io::Error> {
@@ -546,7 +543,7 @@ fn write_crash<W: Write>(
 
 #[cfg(all(target_os = "netbsd", target_arch = "x86_64"))]
 fn write_crash<W: Write>(
-    writer: &mut BufWriter<W>,
+    writer: &mut W,
     signal: Signal,
     ucontext: &ucontext_t,
 ) -> Result<(), std::io::Error> {
@@ -564,7 +561,7 @@ fn write_crash<W: Write>(
     target_arch = "x86_64"
 ))]
 fn write_crash<W: Write>(
-    writer: &mut BufWriter<W>,
+    writer: &mut W,
     signal: Signal,
     ucontext: &ucontext_t,
 ) -> Result<(), std::io::Error> {
@@ -589,7 +586,7 @@ fn write_crash<W: Write>(
     any(target_os = "solaris", target_os = "illumos"),
 )))]
 fn write_crash<W: Write>(
-    writer: &mut BufWriter<W>,
+    writer: &mut W,
     signal: Signal,
     _ucontext: &ucontext_t,
 ) -> Result<(), std::io::Error> {
@@ -599,30 +596,90 @@ fn write_crash<W: Write>(
     Ok(())
 }
 
-/// Generates a mini-BSOD given a signal and context.
-#[cfg(unix)]
+/// Writes the crash and the registers
 #[allow(clippy::non_ascii_literal)]
-pub fn generate_minibsod<W: Write>(
-    writer: &mut BufWriter<W>,
+fn write_crash_and_registers<W: Write>(
+    writer: &mut W,
     signal: Signal,
-    _siginfo: siginfo_t,
+    siginfo: siginfo_t,
     ucontext: &ucontext_t,
 ) -> Result<(), std::io::Error> {
     writeln!(writer, "{:━^100}", " CRASH ")?;
     write_crash(writer, signal, ucontext)?;
+    // `si_code` tells apart e.g. `SEGV_MAPERR` from `SEGV_ACCERR`, or a kernel signal from a `kill`.
+    writeln!(
+        writer,
+        "si_code: {}, si_errno: {}",
+        siginfo.si_code, siginfo.si_errno
+    )?;
     writeln!(writer, "{:━^100}", " REGISTERS ")?;
-    dump_registers(writer, ucontext)?;
+    dump_registers(writer, ucontext)
+}
+
+/// Copies `/proc/self/maps` using raw `open`/`read` calls, which don't allocate
+#[cfg(any(target_os = "linux", target_os = "android"))]
+#[allow(clippy::non_ascii_literal)]
+fn write_maps<W: Write>(writer: &mut W) -> Result<(), std::io::Error> {
+    writeln!(writer, "{:━^100}", " MAPS ")?;
+
+    let fd = unsafe {
+        libc::open(
+            b"/proc/self/maps\0".as_ptr().cast(),
+            libc::O_RDONLY | libc::O_CLOEXEC,
+        )
+    };
+    if fd < 0 {
+        return writeln!(writer, "Couldn't load mappings");
+    }
+    let mut buf = [0_u8; 1024];
+    let ret = loop {
+        let ret = unsafe { libc::read(fd, buf.as_mut_ptr().cast(), buf.len()) };
+        match usize::try_from(ret) {
+            Ok(0) => break Ok(()),
+            Ok(len) => {
+                if let Err(e) = writer.write_all(&buf[..len]) {
+                    break Err(e);
+                }
+            }
+            Err(_) if nix::errno::errno() == libc::EINTR => {}
+            Err(_) => break writeln!(writer, "Couldn't load all mappings"),
+        }
+    };
+    unsafe { libc::close(fd) };
+    ret
+}
+
+/// Generates a mini-BSOD given a signal and context.
+#[cfg(unix)]
+#[allow(clippy::non_ascii_literal)]
+pub fn generate_minibsod<W: Write>(
+    writer: &mut W,
+    signal: Signal,
+    siginfo: siginfo_t,
+    ucontext: &ucontext_t,
+) -> Result<(), std::io::Error> {
+    write_crash_and_registers(writer, signal, siginfo, ucontext)?;
     writeln!(writer, "{:━^100}", " BACKTRACE ")?;
     writeln!(writer, "{:?}", backtrace::Backtrace::new())?;
     #[cfg(any(target_os = "linux", target_os = "android"))]
-    {
-        writeln!(writer, "{:━^100}", " MAPS ")?;
+    write_maps(writer)?;
 
-        match std::fs::read_to_string("/proc/self/maps") {
-            Ok(maps) => writer.write_all(maps.as_bytes())?,
-            Err(e) => writeln!(writer, "Couldn't load mappings: {e:?}")?,
-        };
-    }
+    Ok(())
+}
+
+/// Generates a mini-BSOD given a signal and context, like [`generate_minibsod`], from inside a signal handler.
+/// Leaves out the backtrace, since collecting it allocates.
+/// Together with a [`crate::bolts::os::unix_signals::SignalSafeWriter`], this neither allocates nor takes locks.
+#[cfg(unix)]
+pub fn generate_minibsod_signal_safe<W: Write>(
+    writer: &mut W,
+    signal: Signal,
+    siginfo: siginfo_t,
+    ucontext: &ucontext_t,
+) -> Result<(), std::io::Error> {
+    write_crash_and_registers(writer, signal, siginfo, ucontext)?;
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    write_maps(writer)?;
 
     Ok(())
 }
@@ -632,7 +689,12 @@ mod tests {
 
     use std::io::{stdout, BufWriter};
 
-    use crate::bolts::{minibsod::dump_registers, os::unix_signals::ucontext};
+    use libc::siginfo_t;
+
+    use crate::bolts::{
+        minibsod::{dump_registers, generate_minibsod, generate_minibsod_signal_safe},
+        os::unix_signals::{ucontext, Signal},
+    };
 
     #[test]
     #[cfg_attr(miri, ignore)]
@@ -641,4 +703,42 @@ mod tests {
         let mut writer = BufWriter::new(stdout());
         dump_registers(&mut writer, &ucontext).unwrap();
     }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    pub fn test_generate_minibsod() {
+        let ucontext = ucontext().unwrap();
+        let mut siginfo: siginfo_t = unsafe { core::mem::zeroed() };
+        siginfo.si_code = 1;
+
+        let mut bsod = Vec::new();
+        {
+            let mut writer = BufWriter::new(&mut bsod);
+            generate_minibsod(
+                &mut writer,
+                Signal::SigSegmentationFault,
+                siginfo,
+                &ucontext,
+            )
+            .unwrap();
+        }
+        let bsod = String::from_utf8(bsod).unwrap();
+        assert!(bsod.contains("si_code: 1, si_errno: 0"));
+    }
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    pub fn test_generate_minibsod_signal_safe() {
+        let ucontext = ucontext().unwrap();
+        let mut siginfo: siginfo_t = unsafe { core::mem::zeroed() };
+        siginfo.si_code = 1;
+
+        let mut bsod = Vec::new();
+        generate_minibsod_signal_safe(&mut bsod, Signal::SigSegmentationFault, siginfo, &ucontext)
+            .unwrap();
+        let bsod = String::from_utf8(bsod).unwrap();
+        assert!(bsod.contains("si_code: 1, si_errno: 0"));
+        assert!(!bsod.contains("BACKTRACE"));
+        #[cfg(target_os = "linux")]
+        assert!(bsod.contains("[stack]"));
+    }
 }
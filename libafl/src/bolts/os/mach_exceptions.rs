@@ -0,0 +1,311 @@
+//! Catch crashes on `MacOS` using a Mach exception port.
+//!
+//! Mach exceptions are raised before, and independent of, the `BSD` signals the kernel builds from them.
+//! Some faults, like guard page hits or `EXC_BAD_ACCESS` under the hardened runtime, don't reliably
+//! reach the crashing thread as a signal, but always arrive at the exception port.
+//! Each exception is turned into the signal it would have raised, and handed to the handler registered
+//! for it with [`crate::bolts::os::unix_signals::setup_signal_handler`], together with the faulting
+//! address and the state of the crashing thread. This way, the in-process executors report them
+//! as [`crate::executors::ExitKind::Crash`], like any other crash.
+//! The crash recovery of the in-process executors is not used for exceptions, since it would
+//! jump back onto the stack of the crashing thread from the exception thread.
+
+use core::{
+    fmt::Write,
+    mem,
+    ptr::addr_of_mut,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+use std::thread;
+
+use libc::{c_int, c_void, siginfo_t, SIGBUS, SIGFPE, SIGILL, SIGSEGV};
+
+#[cfg(target_arch = "aarch64")]
+use crate::bolts::os::unix_signals::mcontext64;
+use crate::{
+    bolts::os::unix_signals::{handle_signal, ucontext_t, SignalSafeWriter},
+    Error,
+};
+
+#[allow(non_camel_case_types)]
+type mach_port_t = u32;
+#[allow(non_camel_case_types)]
+type kern_return_t = c_int;
+
+/// The machine context filled from the thread state of the crashing thread
+#[cfg(target_arch = "aarch64")]
+type MContext = mcontext64;
+#[cfg(target_arch = "x86_64")]
+type MContext = libc::__darwin_mcontext64;
+
+/// The `pthread_t` of the thread waiting for exceptions, `0` until it runs
+static EXCEPTION_THREAD: AtomicUsize = AtomicUsize::new(0);
+
+const KERN_SUCCESS: kern_return_t = 0;
+/// Replying this makes the kernel deliver the exception as a signal, as if we never saw it
+const KERN_FAILURE: kern_return_t = 5;
+/// The `EXC_BAD_ACCESS` code for unmapped memory, other bad accesses become `SIGBUS`
+const KERN_INVALID_ADDRESS: i64 = 1;
+
+const MACH_PORT_NULL: mach_port_t = 0;
+const MACH_PORT_RIGHT_RECEIVE: u32 = 1;
+const MACH_MSG_TYPE_MAKE_SEND: u32 = 20;
+const MACH_MSGH_BITS_REMOTE_MASK: u32 = 0x1f;
+const MACH_SEND_MSG: c_int = 1;
+const MACH_RCV_MSG: c_int = 2;
+const MACH_MSG_TIMEOUT_NONE: u32 = 0;
+
+const EXC_BAD_ACCESS: c_int = 1;
+const EXC_BAD_INSTRUCTION: c_int = 2;
+const EXC_ARITHMETIC: c_int = 3;
+/// The exceptions we catch
+const EXC_MASK_CRASHES: u32 =
+    (1 << EXC_BAD_ACCESS) | (1 << EXC_BAD_INSTRUCTION) | (1 << EXC_ARITHMETIC);
+const EXCEPTION_DEFAULT: c_int = 1;
+/// `0x80000000`, to receive 64 bit exception codes
+const MACH_EXCEPTION_CODES: c_int = c_int::MIN;
+const THREAD_STATE_NONE: c_int = 13;
+
+/// `x86_THREAD_STATE64`
+#[cfg(target_arch = "x86_64")]
+const THREAD_STATE_FLAVOR: c_int = 4;
+/// `ARM_THREAD_STATE64`
+#[cfg(target_arch = "aarch64")]
+const THREAD_STATE_FLAVOR: c_int = 6;
+
+// The message layouts are defined by the kernel, not all of their fields are used
+/// `mach_msg_header_t`
+#[derive(Debug, Clone, Copy)]
+#[allow(dead_code)]
+#[repr(C)]
+struct MachMsgHeader {
+    bits: u32,
+    size: u32,
+    remote_port: mach_port_t,
+    local_port: mach_port_t,
+    voucher_port: mach_port_t,
+    id: i32,
+}
+
+/// `mach_msg_port_descriptor_t`
+#[derive(Debug, Clone, Copy)]
+#[allow(dead_code)]
+#[repr(C)]
+struct MachMsgPortDescriptor {
+    name: mach_port_t,
+    pad1: u32,
+    pad2: u16,
+    disposition: u8,
+    descriptor_type: u8,
+}
+
+/// `__Request__mach_exception_raise_t`, as received by the exception thread
+#[allow(dead_code)]
+#[repr(C, packed(4))]
+struct ExceptionRequest {
+    header: MachMsgHeader,
+    descriptor_count: u32,
+    thread: MachMsgPortDescriptor,
+    task: MachMsgPortDescriptor,
+    ndr: [u8; 8],
+    exception: c_int,
+    code_count: u32,
+    code: [i64; 2],
+    /// Room for the trailer the kernel appends
+    trailer: [u8; 64],
+}
+
+/// `__Reply__mach_exception_raise_t`
+#[allow(dead_code)]
+#[repr(C, packed(4))]
+struct ExceptionReply {
+    header: MachMsgHeader,
+    ndr: [u8; 8],
+    ret_code: kern_return_t,
+}
+
+extern "C" {
+    static mach_task_self_: mach_port_t;
+
+    fn mach_port_allocate(task: mach_port_t, right: u32, name: *mut mach_port_t) -> kern_return_t;
+    fn mach_port_insert_right(
+        task: mach_port_t,
+        name: mach_port_t,
+        right: mach_port_t,
+        right_type: u32,
+    ) -> kern_return_t;
+    fn task_set_exception_ports(
+        task: mach_port_t,
+        exception_mask: u32,
+        new_port: mach_port_t,
+        behavior: c_int,
+        new_flavor: c_int,
+    ) -> kern_return_t;
+    fn mach_msg(
+        msg: *mut MachMsgHeader,
+        option: c_int,
+        send_size: u32,
+        rcv_size: u32,
+        rcv_name: mach_port_t,
+        timeout: u32,
+        notify: mach_port_t,
+    ) -> kern_return_t;
+    fn thread_get_state(
+        thread: mach_port_t,
+        flavor: c_int,
+        state: *mut u32,
+        count: *mut u32,
+    ) -> kern_return_t;
+}
+
+/// Catch crashes using a Mach exception port for this task, in addition to the signal handlers.
+///
+/// A thread waits for `EXC_BAD_ACCESS`, `EXC_BAD_INSTRUCTION` and `EXC_ARITHMETIC` exceptions,
+/// and calls the signal handlers registered for `SIGSEGV`/`SIGBUS`, `SIGILL` and `SIGFPE`.
+/// If a handler returns instead of exiting, the exception is delivered as a signal, as usual.
+/// Call this after setting up the executor, so its crash handlers are in place.
+///
+/// # Safety
+///
+/// This replaces the exception ports of the task, e.g. those of a debugger.
+/// The signal handlers are called from the exception thread while the crashing thread is suspended,
+/// they must not rely on running on the crashing thread.
+/// In particular, they must not jump back into the crashing thread, as the crash recovery of the
+/// in-process executors would, which is why it is skipped, see [`on_exception_thread`].
+pub unsafe fn setup_mach_exception_handler() -> Result<(), Error> {
+    let task = mach_task_self_;
+    let mut port = MACH_PORT_NULL;
+    if mach_port_allocate(task, MACH_PORT_RIGHT_RECEIVE, &mut port) != KERN_SUCCESS
+        || mach_port_insert_right(task, port, port, MACH_MSG_TYPE_MAKE_SEND) != KERN_SUCCESS
+    {
+        return Err(Error::unknown("Could not allocate the Mach exception port"));
+    }
+
+    thread::Builder::new()
+        .name("mach_exceptions".into())
+        .spawn(move || {
+            EXCEPTION_THREAD.store(unsafe { libc::pthread_self() } as usize, Ordering::SeqCst);
+            loop {
+                unsafe { handle_exception(port) };
+            }
+        })?;
+
+    if task_set_exception_ports(
+        task,
+        EXC_MASK_CRASHES,
+        port,
+        EXCEPTION_DEFAULT | MACH_EXCEPTION_CODES,
+        THREAD_STATE_NONE,
+    ) != KERN_SUCCESS
+    {
+        return Err(Error::unknown("Could not set the Mach exception port"));
+    }
+    Ok(())
+}
+
+/// Whether the current thread is the one handling Mach exceptions, so signal handlers can tell
+/// if they run for an exception, on a thread other than the crashing one
+#[must_use]
+pub fn on_exception_thread() -> bool {
+    let thread = EXCEPTION_THREAD.load(Ordering::SeqCst);
+    thread != 0 && thread == unsafe { libc::pthread_self() } as usize
+}
+
+/// Waits for the next exception on `port`, and hands it to the signal handlers
+#[allow(clippy::cast_sign_loss)]
+unsafe fn handle_exception(port: mach_port_t) {
+    let mut request: ExceptionRequest = mem::zeroed();
+    if mach_msg(
+        addr_of_mut!(request.header),
+        MACH_RCV_MSG,
+        0,
+        mem::size_of::<ExceptionRequest>() as u32,
+        port,
+        MACH_MSG_TIMEOUT_NONE,
+        MACH_PORT_NULL,
+    ) != KERN_SUCCESS
+    {
+        return;
+    }
+
+    let header = request.header;
+    let code = request.code;
+    let signal = match request.exception {
+        EXC_BAD_ACCESS if code[0] == KERN_INVALID_ADDRESS => SIGSEGV,
+        EXC_BAD_ACCESS => SIGBUS,
+        EXC_BAD_INSTRUCTION => SIGILL,
+        _ => SIGFPE,
+    };
+
+    // Rebuild what the kernel would have passed to the signal handler
+    let mut info: siginfo_t = mem::zeroed();
+    info.si_signo = signal;
+    info.si_addr = code[1] as usize as *mut c_void;
+
+    let mut mcontext: MContext = mem::zeroed();
+    let mut count = (mem::size_of_val(&mcontext.__ss) / mem::size_of::<u32>()) as u32;
+    let ret = thread_get_state(
+        request.thread.name,
+        THREAD_STATE_FLAVOR,
+        addr_of_mut!(mcontext.__ss).cast(),
+        &mut count,
+    );
+    if ret != KERN_SUCCESS {
+        // The handlers still get the signal and the faulting address, only the registers are zeroed
+        let mut writer = SignalSafeWriter::stderr();
+        let _ = writeln!(
+            writer,
+            "LibAFL: Could not get the state of the crashing thread: {ret}"
+        );
+    }
+    #[cfg(target_arch = "aarch64")]
+    {
+        mcontext.__es.__far = code[1] as u64;
+    }
+    #[cfg(target_arch = "x86_64")]
+    {
+        mcontext.__es.__faultvaddr = code[1] as u64;
+    }
+
+    let mut context: ucontext_t = mem::zeroed();
+    context.uc_mcsize = mem::size_of::<MContext>() as _;
+    context.uc_mcontext = addr_of_mut!(mcontext);
+
+    // The crash handlers of the executors exit, we only get back here otherwise
+    handle_signal(signal, info, addr_of_mut!(context).cast());
+
+    let mut reply = ExceptionReply {
+        header: MachMsgHeader {
+            bits: header.bits & MACH_MSGH_BITS_REMOTE_MASK,
+            size: mem::size_of::<ExceptionReply>() as u32,
+            remote_port: header.remote_port,
+            local_port: MACH_PORT_NULL,
+            voucher_port: MACH_PORT_NULL,
+            id: header.id + 100,
+        },
+        ndr: request.ndr,
+        ret_code: KERN_FAILURE,
+    };
+    mach_msg(
+        addr_of_mut!(reply.header),
+        MACH_SEND_MSG,
+        mem::size_of::<ExceptionReply>() as u32,
+        0,
+        MACH_PORT_NULL,
+        MACH_MSG_TIMEOUT_NONE,
+        MACH_PORT_NULL,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::bolts::os::mach_exceptions::{on_exception_thread, setup_mach_exception_handler};
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn test_setup_mach_exception_handler() {
+        unsafe { setup_mach_exception_handler().unwrap() };
+        // Only the spawned exception thread handles exceptions
+        assert!(!on_exception_thread());
+    }
+}
@@ -13,6 +13,9 @@ pub mod unix_shmem_server;
 #[cfg(unix)]
 pub mod unix_signals;
 
+#[cfg(all(target_vendor = "apple", feature = "std"))]
+pub mod mach_exceptions;
+
 #[cfg(all(unix, feature = "std"))]
 pub mod pipes;
 
@@ -95,7 +98,7 @@ pub fn startable_self() -> Result<Command, Error> {
 #[cfg(all(unix, feature = "std"))]
 pub fn dup2(fd: i32, device: i32) -> Result<(), Error> {
     match unsafe { libc::dup2(fd, device) } {
-        -1 => Err(Error::file(std::io::Error::last_os_error())),
+        -1 => Err(Error::last_os_error(format!("dup2({fd}, {device}) failed"))),
         _ => Ok(()),
     }
 }
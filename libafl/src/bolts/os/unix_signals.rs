@@ -3,9 +3,10 @@ use alloc::vec::Vec;
 use core::{
     cell::UnsafeCell,
     fmt::{self, Display, Formatter},
-    mem, ptr,
+    mem::{self, transmute},
+    ptr,
     ptr::{addr_of_mut, write_volatile},
-    sync::atomic::{compiler_fence, Ordering},
+    sync::atomic::{compiler_fence, AtomicBool, Ordering},
 };
 #[cfg(feature = "std")]
 use std::ffi::CString;
@@ -241,9 +242,9 @@ use libc::ssize_t;
 )))]
 pub use libc::ucontext_t;
 use libc::{
-    c_int, malloc, sigaction, sigaddset, sigaltstack, sigemptyset, stack_t, SA_NODEFER, SA_ONSTACK,
-    SA_SIGINFO, SIGABRT, SIGALRM, SIGBUS, SIGFPE, SIGHUP, SIGILL, SIGINT, SIGKILL, SIGPIPE,
-    SIGQUIT, SIGSEGV, SIGTERM, SIGTRAP, SIGUSR2,
+    c_int, free, malloc, sigaction, sigaddset, sigaltstack, sigemptyset, stack_t, SA_NODEFER,
+    SA_ONSTACK, SA_SIGINFO, SIGABRT, SIGALRM, SIGBUS, SIGFPE, SIGHUP, SIGILL, SIGINT, SIGKILL,
+    SIGPIPE, SIGQUIT, SIGSEGV, SIGTERM, SIGTRAP, SIGUSR2, SIG_DFL, SIG_IGN, SS_DISABLE,
 };
 pub use libc::{c_void, siginfo_t};
 use num_enum::{IntoPrimitive, TryFromPrimitive};
@@ -334,6 +335,108 @@ impl Display for Signal {
     }
 }
 
+/// The size of the buffer of a [`SignalSafeWriter`]
+pub const SIGNAL_SAFE_WRITER_BUF_SIZE: usize = 4096;
+
+/// A writer for signal handlers, which must neither allocate nor take locks.
+/// Formats into a fixed buffer and writes it to a file descriptor using raw `write(2)` calls,
+/// whenever the buffer is full, on [`SignalSafeWriter::flush_buf`], and on drop.
+/// Errors are ignored: there is nothing a signal handler could do about them.
+pub struct SignalSafeWriter {
+    fd: c_int,
+    buf: [u8; SIGNAL_SAFE_WRITER_BUF_SIZE],
+    len: usize,
+}
+
+impl fmt::Debug for SignalSafeWriter {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SignalSafeWriter")
+            .field("fd", &self.fd)
+            .field("len", &self.len)
+            .finish_non_exhaustive()
+    }
+}
+
+impl SignalSafeWriter {
+    /// Creates a new [`SignalSafeWriter`], writing to `fd`
+    #[must_use]
+    pub const fn new(fd: c_int) -> Self {
+        Self {
+            fd,
+            buf: [0; SIGNAL_SAFE_WRITER_BUF_SIZE],
+            len: 0,
+        }
+    }
+
+    /// Creates a new [`SignalSafeWriter`], writing to `stderr`
+    #[must_use]
+    pub const fn stderr() -> Self {
+        Self::new(libc::STDERR_FILENO)
+    }
+
+    /// Appends `bytes`, writing out the buffer as it fills up
+    pub fn write_bytes(&mut self, mut bytes: &[u8]) {
+        while !bytes.is_empty() {
+            if self.len == self.buf.len() {
+                self.flush_buf();
+            }
+            let len = bytes.len().min(self.buf.len() - self.len);
+            self.buf[self.len..self.len + len].copy_from_slice(&bytes[..len]);
+            self.len += len;
+            bytes = &bytes[len..];
+        }
+    }
+
+    /// Writes out the buffered bytes
+    pub fn flush_buf(&mut self) {
+        let mut written = 0;
+        while written < self.len {
+            let ret = unsafe {
+                libc::write(
+                    self.fd,
+                    self.buf[written..self.len].as_ptr().cast(),
+                    self.len - written,
+                )
+            };
+            match usize::try_from(ret) {
+                Ok(0) => break,
+                Ok(len) => written += len,
+                // Retry if interrupted by another signal
+                #[cfg(feature = "std")]
+                Err(_) if errno() == libc::EINTR => continue,
+                Err(_) => break,
+            }
+        }
+        self.len = 0;
+    }
+}
+
+impl fmt::Write for SignalSafeWriter {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.write_bytes(s.as_bytes());
+        Ok(())
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::io::Write for SignalSafeWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.write_bytes(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.flush_buf();
+        Ok(())
+    }
+}
+
+impl Drop for SignalSafeWriter {
+    fn drop(&mut self) {
+        self.flush_buf();
+    }
+}
+
 /// A trait for `LibAFL` signal handling
 pub trait Handler {
     /// Handle a signal
@@ -350,8 +453,6 @@ unsafe impl Send for HandlerHolder {}
 
 /// Let's get 8 mb for now.
 const SIGNAL_STACK_SIZE: usize = 2 << 22;
-/// To be able to handle SIGSEGV when the stack is exhausted, we need our own little stack space.
-static mut SIGNAL_STACK_PTR: *mut c_void = ptr::null_mut();
 
 /// Keep track of which handler is registered for which signal
 static mut SIGNAL_HANDLERS: [Option<HandlerHolder>; 32] = [
@@ -361,12 +462,57 @@ static mut SIGNAL_HANDLERS: [Option<HandlerHolder>; 32] = [
     None, None, None, None, None, None, None, None, None, None, None, None, None, None, None, None,
 ];
 
+/// The signal handlers that were installed before ours, e.g. by `ASan` or the host application
+static mut PREVIOUS_HANDLERS: [Option<sigaction>; 32] = [None; 32];
+
+/// If the previous signal handlers should be called after ours, see [`set_chain_previous_handlers`]
+static CHAIN_PREVIOUS_HANDLERS: AtomicBool = AtomicBool::new(false);
+
+/// Call the signal handlers that were installed before [`setup_signal_handler()`], e.g. by `ASan`
+/// or the host application, after `LibAFL` handled a signal.
+/// Handlers that exit the process, like the crash handler of the in-process executors,
+/// call them right before exiting.
+pub fn set_chain_previous_handlers(chain: bool) {
+    CHAIN_PREVIOUS_HANDLERS.store(chain, Ordering::Relaxed);
+}
+
+/// Call the handler that was installed for `signal` before ours, if chaining is enabled using
+/// [`set_chain_previous_handlers`].
+/// Default and ignored dispositions are not invoked.
+///
+/// # Safety
+///
+/// This runs foreign signal handling code, and should only be called while handling `signal`.
+pub unsafe fn call_previous_handler(signal: Signal, info: &mut siginfo_t, context: *mut c_void) {
+    if !CHAIN_PREVIOUS_HANDLERS.load(Ordering::Relaxed) {
+        return;
+    }
+    let Some(previous) = PREVIOUS_HANDLERS[signal as usize] else {
+        return;
+    };
+    if previous.sa_sigaction == SIG_DFL || previous.sa_sigaction == SIG_IGN {
+        return;
+    }
+    if previous.sa_flags & SA_SIGINFO == 0 {
+        let handler: extern "C" fn(c_int) = transmute(previous.sa_sigaction);
+        handler(signal as c_int);
+    } else {
+        let handler: extern "C" fn(c_int, *mut siginfo_t, *mut c_void) =
+            transmute(previous.sa_sigaction);
+        handler(signal as c_int, info, context);
+    }
+}
+
 /// Internal function that is being called whenever a signal we are registered for arrives.
 /// # Safety
 /// This should be somewhat safe to call for signals previously registered,
 /// unless the signal handlers registered using [`setup_signal_handler()`] are broken.
-unsafe fn handle_signal(sig: c_int, info: siginfo_t, void: *mut c_void) {
-    let signal = &Signal::try_from(sig).unwrap();
+pub(crate) unsafe fn handle_signal(sig: c_int, mut info: siginfo_t, void: *mut c_void) {
+    // Never panic in here, unwinding out of a signal handler is undefined behavior.
+    let signal = &match Signal::try_from(sig) {
+        Ok(signal) => signal,
+        Err(_) => return,
+    };
     let handler = {
         match &SIGNAL_HANDLERS[*signal as usize] {
             Some(handler_holder) => &mut **handler_holder.handler.get(),
@@ -374,6 +520,42 @@ unsafe fn handle_signal(sig: c_int, info: siginfo_t, void: *mut c_void) {
         }
     };
     handler.handle(*signal, info, &mut *(void as *mut ucontext_t));
+    call_previous_handler(*signal, &mut info, void);
+}
+
+/// Make the current thread handle signals on its own alternate stack, unless it already has a large enough one.
+/// Without it, a stack overflow, i.e. a `SIGSEGV` on the guard page, can't be handled,
+/// since the handler would run on the exhausted stack.
+/// Signal stacks are per thread: [`setup_signal_handler()`] sets one up for the calling thread,
+/// call this from every other thread that may crash, e.g. threads spawned by the target.
+///
+/// # Safety
+///
+/// Replaces the previous, smaller, signal stack of this thread, which is leaked.
+pub unsafe fn setup_signal_stack() -> Result<(), Error> {
+    let mut current: stack_t = mem::zeroed();
+    if sigaltstack(ptr::null(), addr_of_mut!(current)) == 0
+        && current.ss_flags & SS_DISABLE == 0
+        && current.ss_size >= SIGNAL_STACK_SIZE
+    {
+        return Ok(());
+    }
+
+    let stack_ptr = malloc(SIGNAL_STACK_SIZE);
+    // Rust always panics on OOM, so we will, too.
+    assert!(
+        !stack_ptr.is_null(),
+        "Failed to allocate signal stack with {SIGNAL_STACK_SIZE} bytes!"
+    );
+
+    let mut ss: stack_t = mem::zeroed();
+    ss.ss_size = SIGNAL_STACK_SIZE;
+    ss.ss_sp = stack_ptr;
+    if sigaltstack(addr_of_mut!(ss), ptr::null_mut()) != 0 {
+        free(stack_ptr);
+        return Err(Error::unknown("Could not set up the signal stack"));
+    }
+    Ok(())
 }
 
 /// Setup signal handlers in a somewhat rusty way.
@@ -386,28 +568,31 @@ unsafe fn handle_signal(sig: c_int, info: siginfo_t, void: *mut c_void) {
 /// The signal handlers will be called on any signal. They should (tm) be async safe.
 /// A lot can go south in signal handling. Be sure you know what you are doing.
 pub unsafe fn setup_signal_handler<T: 'static + Handler>(handler: &mut T) -> Result<(), Error> {
-    // First, set up our own stack to be used during segfault handling. (and specify `SA_ONSTACK` in `sigaction`)
-    if SIGNAL_STACK_PTR.is_null() {
-        SIGNAL_STACK_PTR = malloc(SIGNAL_STACK_SIZE);
+    let signals = handler.signals();
+    setup_signal_handler_for(handler, &signals)
+}
 
-        // Rust always panics on OOM, so we will, too.
-        assert!(
-            !SIGNAL_STACK_PTR.is_null(),
-            "Failed to allocate signal stack with {SIGNAL_STACK_SIZE} bytes!"
-        );
-    }
-    let mut ss: stack_t = mem::zeroed();
-    ss.ss_size = SIGNAL_STACK_SIZE;
-    ss.ss_sp = SIGNAL_STACK_PTR;
-    sigaltstack(addr_of_mut!(ss), ptr::null_mut() as _);
+/// Setup signal handlers like [`setup_signal_handler()`], but only for the given `signals`,
+/// instead of the [`Handler::signals`] of the `handler`.
+/// The handlers of all other signals are left as they are.
+///
+/// # Safety
+///
+/// The signal handlers will be called on any signal. They should (tm) be async safe.
+/// A lot can go south in signal handling. Be sure you know what you are doing.
+pub unsafe fn setup_signal_handler_for<T: 'static + Handler>(
+    handler: &mut T,
+    signals: &[Signal],
+) -> Result<(), Error> {
+    // First, set up our own stack to be used during segfault handling. (and specify `SA_ONSTACK` in `sigaction`)
+    setup_signal_stack()?;
 
     let mut sa: sigaction = mem::zeroed();
     sigemptyset(addr_of_mut!(sa.sa_mask));
     sigaddset(addr_of_mut!(sa.sa_mask), SIGALRM);
     sa.sa_flags = SA_NODEFER | SA_SIGINFO | SA_ONSTACK;
     sa.sa_sigaction = handle_signal as usize;
-    let signals = handler.signals();
-    for sig in signals {
+    for &sig in signals {
         write_volatile(
             &mut SIGNAL_HANDLERS[sig as usize],
             Some(HandlerHolder {
@@ -415,7 +600,8 @@ pub unsafe fn setup_signal_handler<T: 'static + Handler>(handler: &mut T) -> Res
             }),
         );
 
-        if sigaction(sig as i32, addr_of_mut!(sa), ptr::null_mut()) < 0 {
+        let mut previous: sigaction = mem::zeroed();
+        if sigaction(sig as i32, addr_of_mut!(sa), addr_of_mut!(previous)) < 0 {
             #[cfg(feature = "std")]
             {
                 let err_str = CString::new(format!("Failed to setup {sig} handler")).unwrap();
@@ -423,6 +609,10 @@ pub unsafe fn setup_signal_handler<T: 'static + Handler>(handler: &mut T) -> Res
             }
             return Err(Error::unknown(format!("Could not set up {sig} handler")));
         }
+        // Don't chain to ourselves if the handlers are set up more than once
+        if previous.sa_sigaction != handle_signal as usize {
+            write_volatile(&mut PREVIOUS_HANDLERS[sig as usize], Some(previous));
+        }
     }
     compiler_fence(Ordering::SeqCst);
 
@@ -461,3 +651,197 @@ pub fn ucontext() -> Result<ucontext_t, Error> {
         Ok(ucontext)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use alloc::{boxed::Box, vec::Vec};
+    use core::{
+        fmt::Write,
+        mem, ptr,
+        sync::atomic::{AtomicUsize, Ordering},
+    };
+
+    use libc::{
+        c_int, c_void, sigaction, sigaltstack, sigemptyset, siginfo_t, stack_t, SA_SIGINFO, SIGHUP,
+        SIGQUIT, SIG_IGN, SS_DISABLE,
+    };
+    #[cfg(feature = "std")]
+    use serial_test::serial;
+
+    use crate::bolts::os::unix_signals::{
+        handle_signal, set_chain_previous_handlers, setup_signal_handler_for, setup_signal_stack,
+        ucontext_t, Handler, Signal, SignalSafeWriter, SIGNAL_SAFE_WRITER_BUF_SIZE,
+        SIGNAL_STACK_SIZE,
+    };
+
+    /// The handler currently installed for `sig`
+    fn installed_handler(sig: libc::c_int) -> usize {
+        unsafe {
+            let mut current: sigaction = mem::zeroed();
+            sigaction(sig, ptr::null(), &mut current);
+            current.sa_sigaction
+        }
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn test_signal_safe_writer() {
+        let mut fds = [0; 2];
+        assert_eq!(unsafe { libc::pipe(fds.as_mut_ptr()) }, 0);
+
+        let long = [b'a'; SIGNAL_SAFE_WRITER_BUF_SIZE + 10];
+        {
+            let mut writer = SignalSafeWriter::new(fds[1]);
+            write!(writer, "signal {}, ", 11).unwrap();
+            writer.write_bytes(&long);
+            // Written out on drop
+        }
+        unsafe { libc::close(fds[1]) };
+
+        let mut out = Vec::new();
+        let mut buf = [0_u8; 1024];
+        loop {
+            let ret = unsafe { libc::read(fds[0], buf.as_mut_ptr().cast(), buf.len()) };
+            let Ok(len) = usize::try_from(ret) else {
+                panic!("read failed")
+            };
+            if len == 0 {
+                break;
+            }
+            out.extend_from_slice(&buf[..len]);
+        }
+        unsafe { libc::close(fds[0]) };
+
+        assert!(out.starts_with(b"signal 11, aaa"));
+        assert_eq!(out.len(), "signal 11, ".len() + long.len());
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    #[serial]
+    #[cfg_attr(miri, ignore)]
+    fn test_setup_signal_handler_for() {
+        static HANDLED: AtomicUsize = AtomicUsize::new(0);
+
+        struct SubsetHandler;
+        impl Handler for SubsetHandler {
+            fn handle(&mut self, _signal: Signal, _info: siginfo_t, _context: &mut ucontext_t) {
+                HANDLED.fetch_add(1, Ordering::Relaxed);
+            }
+
+            fn signals(&self) -> Vec<Signal> {
+                vec![Signal::SigHangUp, Signal::SigQuit]
+            }
+        }
+
+        unsafe {
+            setup_signal_handler_for(Box::leak(Box::new(SubsetHandler)), &[Signal::SigHangUp])
+                .unwrap();
+        }
+        // Only the given signal is handled, all others keep their handlers
+        assert_eq!(installed_handler(SIGHUP), handle_signal as usize);
+        assert_ne!(installed_handler(SIGQUIT), handle_signal as usize);
+
+        unsafe { libc::raise(SIGHUP) };
+        assert_eq!(HANDLED.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    #[serial]
+    #[cfg_attr(miri, ignore)]
+    fn test_chain_previous_handlers() {
+        static HANDLED: AtomicUsize = AtomicUsize::new(0);
+        static PREVIOUS_SIGINFO: AtomicUsize = AtomicUsize::new(0);
+        static PREVIOUS_PLAIN: AtomicUsize = AtomicUsize::new(0);
+
+        struct ChainedHandler;
+        impl Handler for ChainedHandler {
+            fn handle(&mut self, _signal: Signal, _info: siginfo_t, _context: &mut ucontext_t) {
+                HANDLED.fetch_add(1, Ordering::Relaxed);
+            }
+
+            fn signals(&self) -> Vec<Signal> {
+                vec![Signal::SigHangUp]
+            }
+        }
+
+        extern "C" fn previous_siginfo(_sig: c_int, _info: *mut siginfo_t, _context: *mut c_void) {
+            PREVIOUS_SIGINFO.fetch_add(1, Ordering::Relaxed);
+        }
+        extern "C" fn previous_plain(_sig: c_int) {
+            PREVIOUS_PLAIN.fetch_add(1, Ordering::Relaxed);
+        }
+
+        // Installs `previous` for `SIGHUP`, then ours on top, and raises it
+        let raise_with_previous = |previous: usize, flags: c_int| unsafe {
+            let mut sa: sigaction = mem::zeroed();
+            sigemptyset(&mut sa.sa_mask);
+            sa.sa_flags = flags;
+            sa.sa_sigaction = previous;
+            sigaction(SIGHUP, &sa, ptr::null_mut());
+            setup_signal_handler_for(Box::leak(Box::new(ChainedHandler)), &[Signal::SigHangUp])
+                .unwrap();
+            libc::raise(SIGHUP);
+            (
+                HANDLED.load(Ordering::Relaxed),
+                PREVIOUS_SIGINFO.load(Ordering::Relaxed),
+                PREVIOUS_PLAIN.load(Ordering::Relaxed),
+            )
+        };
+
+        set_chain_previous_handlers(true);
+        assert_eq!(
+            raise_with_previous(previous_siginfo as usize, SA_SIGINFO),
+            (1, 1, 0)
+        );
+        assert_eq!(raise_with_previous(previous_plain as usize, 0), (2, 1, 1));
+        // Ignored signals are not chained
+        assert_eq!(raise_with_previous(SIG_IGN, 0), (3, 1, 1));
+
+        // Setting up our handler again doesn't chain it to itself
+        unsafe {
+            setup_signal_handler_for(Box::leak(Box::new(ChainedHandler)), &[Signal::SigHangUp])
+                .unwrap();
+            libc::raise(SIGHUP);
+        }
+        assert_eq!(HANDLED.load(Ordering::Relaxed), 4);
+
+        set_chain_previous_handlers(false);
+        assert_eq!(raise_with_previous(previous_plain as usize, 0), (5, 1, 1));
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    #[cfg_attr(miri, ignore)]
+    fn test_setup_signal_stack_per_thread() {
+        fn current_stack() -> stack_t {
+            unsafe {
+                let mut current: stack_t = mem::zeroed();
+                assert_eq!(sigaltstack(ptr::null(), &mut current), 0);
+                current
+            }
+        }
+
+        unsafe { setup_signal_stack().unwrap() };
+        let main_stack = current_stack().ss_sp as usize;
+
+        std::thread::spawn(move || {
+            // New threads start without a signal stack, or with a small one set up by Rust
+            let initial = current_stack();
+            assert!(initial.ss_flags & SS_DISABLE != 0 || initial.ss_size < SIGNAL_STACK_SIZE);
+
+            unsafe { setup_signal_stack().unwrap() };
+            let stack = current_stack();
+            assert_eq!(stack.ss_flags & SS_DISABLE, 0);
+            assert!(stack.ss_size >= SIGNAL_STACK_SIZE);
+            assert_ne!(stack.ss_sp as usize, main_stack);
+
+            // A large enough stack is kept
+            unsafe { setup_signal_stack().unwrap() };
+            assert_eq!(current_stack().ss_sp, stack.ss_sp);
+        })
+        .join()
+        .unwrap();
+    }
+}
@@ -6,7 +6,7 @@ use core::{
     fmt::{self, Display, Formatter},
     ptr,
     ptr::write_volatile,
-    sync::atomic::{compiler_fence, Ordering},
+    sync::atomic::{compiler_fence, AtomicBool, Ordering},
 };
 use std::os::raw::{c_long, c_void};
 
@@ -309,6 +309,9 @@ static mut EXCEPTION_HANDLERS: [Option<HandlerHolder>; EXCEPTION_HANDLERS_SIZE]
     None, None, None, None, None, None, None, None, None, None, None, None, None, None, None, None,
 ];
 
+/// Whether our vectored exception handler has already been registered with the OS
+static VEH_INSTALLED: AtomicBool = AtomicBool::new(false);
+
 unsafe fn internal_handle_exception(
     exception_code: ExceptionCode,
     exception_pointers: *mut EXCEPTION_POINTERS,
@@ -397,9 +400,13 @@ pub unsafe fn setup_exception_handler<T: 'static + Handler>(handler: &mut T) ->
     }
     // SetUnhandledFilter does not work with frida since the stack is changed and exception handler is lost with Stalker enabled.
     // See https://github.com/AFLplusplus/LibAFL/pull/403
-    AddVectoredExceptionHandler(
-        0,
-        Some(core::mem::transmute(handle_exception as *const c_void)),
-    );
+    // The handler table above is global, so registering the vectored handler once is enough,
+    // even if multiple executors call this function.
+    if !VEH_INSTALLED.swap(true, Ordering::SeqCst) {
+        AddVectoredExceptionHandler(
+            0,
+            Some(core::mem::transmute(handle_exception as *const c_void)),
+        );
+    }
     Ok(())
 }
@@ -221,7 +221,7 @@ impl Rand for XorShift64Rand {
 }
 
 impl XorShift64Rand {
-    /// Creates a new Xoshiro rand with the given seed
+    /// Creates a new XorShift64 rand with the given seed
     #[must_use]
     pub fn with_seed(seed: u64) -> Self {
         let mut ret: Self = Self { rand_seed: 0 };
@@ -245,7 +245,7 @@ impl Rand for Lehmer64Rand {
     #[inline]
     #[allow(clippy::unreadable_literal)]
     fn next(&mut self) -> u64 {
-        self.rand_seed *= 0xda942042e4dd58b5;
+        self.rand_seed = self.rand_seed.wrapping_mul(0xda942042e4dd58b5);
         (self.rand_seed >> 64) as u64
     }
 }
@@ -380,7 +380,8 @@ mod tests {
     //use xxhash_rust::xxh3::xxh3_64_with_seed;
 
     use crate::bolts::rands::{
-        Rand, RomuDuoJrRand, RomuTrioRand, StdRand, XorShift64Rand, Xoshiro256StarRand,
+        Lehmer64Rand, Rand, RomuDuoJrRand, RomuTrioRand, StdRand, XorShift64Rand,
+        Xoshiro256StarRand,
     };
 
     fn test_single_rand<R: Rand>(rand: &mut R) {
@@ -399,6 +400,7 @@ mod tests {
         test_single_rand(&mut RomuDuoJrRand::with_seed(0));
         test_single_rand(&mut XorShift64Rand::with_seed(0));
         test_single_rand(&mut Xoshiro256StarRand::with_seed(0));
+        test_single_rand(&mut Lehmer64Rand::with_seed(0));
     }
 
     #[cfg(feature = "std")]
@@ -100,17 +100,25 @@ macro_rules! create_serde_registry_for_trait {
                 where
                     V: serde::de::SeqAccess<'de>,
                 {
-                    let id: u64 = visitor.next_element()?.unwrap();
+                    let id: u64 = visitor
+                        .next_element()?
+                        .ok_or_else(|| serde::de::Error::invalid_length(0, &self))?;
                     let cb = unsafe {
                         *REGISTRY
                             .deserializers
                             .as_ref()
-                            .expect("Empty types registry")
+                            .ok_or_else(|| serde::de::Error::custom("Empty types registry"))?
                             .get(&id)
-                            .expect("Cannot deserialize an unregistered type")
+                            .ok_or_else(|| {
+                                serde::de::Error::custom(format_args!(
+                                    "Cannot deserialize an unregistered type (id {id})"
+                                ))
+                            })?
                     };
                     let seed = DeserializeCallbackSeed::<dyn $trait_name> { cb };
-                    let obj: Self::Value = visitor.next_element_seed(seed)?.unwrap();
+                    let obj: Self::Value = visitor
+                        .next_element_seed(seed)?
+                        .ok_or_else(|| serde::de::Error::invalid_length(1, &self))?;
                     Ok(obj)
                 }
             }
@@ -677,3 +685,41 @@ macro_rules! impl_serdeany {
         $crate::register_at_startup!($struct_name);
     };
 }
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+
+    use super::{NamedSerdeAnyMap, SerdeAnyMap};
+
+    #[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+    struct TestMetadata(u32);
+
+    crate::impl_serdeany!(TestMetadata);
+
+    #[test]
+    fn test_serdeany_map_roundtrip() {
+        let mut map = SerdeAnyMap::new();
+        map.insert(TestMetadata(42));
+        assert!(map.contains::<TestMetadata>());
+
+        let serialized = postcard::to_allocvec(&map).unwrap();
+        let map: SerdeAnyMap = postcard::from_bytes(&serialized).unwrap();
+        assert_eq!(map.get::<TestMetadata>(), Some(&TestMetadata(42)));
+
+        let mut named = NamedSerdeAnyMap::new();
+        named.insert(TestMetadata(1), "first");
+        named.insert(TestMetadata(2), "second");
+        let cloned = named.clone();
+        assert_eq!(cloned.get::<TestMetadata>("second"), Some(&TestMetadata(2)));
+        assert!(!cloned.contains::<TestMetadata>("third"));
+        assert_eq!(named.get::<TestMetadata>("first"), Some(&TestMetadata(1)));
+    }
+
+    #[test]
+    fn test_serdeany_unregistered_type() {
+        // One map entry, keyed 7, holding a `(type id 7, 0)` seq. No type has id 7.
+        let bytes = [1_u8, 7, 2, 7, 0];
+        assert!(postcard::from_bytes::<SerdeAnyMap>(&bytes).is_err());
+    }
+}
@@ -104,7 +104,13 @@ impl ShMemId {
     /// Try to create a new id from a bytes string.
     /// The slice must have a length of at least 20 bytes and contain a valid cstring.
     pub fn try_from_slice(slice: &[u8]) -> Result<Self, Error> {
-        Ok(Self::from_array(&slice[0..20].try_into()?))
+        let Some(array) = slice.get(0..20) else {
+            return Err(Error::illegal_argument(format!(
+                "ShMemId needs at least 20 bytes, got {}",
+                slice.len()
+            )));
+        };
+        Ok(Self::from_array(array.try_into()?))
     }
 
     /// Create a new id from an int
@@ -139,7 +145,10 @@ impl ShMemId {
     /// Returns the first null-byte in or the end of the buffer
     #[must_use]
     pub fn null_pos(&self) -> usize {
-        self.id.iter().position(|&c| c == 0).unwrap()
+        self.id
+            .iter()
+            .position(|&c| c == 0)
+            .unwrap_or(self.id.len())
     }
 
     /// Returns a `str` representation of this [`ShMemId`]
@@ -959,12 +968,12 @@ pub mod unix_shmem {
     #[cfg(all(unix, feature = "std"))]
     pub mod ashmem {
         use alloc::string::ToString;
-        use core::{ptr, slice};
+        use core::{mem, ptr, slice};
         use std::ffi::CString;
 
         use libc::{
-            c_uint, c_ulong, c_void, close, ioctl, mmap, open, MAP_SHARED, O_RDWR, PROT_READ,
-            PROT_WRITE,
+            c_int, c_uint, c_ulong, c_void, close, fstat, ioctl, mmap, open, MAP_SHARED, O_RDWR,
+            PROT_READ, PROT_WRITE,
         };
 
         use crate::{
@@ -996,6 +1005,41 @@ pub mod unix_shmem {
         //const ASHMEM_SET_NAME: c_long = 0x41007701;
         const ASHMEM_SET_SIZE: c_ulong = 0x40087703;
 
+        /// Creates a `memfd` of `map_size` bytes, for devices that don't let us open the ashmem device
+        #[cfg(any(target_os = "linux", target_os = "android"))]
+        #[allow(clippy::cast_possible_wrap)]
+        unsafe fn memfd(map_size: usize) -> Result<c_int, Error> {
+            let fd = libc::syscall(libc::SYS_memfd_create, b"libafl\0".as_ptr(), 0) as c_int;
+            if fd == -1 {
+                return Err(Error::last_os_error("Failed to create a memfd"));
+            }
+            if libc::ftruncate(fd, map_size as libc::off_t) != 0 {
+                let err = Error::last_os_error("Failed to set the size of the memfd");
+                close(fd);
+                return Err(err);
+            }
+            Ok(fd)
+        }
+
+        /// Creates a `memfd` of `map_size` bytes, for devices that don't let us open the ashmem device
+        #[cfg(not(any(target_os = "linux", target_os = "android")))]
+        unsafe fn memfd(_map_size: usize) -> Result<c_int, Error> {
+            Err(Error::unsupported(
+                "memfd is only available on Linux and Android",
+            ))
+        }
+
+        /// The size of the ashmem region, or of the `memfd`, behind `fd`
+        #[allow(trivial_numeric_casts, clippy::cast_sign_loss)]
+        unsafe fn fd_size(fd: c_int) -> Option<usize> {
+            let size = ioctl(fd, ASHMEM_GET_SIZE as _);
+            if size >= 0 {
+                return Some(size as usize);
+            }
+            let mut stat: libc::stat = mem::zeroed();
+            (fstat(fd, &mut stat) == 0).then_some(stat.st_size as usize)
+        }
+
         impl AshmemShMem {
             /// Create a new shared memory mapping, using shmget/shmat
             pub fn new(map_size: usize) -> Result<Self, Error> {
@@ -1017,23 +1061,27 @@ pub mod unix_shmem {
                     .unwrap();
 
                     let fd = open(device_path.as_ptr(), O_RDWR);
-                    if fd == -1 {
-                        return Err(Error::unknown(format!(
-                            "Failed to open the ashmem device at {device_path:?}"
-                        )));
-                    }
-
-                    //if ioctl(fd, ASHMEM_SET_NAME, name) != 0 {
-                    //close(fd);
-                    //return Err(Error::unknown("Failed to set the ashmem mapping's name".to_string()));
-                    //};
-
-                    #[allow(trivial_numeric_casts)]
-                    if ioctl(fd, ASHMEM_SET_SIZE as _, map_size) != 0 {
-                        close(fd);
-                        return Err(Error::unknown(
-                            "Failed to set the ashmem mapping's size".to_string(),
-                        ));
+                    let fd = if fd == -1 {
+                        // Newer Android versions don't let apps open the ashmem device
+                        memfd(map_size).map_err(|err| {
+                            Error::unknown(format!(
+                                "Failed to open the ashmem device at {device_path:?}, and to fall back to a memfd: {err}"
+                            ))
+                        })?
+                    } else {
+                        //if ioctl(fd, ASHMEM_SET_NAME, name) != 0 {
+                        //close(fd);
+                        //return Err(Error::unknown("Failed to set the ashmem mapping's name".to_string()));
+                        //};
+
+                        #[allow(trivial_numeric_casts)]
+                        if ioctl(fd, ASHMEM_SET_SIZE as _, map_size) != 0 {
+                            close(fd);
+                            return Err(Error::unknown(
+                                "Failed to set the ashmem mapping's size".to_string(),
+                            ));
+                        };
+                        fd
                     };
 
                     let map = mmap(
@@ -1063,8 +1111,7 @@ pub mod unix_shmem {
             pub fn shmem_from_id_and_size(id: ShMemId, map_size: usize) -> Result<Self, Error> {
                 unsafe {
                     let fd: i32 = id.to_string().parse().unwrap();
-                    #[allow(trivial_numeric_casts, clippy::cast_sign_loss)]
-                    if ioctl(fd, ASHMEM_GET_SIZE as _) as u32 as usize != map_size {
+                    if fd_size(fd) != Some(map_size) {
                         return Err(Error::unknown(
                             "The mapping's size differs from the requested size".to_string(),
                         ));
@@ -1128,9 +1175,8 @@ pub mod unix_shmem {
                 unsafe {
                     let fd: i32 = self.id.to_string().parse().unwrap();
 
-                    #[allow(trivial_numeric_casts)]
-                    #[allow(clippy::cast_sign_loss)]
-                    let length = ioctl(fd, ASHMEM_GET_SIZE as _) as u32;
+                    // Unpinning a memfd fails, which is fine
+                    let length = fd_size(fd).unwrap_or(0) as u32;
 
                     let ap = ashmem_pin {
                         offset: 0,
@@ -1468,8 +1514,10 @@ impl<T: ShMem> std::io::Seek for ShMemCursor<T> {
 mod tests {
     use serial_test::serial;
 
+    #[cfg(target_os = "linux")]
+    use crate::bolts::shmem::{unix_shmem::ashmem::AshmemShMemProvider, ShMem};
     use crate::bolts::{
-        shmem::{ShMemProvider, StdShMemProvider},
+        shmem::{ShMemId, ShMemProvider, StdShMemProvider},
         AsMutSlice, AsSlice,
     };
 
@@ -1482,4 +1530,29 @@ mod tests {
         map.as_mut_slice()[0] = 1;
         assert!(map.as_slice()[0] == 1);
     }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    #[cfg_attr(miri, ignore)]
+    fn test_ashmem_memfd_fallback() {
+        // Without an ashmem device, the mapping is backed by a memfd
+        let mut provider = AshmemShMemProvider::new().unwrap();
+        let mut map = provider.new_shmem(1024).unwrap();
+        assert_eq!(map.len(), 1024);
+        map.as_mut_slice()[1023] = 1;
+        assert_eq!(map.as_slice()[1023], 1);
+    }
+
+    #[test]
+    fn test_shmem_id() {
+        let id = ShMemId::from_string("1337");
+        assert_eq!(id.as_str(), "1337");
+        assert_eq!(ShMemId::try_from_slice(id.as_array()).unwrap(), id);
+        assert!(ShMemId::try_from_slice(b"1337").is_err());
+
+        // An id using the whole buffer has no null byte
+        let full = ShMemId::from_array(&[b'1'; 20]);
+        assert_eq!(full.null_pos(), 20);
+        assert_eq!(full.as_str().len(), 20);
+    }
 }
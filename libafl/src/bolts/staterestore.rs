@@ -134,12 +134,12 @@ where
             let filename_buf = postcard::to_allocvec(&filename)?;
 
             let len = filename_buf.len();
-            if len > self.shmem.len() {
+            if size_of::<StateShMemContent>() + len > self.shmem.len() {
                 return Err(Error::illegal_state(format!(
                     "The state restorer map is too small to fit anything, even the filename! 
                         It needs to be at least {} bytes. 
                         The tmpfile was written to {:?}.",
-                    len,
+                    size_of::<StateShMemContent>() + len,
                     temp_dir().join(&filename)
                 )));
             }
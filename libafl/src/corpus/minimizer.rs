@@ -118,11 +118,8 @@ where
                 let weight = TS::compute(state, &mut *testcase)?
                     .to_u64()
                     .expect("Weight must be computable.");
-                let input = testcase
-                    .input()
-                    .as_ref()
-                    .expect("Input must be available.")
-                    .clone();
+                // On-disk corpora don't keep the inputs in memory
+                let input = testcase.load_input(state.corpus())?.clone();
                 (weight, input)
             };
 
@@ -137,7 +134,9 @@ where
             let obs: &O = executor
                 .observers()
                 .match_name::<O>(&self.obs_name)
-                .expect("Observer must be present.");
+                .ok_or_else(|| {
+                    Error::key_not_found(format!("MapObserver {} not found", self.obs_name))
+                })?;
 
             // Store coverage, mapping coverage map indices to hit counts (if present) and the
             // associated seeds for the map indices with those hit counts.
@@ -128,7 +128,7 @@ pub trait Corpus: UsesInput + Serialize + for<'de> Deserialize<'de> {
     fn nth(&self, nth: usize) -> CorpusId {
         self.ids()
             .nth(nth)
-            .expect("Failed to get the {nth} CorpusId")
+            .unwrap_or_else(|| panic!("Failed to get the {nth} CorpusId"))
     }
 
     /// Method to load the input for this [`Testcase`] from persistent storage,
@@ -165,7 +165,13 @@ where
 
     fn next(&mut self) -> Option<Self::Item> {
         if let Some(cur) = self.cur {
-            self.cur = self.corpus.next(cur);
+            if self.cur_back == Some(cur) {
+                // Both ends met, the iterator is exhausted
+                self.cur = None;
+                self.cur_back = None;
+            } else {
+                self.cur = self.corpus.next(cur);
+            }
             Some(cur)
         } else {
             None
@@ -179,7 +185,13 @@ where
 {
     fn next_back(&mut self) -> Option<Self::Item> {
         if let Some(cur_back) = self.cur_back {
-            self.cur_back = self.corpus.prev(cur_back);
+            if self.cur == Some(cur_back) {
+                // Both ends met, the iterator is exhausted
+                self.cur = None;
+                self.cur_back = None;
+            } else {
+                self.cur_back = self.corpus.prev(cur_back);
+            }
             Some(cur_back)
         } else {
             None
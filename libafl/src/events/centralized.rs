@@ -64,6 +64,7 @@ where
                     exit_kind: _,
                     corpus_size: _,
                     observers_buf: _,
+                    coverage_hash: _,
                     time: _,
                     executions: _,
                     forward_id,
@@ -160,6 +161,7 @@ where
                             exit_kind,
                             corpus_size,
                             observers_buf,
+                            coverage_hash,
                             time,
                             executions,
                             forward_id,
@@ -185,6 +187,7 @@ where
                                     Event::NewTestcase {
                                         input,
                                         observers_buf,
+                                        coverage_hash,
                                         exit_kind,
                                         corpus_size,
                                         client_config,
@@ -2,15 +2,26 @@
 
 use alloc::{
     boxed::Box,
+    collections::VecDeque,
     string::{String, ToString},
     vec::Vec,
 };
 #[cfg(feature = "std")]
 use core::sync::atomic::{compiler_fence, Ordering};
-use core::{marker::PhantomData, num::NonZeroUsize, time::Duration};
+use core::{
+    hash::{BuildHasher, Hasher},
+    marker::PhantomData,
+    num::NonZeroUsize,
+    time::Duration,
+};
 #[cfg(feature = "std")]
-use std::net::{SocketAddr, ToSocketAddrs};
+use std::{
+    net::{SocketAddr, ToSocketAddrs},
+    path::PathBuf,
+};
 
+use ahash::RandomState;
+use hashbrown::HashSet;
 use serde::Deserialize;
 #[cfg(feature = "std")]
 use serde::{de::DeserializeOwned, Serialize};
@@ -35,6 +46,8 @@ use crate::bolts::{
 use crate::bolts::{llmp::LlmpConnection, shmem::StdShMemProvider, staterestore::StateRestorer};
 #[cfg(all(unix, feature = "std"))]
 use crate::events::{shutdown_handler, SHUTDOWN_SIGHANDLER_DATA};
+#[cfg(feature = "std")]
+use crate::state::State;
 use crate::{
     bolts::{
         llmp::{self, LlmpClient, LlmpClientDescription, Tag},
@@ -67,6 +80,62 @@ const _LLMP_TAG_NO_RESTART: Tag = Tag(0x57A7EE71);
 #[cfg(feature = "llmp_compression")]
 const COMPRESS_THRESHOLD: usize = 1024;
 
+/// The hashes of the testcases a broker forwarded most recently
+#[derive(Debug)]
+struct SeenTestcases {
+    hashes: HashSet<u64>,
+    order: VecDeque<u64>,
+    max_entries: usize,
+}
+
+impl SeenTestcases {
+    fn new(max_entries: NonZeroUsize) -> Self {
+        Self {
+            hashes: HashSet::new(),
+            order: VecDeque::new(),
+            max_entries: max_entries.get(),
+        }
+    }
+
+    /// Remembers the hash, forgetting the oldest one if full. Returns `false` if it was seen before.
+    fn insert(&mut self, hash: u64) -> bool {
+        if !self.hashes.insert(hash) {
+            return false;
+        }
+        if self.order.len() == self.max_entries {
+            if let Some(oldest) = self.order.pop_front() {
+                self.hashes.remove(&oldest);
+            }
+        }
+        self.order.push_back(hash);
+        true
+    }
+}
+
+/// A [`postcard`] flavor hashing the serialized bytes, instead of storing them
+struct HashFlavor<H>(H);
+
+impl<H> postcard::ser_flavors::Flavor for HashFlavor<H>
+where
+    H: Hasher,
+{
+    type Output = u64;
+
+    fn try_push(&mut self, data: u8) -> postcard::Result<()> {
+        self.0.write_u8(data);
+        Ok(())
+    }
+
+    fn try_extend(&mut self, data: &[u8]) -> postcard::Result<()> {
+        self.0.write(data);
+        Ok(())
+    }
+
+    fn finalize(self) -> postcard::Result<u64> {
+        Ok(self.0.finish())
+    }
+}
+
 /// An LLMP-backed event manager for scalable multi-processed fuzzing
 #[derive(Debug)]
 pub struct LlmpEventBroker<I, MT, SP>
@@ -80,6 +149,8 @@ where
     llmp: llmp::LlmpBroker<SP>,
     #[cfg(feature = "llmp_compression")]
     compressor: GzipCompressor,
+    /// The testcases forwarded most recently, if deduplication is enabled
+    seen_testcases: Option<SeenTestcases>,
     phantom: PhantomData<I>,
 }
 
@@ -96,6 +167,7 @@ where
             llmp,
             #[cfg(feature = "llmp_compression")]
             compressor: GzipCompressor::new(COMPRESS_THRESHOLD),
+            seen_testcases: None,
             phantom: PhantomData,
         })
     }
@@ -110,6 +182,7 @@ where
             llmp: llmp::LlmpBroker::create_attach_to_tcp(shmem_provider, port)?,
             #[cfg(feature = "llmp_compression")]
             compressor: GzipCompressor::new(COMPRESS_THRESHOLD),
+            seen_testcases: None,
             phantom: PhantomData,
         })
     }
@@ -119,6 +192,17 @@ where
         self.llmp.set_exit_cleanly_after(n_clients);
     }
 
+    /// Only forward the first [`Event::NewTestcase`] for each coverage to the clients.
+    /// Testcases with the same coverage sent later, e.g. found by several clients at once,
+    /// are not re-executed by everyone.
+    ///
+    /// The coverage is known for feedbacks that track the map indexes, other testcases are
+    /// deduplicated by their input. Only the last `max_entries` testcases are remembered,
+    /// `None` disables the deduplication.
+    pub fn set_deduplicate_testcases(&mut self, max_entries: Option<NonZeroUsize>) {
+        self.seen_testcases = max_entries.map(SeenTestcases::new);
+    }
+
     /// Connect to an LLMP broker on the given address
     #[cfg(feature = "std")]
     pub fn connect_b2b<A>(&mut self, addr: A) -> Result<(), Error>
@@ -132,6 +216,7 @@ where
     #[cfg(not(feature = "llmp_broker_timeouts"))]
     pub fn broker_loop(&mut self) -> Result<(), Error> {
         let monitor = &mut self.monitor;
+        let seen_testcases = &mut self.seen_testcases;
         #[cfg(feature = "llmp_compression")]
         let compressor = &self.compressor;
         self.llmp.loop_forever(
@@ -149,7 +234,7 @@ where
                         msg
                     };
                     let event: Event<I> = postcard::from_bytes(event_bytes)?;
-                    match Self::handle_in_broker(monitor, client_id, &event)? {
+                    match Self::handle_in_broker(monitor, seen_testcases, client_id, &event)? {
                         BrokerEventResult::Forward => Ok(llmp::LlmpMsgHookResult::ForwardToClients),
                         BrokerEventResult::Handled => Ok(llmp::LlmpMsgHookResult::Handled),
                     }
@@ -170,6 +255,7 @@ where
     #[cfg(feature = "llmp_broker_timeouts")]
     pub fn broker_loop(&mut self) -> Result<(), Error> {
         let monitor = &mut self.monitor;
+        let seen_testcases = &mut self.seen_testcases;
         #[cfg(feature = "llmp_compression")]
         let compressor = &self.compressor;
         self.llmp.loop_with_timeouts(
@@ -188,7 +274,7 @@ where
                             msg
                         };
                         let event: Event<I> = postcard::from_bytes(event_bytes)?;
-                        match Self::handle_in_broker(monitor, client_id, &event)? {
+                        match Self::handle_in_broker(monitor, seen_testcases, client_id, &event)? {
                             BrokerEventResult::Forward => {
                                 Ok(llmp::LlmpMsgHookResult::ForwardToClients)
                             }
@@ -216,16 +302,18 @@ where
     #[allow(clippy::unnecessary_wraps)]
     fn handle_in_broker(
         monitor: &mut MT,
+        seen_testcases: &mut Option<SeenTestcases>,
         client_id: ClientId,
         event: &Event<I>,
     ) -> Result<BrokerEventResult, Error> {
         match &event {
             Event::NewTestcase {
-                input: _,
+                input,
                 client_config: _,
                 exit_kind: _,
                 corpus_size,
                 observers_buf: _,
+                coverage_hash,
                 time,
                 executions,
                 forward_id,
@@ -239,6 +327,19 @@ where
                 client.update_corpus_size(*corpus_size as u64);
                 client.update_executions(*executions as u64, *time);
                 monitor.display(event.name().to_string(), id);
+                if let Some(seen_testcases) = seen_testcases {
+                    let hash = match coverage_hash {
+                        Some(hash) => *hash,
+                        None => postcard::serialize_with_flavor(
+                            input,
+                            HashFlavor(RandomState::with_seeds(0, 0, 0, 0).build_hasher()),
+                        )?,
+                    };
+                    if !seen_testcases.insert(hash) {
+                        // Another client already sent this coverage, the others evaluated it before
+                        return Ok(BrokerEventResult::Handled);
+                    }
+                }
                 Ok(BrokerEventResult::Forward)
             }
             Event::UpdateExecStats {
@@ -286,9 +387,16 @@ where
                 // Correctly handled the event
                 Ok(BrokerEventResult::Handled)
             }
-            Event::Objective { objective_size } => {
+            Event::Objective {
+                objective_size,
+                solution_name,
+                exit_kind,
+                time,
+            } => {
+                let start_time = monitor.start_time();
                 let client = monitor.client_stats_mut_for(client_id);
                 client.update_objective_size(*objective_size as u64);
+                client.update_last_objective(solution_name, *exit_kind, *time, start_time);
                 monitor.display(event.name().to_string(), client_id);
                 Ok(BrokerEventResult::Handled)
             }
@@ -462,6 +570,7 @@ where
                 exit_kind,
                 corpus_size: _,
                 observers_buf,
+                coverage_hash: _,
                 time: _,
                 executions: _,
                 forward_id,
@@ -676,6 +785,8 @@ where
     staterestorer: StateRestorer<SP>,
     /// Decide if the state restorer must save the serialized state
     save_state: bool,
+    /// The file to also save the state to on restart, to resume the campaign from later
+    state_file: Option<PathBuf>,
 }
 
 #[cfg(feature = "std")]
@@ -719,7 +830,7 @@ where
 #[cfg(feature = "std")]
 impl<S, SP> EventRestarter for LlmpRestartingEventManager<S, SP>
 where
-    S: UsesInput + HasExecutions + HasClientPerfMonitor + Serialize,
+    S: State + HasExecutions + HasClientPerfMonitor,
     SP: ShMemProvider,
     //CE: CustomEvent<I>,
 {
@@ -732,6 +843,9 @@ where
 
     /// Reset the single page (we reuse it over and over from pos 0), then send the current state to the next runner.
     fn on_restart(&mut self, state: &mut S) -> Result<(), Error> {
+        if let Some(state_file) = &self.state_file {
+            state.save_to(state_file)?;
+        }
         // First, reset the page to 0 so the next iteration can read read from the beginning of this page
         self.staterestorer.reset();
         self.staterestorer.save(&(
@@ -767,7 +881,7 @@ impl<E, S, SP, Z> EventManager<E, Z> for LlmpRestartingEventManager<S, SP>
 where
     E: HasObservers<State = S> + Executor<LlmpEventManager<S, SP>, Z>,
     for<'a> E::Observers: Deserialize<'a>,
-    S: UsesInput + HasExecutions + HasClientPerfMonitor + HasMetadata + Serialize,
+    S: State + HasExecutions + HasClientPerfMonitor + HasMetadata,
     SP: ShMemProvider + 'static,
     Z: EvaluatorObservers<E::Observers, State = S> + ExecutionProcessor<E::Observers>, //CE: CustomEvent<I>,
 {
@@ -803,6 +917,7 @@ where
             llmp_mgr,
             staterestorer,
             save_state: true,
+            state_file: None,
         }
     }
 
@@ -816,9 +931,16 @@ where
             llmp_mgr,
             staterestorer,
             save_state,
+            state_file: None,
         }
     }
 
+    /// Set the file to also save the state to on every restart.
+    /// The campaign can later be resumed from it using [`State::load_from`].
+    pub fn set_state_file(&mut self, state_file: Option<PathBuf>) {
+        self.state_file = state_file;
+    }
+
     /// Get the staterestorer
     pub fn staterestorer(&self) -> &StateRestorer<SP> {
         &self.staterestorer
@@ -857,7 +979,7 @@ pub fn setup_restarting_mgr_std<MT, S>(
 ) -> Result<(Option<S>, LlmpRestartingEventManager<S, StdShMemProvider>), Error>
 where
     MT: Monitor + Clone,
-    S: DeserializeOwned + UsesInput + HasClientPerfMonitor + HasExecutions,
+    S: State + HasClientPerfMonitor + HasExecutions,
 {
     RestartingMgr::builder()
         .shmem_provider(StdShMemProvider::new()?)
@@ -909,6 +1031,10 @@ where
     /// Tell the manager to serialize or not the state on restart
     #[builder(default = true)]
     serialize_state: bool,
+    /// A file to save the state to on every restart.
+    /// If it exists when the fuzzer starts anew, the campaign is resumed from it.
+    #[builder(default = None)]
+    state_file: Option<PathBuf>,
     #[builder(setter(skip), default = PhantomData)]
     phantom_data: PhantomData<S>,
 }
@@ -918,7 +1044,7 @@ where
 impl<MT, S, SP> RestartingMgr<MT, S, SP>
 where
     SP: ShMemProvider,
-    S: UsesInput + HasExecutions + HasClientPerfMonitor + DeserializeOwned,
+    S: State + HasExecutions + HasClientPerfMonitor,
     MT: Monitor + Clone,
 {
     /// Launch the restarting manager
@@ -1117,8 +1243,16 @@ where
                     self.configuration,
                 )?;
 
+                let state = match &self.state_file {
+                    Some(state_file) if state_file.exists() => {
+                        log::info!("Resuming from the state in {}", state_file.display());
+                        Some(S::load_from(state_file)?)
+                    }
+                    _ => None,
+                };
+
                 (
-                    None,
+                    state,
                     LlmpRestartingEventManager::with_save_state(
                         mgr,
                         staterestorer,
@@ -1128,6 +1262,7 @@ where
             };
         // We reset the staterestorer, the next staterestorer and receiver (after crash) will reuse the page from the initial message.
         mgr.staterestorer.reset();
+        mgr.set_state_file(self.state_file.clone());
 
         /* TODO: Not sure if this is needed
         // We commit an empty NO_RESTART message to this buf, against infinite loops,
@@ -1289,6 +1424,7 @@ where
                 exit_kind: _,
                 corpus_size: _,
                 observers_buf: _, // Useless as we are converting between types
+                coverage_hash: _,
                 time: _,
                 executions: _,
                 forward_id,
@@ -1410,6 +1546,7 @@ where
                 exit_kind,
                 corpus_size,
                 observers_buf,
+                coverage_hash,
                 time,
                 executions,
                 forward_id,
@@ -1419,6 +1556,7 @@ where
                 exit_kind,
                 corpus_size,
                 observers_buf,
+                coverage_hash,
                 time,
                 executions,
                 forward_id,
@@ -1464,6 +1602,7 @@ where
                 exit_kind,
                 corpus_size,
                 observers_buf,
+                coverage_hash,
                 time,
                 executions,
                 forward_id,
@@ -1473,6 +1612,7 @@ where
                 exit_kind,
                 corpus_size,
                 observers_buf,
+                coverage_hash,
                 time,
                 executions,
                 forward_id,
@@ -1491,7 +1631,12 @@ where
 #[cfg(test)]
 #[cfg(feature = "std")]
 mod tests {
-    use core::sync::atomic::{compiler_fence, Ordering};
+    use alloc::vec::Vec;
+    use core::{
+        num::NonZeroUsize,
+        sync::atomic::{compiler_fence, Ordering},
+        time::Duration,
+    };
 
     use serial_test::serial;
 
@@ -1505,15 +1650,20 @@ mod tests {
             ClientId,
         },
         corpus::{Corpus, InMemoryCorpus, Testcase},
-        events::{llmp::_ENV_FUZZER_SENDER, LlmpEventManager},
+        events::{
+            llmp::{SeenTestcases, _ENV_FUZZER_SENDER},
+            BrokerEventResult, Event, EventConfig, EventRestarter, LlmpEventBroker,
+            LlmpEventManager, LlmpRestartingEventManager,
+        },
         executors::{ExitKind, InProcessExecutor},
         feedbacks::ConstFeedback,
         fuzzer::Fuzzer,
         inputs::BytesInput,
+        monitors::NopMonitor,
         mutators::BitFlipMutator,
         schedulers::RandScheduler,
         stages::StdMutationalStage,
-        state::StdState,
+        state::{HasCorpus, State, StdState},
         StdFuzzer,
     };
 
@@ -1609,4 +1759,97 @@ mod tests {
                 .unwrap();
         }
     }
+
+    #[test]
+    #[serial]
+    #[cfg_attr(miri, ignore)]
+    fn test_mgr_state_file() {
+        let mut corpus = InMemoryCorpus::<BytesInput>::new();
+        corpus.add(Testcase::new(vec![0; 4].into())).unwrap();
+        let mut feedback = ConstFeedback::new(false);
+        let mut objective = ConstFeedback::new(false);
+        let mut state = StdState::new(
+            StdRand::with_seed(0),
+            corpus,
+            InMemoryCorpus::<BytesInput>::new(),
+            &mut feedback,
+            &mut objective,
+        )
+        .unwrap();
+
+        let mut shmem_provider = StdShMemProvider::new().unwrap();
+        let mut llmp_client = LlmpClient::new(
+            shmem_provider.clone(),
+            LlmpSharedMap::new(ClientId(0), shmem_provider.new_shmem(1024).unwrap()),
+            ClientId(0),
+        )
+        .unwrap();
+        // A little hack for CI. Don't do that in a real-world scenario.
+        unsafe {
+            llmp_client.mark_safe_to_unmap();
+        }
+        let staterestorer = StateRestorer::<StdShMemProvider>::new(
+            shmem_provider.new_shmem(256 * 1024 * 1024).unwrap(),
+        );
+        let mut mgr = LlmpRestartingEventManager::new(
+            LlmpEventManager::new(llmp_client, "fuzzer".into()).unwrap(),
+            staterestorer,
+        );
+
+        let path = std::env::temp_dir().join("libafl_test_mgr_state_file");
+        let _ = std::fs::remove_file(&path);
+        mgr.set_state_file(Some(path.clone()));
+        mgr.on_restart(&mut state).unwrap();
+
+        let resumed = StdState::<
+            BytesInput,
+            InMemoryCorpus<BytesInput>,
+            StdRand,
+            InMemoryCorpus<BytesInput>,
+        >::load_from(&path)
+        .unwrap();
+        assert_eq!(resumed.corpus().count(), 1);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_broker_deduplicate_testcases() {
+        let mut monitor = NopMonitor::new();
+        let mut seen_testcases = Some(SeenTestcases::new(NonZeroUsize::new(2).unwrap()));
+        let new_testcase = |bytes: Vec<u8>, coverage_hash| Event::NewTestcase {
+            input: BytesInput::new(bytes),
+            observers_buf: None,
+            coverage_hash,
+            exit_kind: ExitKind::Ok,
+            corpus_size: 1,
+            client_config: EventConfig::AlwaysUnique,
+            time: Duration::from_secs(1),
+            executions: 1,
+            forward_id: None,
+        };
+
+        let mut forwarded = |client_id, event: &Event<BytesInput>| {
+            matches!(
+                LlmpEventBroker::<BytesInput, NopMonitor, StdShMemProvider>::handle_in_broker(
+                    &mut monitor,
+                    &mut seen_testcases,
+                    ClientId(client_id),
+                    event,
+                )
+                .unwrap(),
+                BrokerEventResult::Forward
+            )
+        };
+        assert!(forwarded(0, &new_testcase(vec![1, 2], Some(1))));
+        // A different input with the same coverage, found by another client, is not broadcast again
+        assert!(!forwarded(1, &new_testcase(vec![3], Some(1))));
+        assert!(forwarded(1, &new_testcase(vec![3], Some(2))));
+
+        // Without coverage, the inputs themselves are compared
+        assert!(forwarded(0, &new_testcase(vec![4], None)));
+        assert!(!forwarded(1, &new_testcase(vec![4], None)));
+
+        // Only the last two testcases are remembered
+        assert!(forwarded(1, &new_testcase(vec![1, 2], Some(1))));
+    }
 }
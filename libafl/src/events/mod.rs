@@ -140,7 +140,7 @@ impl From<LogSeverity> for log::Level {
         match value {
             LogSeverity::Debug => log::Level::Debug,
             LogSeverity::Info => log::Level::Info,
-            LogSeverity::Warn => log::Level::Trace,
+            LogSeverity::Warn => log::Level::Warn,
             LogSeverity::Error => log::Level::Error,
         }
     }
@@ -278,6 +278,8 @@ where
         input: I,
         /// The state of the observers when this testcase was found
         observers_buf: Option<Vec<u8>>,
+        /// A hash of the coverage of this testcase, if the feedbacks tracked its map indexes
+        coverage_hash: Option<u64>,
         /// The exit kind
         exit_kind: ExitKind,
         /// The new corpus size of this client
@@ -326,6 +328,12 @@ where
     Objective {
         /// Objective corpus size
         objective_size: usize,
+        /// The name of the objective, the file name of on-disk solution corpora, to reproduce it
+        solution_name: String,
+        /// How the execution that led to the objective finished
+        exit_kind: ExitKind,
+        /// The time at which the objective was found, since the epoch, see [`crate::bolts::current_time`]
+        time: Duration,
     },
     /// Write a new log
     Log {
@@ -362,6 +370,7 @@ where
                 corpus_size: _,
                 exit_kind: _,
                 observers_buf: _,
+                coverage_hash: _,
                 time: _,
                 executions: _,
                 forward_id: _,
@@ -651,7 +660,7 @@ mod tests {
             current_time,
             tuples::{tuple_list, Named},
         },
-        events::{Event, EventConfig},
+        events::{Event, EventConfig, LogSeverity},
         executors::ExitKind,
         inputs::bytes::BytesInput,
         observers::StdMapObserver,
@@ -669,6 +678,7 @@ mod tests {
         let e = Event::NewTestcase {
             input: i,
             observers_buf: Some(observers_buf),
+            coverage_hash: None,
             exit_kind: ExitKind::Ok,
             corpus_size: 123,
             client_config: EventConfig::AlwaysUnique,
@@ -684,6 +694,7 @@ mod tests {
             Event::NewTestcase {
                 input: _,
                 observers_buf,
+                coverage_hash: _,
                 corpus_size: _,
                 exit_kind: _,
                 client_config: _,
@@ -698,6 +709,14 @@ mod tests {
             _ => panic!("mistmatch"),
         };
     }
+
+    #[test]
+    fn test_log_severity_level() {
+        assert_eq!(log::Level::from(LogSeverity::Debug), log::Level::Debug);
+        assert_eq!(log::Level::from(LogSeverity::Info), log::Level::Info);
+        assert_eq!(log::Level::from(LogSeverity::Warn), log::Level::Warn);
+        assert_eq!(log::Level::from(LogSeverity::Error), log::Level::Error);
+    }
 }
 
 /// `EventManager` Python bindings
@@ -210,6 +210,7 @@ where
                 exit_kind: _,
                 corpus_size,
                 observers_buf: _,
+                coverage_hash: _,
                 time,
                 executions,
                 forward_id: _,
@@ -261,10 +262,16 @@ where
                 monitor.display(event.name().to_string(), ClientId(0));
                 Ok(BrokerEventResult::Handled)
             }
-            Event::Objective { objective_size } => {
-                monitor
-                    .client_stats_mut_for(ClientId(0))
-                    .update_objective_size(*objective_size as u64);
+            Event::Objective {
+                objective_size,
+                solution_name,
+                exit_kind,
+                time,
+            } => {
+                let start_time = monitor.start_time();
+                let client = monitor.client_stats_mut_for(ClientId(0));
+                client.update_objective_size(*objective_size as u64);
+                client.update_last_objective(solution_name, *exit_kind, *time, start_time);
                 monitor.display(event.name().to_string(), ClientId(0));
                 Ok(BrokerEventResult::Handled)
             }
@@ -132,8 +132,7 @@ impl CommandConfigurator for StdCommandConfigurator {
                 Ok(cmd.spawn()?)
             }
             InputLocation::StdIn => {
-                self.command.stdin(Stdio::piped()).spawn()?;
-                let mut handle = self.command.spawn()?;
+                let mut handle = self.command.stdin(Stdio::piped()).spawn()?;
                 let mut stdin = handle.stdin.take().unwrap();
                 if let Err(err) = stdin.write_all(input.target_bytes().as_slice()) {
                     if err.kind() != std::io::ErrorKind::BrokenPipe {
@@ -323,8 +322,7 @@ where
         let mut child = self.configurer.spawn_child(input)?;
 
         let res = match child
-            .wait_timeout(Duration::from_secs(5))
-            .expect("waiting on child failed")
+            .wait_timeout(Duration::from_secs(5))?
             .map(|status| status.signal())
         {
             // for reference: https://www.man7.org/linux/man-pages/man7/signal.7.html
@@ -653,7 +651,7 @@ mod tests {
         events::SimpleEventManager,
         executors::{
             command::{CommandExecutor, InputLocation},
-            Executor,
+            Executor, ExitKind,
         },
         inputs::BytesInput,
         monitors::SimpleMonitor,
@@ -686,6 +684,36 @@ mod tests {
             .unwrap();
     }
 
+    #[test]
+    #[cfg(unix)]
+    #[cfg_attr(miri, ignore)]
+    fn test_stdin_input() {
+        let mut mgr = SimpleEventManager::new(SimpleMonitor::new(|status| {
+            log::info!("{status}");
+        }));
+
+        // Crashes unless the input arrived on stdin
+        let mut executor = CommandExecutor::builder();
+        executor
+            .program("sh")
+            .arg("-c")
+            .arg("read x; [ \"$x\" = test ] || kill -SEGV $$");
+        let mut executor = executor.build(()).unwrap();
+
+        let mut run = |input: &[u8]| {
+            executor
+                .run_target(
+                    &mut NopFuzzer::new(),
+                    &mut NopState::new(),
+                    &mut mgr,
+                    &BytesInput::new(input.to_vec()),
+                )
+                .unwrap()
+        };
+        assert_eq!(run(b"test\n"), ExitKind::Ok);
+        assert_eq!(run(b"nope\n"), ExitKind::Crash);
+    }
+
     #[test]
     #[cfg(unix)]
     #[cfg_attr(miri, ignore)]
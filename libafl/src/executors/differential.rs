@@ -168,8 +168,8 @@ where
 
     /// Runs `observe_stdout` for all stdout observers in the list
     fn observe_stdout(&mut self, stdout: &[u8]) {
-        self.primary.as_mut().observe_stderr(stdout);
-        self.secondary.as_mut().observe_stderr(stdout);
+        self.primary.as_mut().observe_stdout(stdout);
+        self.secondary.as_mut().observe_stdout(stdout);
     }
 
     /// Runs `observe_stderr` for all stderr observers in the list
@@ -438,12 +438,16 @@ where
         if self.executor.uses_shmem_testcase() {
             let shmem = unsafe { self.executor.shmem_mut().as_mut().unwrap_unchecked() };
             let target_bytes = input.target_bytes();
-            let size = target_bytes.as_slice().len();
-            let size_in_bytes = size.to_ne_bytes();
+            // Truncate like AFL++ does
+            let size = target_bytes
+                .as_slice()
+                .len()
+                .min(shmem.len() - SHMEM_FUZZ_HDR_SIZE);
+            let size_in_bytes = (size as u32).to_ne_bytes();
             // The first four bytes tells the size of the shmem.
-            shmem.as_mut_slice()[..4].copy_from_slice(&size_in_bytes[..4]);
+            shmem.as_mut_slice()[..SHMEM_FUZZ_HDR_SIZE].copy_from_slice(&size_in_bytes);
             shmem.as_mut_slice()[SHMEM_FUZZ_HDR_SIZE..(SHMEM_FUZZ_HDR_SIZE + size)]
-                .copy_from_slice(target_bytes.as_slice());
+                .copy_from_slice(&target_bytes.as_slice()[..size]);
         } else {
             self.executor
                 .input_file_mut()
@@ -612,6 +616,8 @@ pub struct ForkserverExecutorBuilder<'a, SP> {
     shmem_provider: Option<&'a mut SP>,
     map_size: Option<usize>,
     real_map_size: i32,
+    memlimit: u64,
+    max_input_size: usize,
 }
 
 impl<'a, SP> ForkserverExecutorBuilder<'a, SP> {
@@ -716,23 +722,41 @@ impl<'a, SP> ForkserverExecutorBuilder<'a, SP> {
             None => None,
             Some(provider) => {
                 // setup shared memory
-                let mut shmem = provider.new_shmem(MAX_FILE + SHMEM_FUZZ_HDR_SIZE)?;
+                let shmem_size = self
+                    .max_input_size
+                    .checked_add(SHMEM_FUZZ_HDR_SIZE)
+                    .and_then(|size| u32::try_from(size).ok())
+                    .ok_or_else(|| {
+                        Error::illegal_argument(format!(
+                            "Max input size {} does not fit the shared memory size header",
+                            self.max_input_size
+                        ))
+                    })?;
+                let mut shmem = provider.new_shmem(shmem_size as usize)?;
                 shmem.write_to_env("__AFL_SHM_FUZZ_ID")?;
 
-                let size_in_bytes = (MAX_FILE + SHMEM_FUZZ_HDR_SIZE).to_ne_bytes();
-                shmem.as_mut_slice()[..4].clone_from_slice(&size_in_bytes[..4]);
+                let size_in_bytes = shmem_size.to_ne_bytes();
+                shmem.as_mut_slice()[..SHMEM_FUZZ_HDR_SIZE].clone_from_slice(&size_in_bytes);
                 Some(shmem)
             }
         };
 
+        // Tell `AFL++` targets how large our coverage map is, unless the user already did
+        let mut envs = self.envs.clone();
+        if let Some(map_size) = self.map_size {
+            if !envs.iter().any(|(key, _)| key == "AFL_MAP_SIZE") {
+                envs.push(("AFL_MAP_SIZE".into(), map_size.to_string().into()));
+            }
+        }
+
         let mut forkserver = match &self.program {
             Some(t) => Forkserver::new(
                 t.clone(),
                 self.arguments.clone(),
-                self.envs.clone(),
+                envs,
                 input_file.as_raw_fd(),
                 self.use_stdin,
-                0,
+                self.memlimit,
                 self.is_persistent,
                 self.is_deferred_frksrv,
                 self.debug_child,
@@ -766,8 +790,14 @@ impl<'a, SP> ForkserverExecutorBuilder<'a, SP> {
                 map_size = ((map_size + 63) >> 6) << 6;
             }
 
-            // TODO set AFL_MAP_SIZE
-            assert!(self.map_size.is_none() || map_size as usize <= self.map_size.unwrap());
+            if let Some(configured_map_size) = self.map_size {
+                if map_size as usize > configured_map_size {
+                    // The target would write past the end of our map
+                    return Err(Error::illegal_state(format!(
+                        "The target needs a coverage map of {map_size} bytes, but the coverage map size is {configured_map_size}"
+                    )));
+                }
+            }
 
             self.map_size = Some(map_size as usize);
         }
@@ -999,11 +1029,28 @@ impl<'a, SP> ForkserverExecutorBuilder<'a, SP> {
     }
 
     #[must_use]
-    /// Call this to set a defauult const coverage map size
+    /// Call this to set a defauult const coverage map size.
+    /// It is passed to the target as `AFL_MAP_SIZE`, and building fails if the target needs a larger map.
     pub fn coverage_map_size(mut self, size: usize) -> Self {
         self.map_size = Some(size);
         self
     }
+
+    #[must_use]
+    /// Limit the address space of the target to `memlimit` MB, like AFL's `-m`; default is `0` (no limit)
+    pub fn memlimit(mut self, memlimit: u64) -> Self {
+        self.memlimit = memlimit;
+        self
+    }
+
+    #[must_use]
+    /// The size of the largest input passed over shared memory, longer inputs are truncated; default is 1MB, like `AFL++`.
+    /// Only the forkserver of `libafl_targets` maps as much as the fuzzer provides.
+    /// Targets built with `AFL++` always map 1MB, so larger values make them crash on large inputs.
+    pub fn max_input_size(mut self, max_input_size: usize) -> Self {
+        self.max_input_size = max_input_size;
+        self
+    }
 }
 
 impl<'a> ForkserverExecutorBuilder<'a, UnixShMemProvider> {
@@ -1029,6 +1076,8 @@ impl<'a> ForkserverExecutorBuilder<'a, UnixShMemProvider> {
             shmem_provider: None,
             map_size: None,
             real_map_size: 0,
+            memlimit: 0,
+            max_input_size: MAX_FILE,
         }
     }
 
@@ -1051,6 +1100,8 @@ impl<'a> ForkserverExecutorBuilder<'a, UnixShMemProvider> {
             shmem_provider: Some(shmem_provider),
             map_size: self.map_size,
             real_map_size: self.real_map_size,
+            memlimit: self.memlimit,
+            max_input_size: self.max_input_size,
         }
     }
 }
@@ -1084,17 +1135,16 @@ where
         if self.uses_shmem_testcase {
             let map = unsafe { self.map.as_mut().unwrap_unchecked() };
             let target_bytes = input.target_bytes();
-            let mut size = target_bytes.as_slice().len();
-            if size > MAX_FILE {
-                // Truncate like AFL++ does
-                size = MAX_FILE;
-            }
-            let size_in_bytes = size.to_ne_bytes();
+            // Truncate like AFL++ does
+            let size = target_bytes
+                .as_slice()
+                .len()
+                .min(map.len() - SHMEM_FUZZ_HDR_SIZE);
+            let size_in_bytes = (size as u32).to_ne_bytes();
             // The first four bytes tells the size of the shmem.
-            map.as_mut_slice()[..SHMEM_FUZZ_HDR_SIZE]
-                .copy_from_slice(&size_in_bytes[..SHMEM_FUZZ_HDR_SIZE]);
+            map.as_mut_slice()[..SHMEM_FUZZ_HDR_SIZE].copy_from_slice(&size_in_bytes);
             map.as_mut_slice()[SHMEM_FUZZ_HDR_SIZE..(SHMEM_FUZZ_HDR_SIZE + size)]
-                .copy_from_slice(target_bytes.as_slice());
+                .copy_from_slice(&target_bytes.as_slice()[..size]);
         } else {
             self.input_file.write_buf(input.target_bytes().as_slice())?;
         }
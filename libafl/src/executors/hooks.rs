@@ -0,0 +1,266 @@
+//! Executor hooks run user code right before and after each execution of the wrapped executor,
+//! e.g. to reset global target state, flush caches, or toggle coverage collection.
+
+use crate::{
+    executors::{Executor, ExitKind, HasObservers},
+    inputs::UsesInput,
+    observers::UsesObservers,
+    state::UsesState,
+    Error,
+};
+
+/// A hook, run around each execution of a [`HookedExecutor`]
+pub trait ExecutorHook<S>
+where
+    S: UsesInput,
+{
+    /// Called right before the target runs
+    #[inline]
+    fn pre_run(&mut self, _state: &mut S, _input: &S::Input) -> Result<(), Error> {
+        Ok(())
+    }
+
+    /// Called right after the target returned.
+    /// Executors that exit on crashes, like the [`crate::executors::InProcessExecutor`], won't get here.
+    #[inline]
+    fn post_run(
+        &mut self,
+        _state: &mut S,
+        _input: &S::Input,
+        _exit_kind: &ExitKind,
+    ) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+/// A haskell-style tuple of [`ExecutorHook`]s
+pub trait ExecutorHooksTuple<S>
+where
+    S: UsesInput,
+{
+    /// Runs [`ExecutorHook::pre_run`] of all hooks, in order
+    fn pre_run_all(&mut self, state: &mut S, input: &S::Input) -> Result<(), Error>;
+
+    /// Runs [`ExecutorHook::post_run`] of all hooks, in order
+    fn post_run_all(
+        &mut self,
+        state: &mut S,
+        input: &S::Input,
+        exit_kind: &ExitKind,
+    ) -> Result<(), Error>;
+}
+
+impl<S> ExecutorHooksTuple<S> for ()
+where
+    S: UsesInput,
+{
+    #[inline]
+    fn pre_run_all(&mut self, _state: &mut S, _input: &S::Input) -> Result<(), Error> {
+        Ok(())
+    }
+
+    #[inline]
+    fn post_run_all(
+        &mut self,
+        _state: &mut S,
+        _input: &S::Input,
+        _exit_kind: &ExitKind,
+    ) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+impl<Head, Tail, S> ExecutorHooksTuple<S> for (Head, Tail)
+where
+    Head: ExecutorHook<S>,
+    Tail: ExecutorHooksTuple<S>,
+    S: UsesInput,
+{
+    fn pre_run_all(&mut self, state: &mut S, input: &S::Input) -> Result<(), Error> {
+        self.0.pre_run(state, input)?;
+        self.1.pre_run_all(state, input)
+    }
+
+    fn post_run_all(
+        &mut self,
+        state: &mut S,
+        input: &S::Input,
+        exit_kind: &ExitKind,
+    ) -> Result<(), Error> {
+        self.0.post_run(state, input, exit_kind)?;
+        self.1.post_run_all(state, input, exit_kind)
+    }
+}
+
+/// A [`HookedExecutor`] wraps an executor, running the given [`ExecutorHooksTuple`] around each execution.
+/// The hooks run inside the observers' `pre_exec` and `post_exec`.
+#[derive(Debug)]
+pub struct HookedExecutor<E, HT> {
+    /// The wrapped executor
+    executor: E,
+    /// The hooks
+    hooks: HT,
+}
+
+impl<E, HT> HookedExecutor<E, HT>
+where
+    E: UsesState,
+    HT: ExecutorHooksTuple<E::State>,
+{
+    /// Create a new `HookedExecutor`, wrapping the given `executor`.
+    pub fn new(executor: E, hooks: HT) -> Self {
+        Self { executor, hooks }
+    }
+
+    /// The wrapped executor
+    #[inline]
+    pub fn executor(&self) -> &E {
+        &self.executor
+    }
+
+    /// The wrapped executor, mutable
+    #[inline]
+    pub fn executor_mut(&mut self) -> &mut E {
+        &mut self.executor
+    }
+
+    /// The hooks
+    #[inline]
+    pub fn hooks(&self) -> &HT {
+        &self.hooks
+    }
+
+    /// The hooks, mutable
+    #[inline]
+    pub fn hooks_mut(&mut self) -> &mut HT {
+        &mut self.hooks
+    }
+}
+
+impl<E, EM, HT, Z> Executor<EM, Z> for HookedExecutor<E, HT>
+where
+    E: Executor<EM, Z>,
+    EM: UsesState<State = E::State>,
+    HT: ExecutorHooksTuple<E::State>,
+    Z: UsesState<State = E::State>,
+{
+    fn run_target(
+        &mut self,
+        fuzzer: &mut Z,
+        state: &mut Self::State,
+        mgr: &mut EM,
+        input: &Self::Input,
+    ) -> Result<ExitKind, Error> {
+        self.hooks.pre_run_all(state, input)?;
+        let exit_kind = self.executor.run_target(fuzzer, state, mgr, input)?;
+        self.hooks.post_run_all(state, input, &exit_kind)?;
+        Ok(exit_kind)
+    }
+
+    #[inline]
+    fn post_run_reset(&mut self) {
+        self.executor.post_run_reset();
+    }
+}
+
+impl<E, HT> UsesState for HookedExecutor<E, HT>
+where
+    E: UsesState,
+{
+    type State = E::State;
+}
+
+impl<E, HT> UsesObservers for HookedExecutor<E, HT>
+where
+    E: UsesObservers,
+{
+    type Observers = E::Observers;
+}
+
+impl<E, HT> HasObservers for HookedExecutor<E, HT>
+where
+    E: HasObservers,
+{
+    #[inline]
+    fn observers(&self) -> &Self::Observers {
+        self.executor.observers()
+    }
+
+    #[inline]
+    fn observers_mut(&mut self) -> &mut Self::Observers {
+        self.executor.observers_mut()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::marker::PhantomData;
+
+    use crate::{
+        bolts::tuples::tuple_list,
+        events::NopEventManager,
+        executors::{
+            hooks::{ExecutorHook, HookedExecutor},
+            Executor, ExitKind, NopExecutor,
+        },
+        inputs::{BytesInput, HasBytesVec},
+        state::NopState,
+        Error, NopFuzzer,
+    };
+
+    /// Counts the executions and remembers the last input length
+    #[derive(Debug, Default)]
+    struct CountingHook {
+        pre_runs: usize,
+        post_runs: usize,
+        last_len: usize,
+    }
+
+    impl ExecutorHook<NopState<BytesInput>> for CountingHook {
+        fn pre_run(
+            &mut self,
+            _state: &mut NopState<BytesInput>,
+            input: &BytesInput,
+        ) -> Result<(), Error> {
+            self.pre_runs += 1;
+            self.last_len = input.bytes().len();
+            Ok(())
+        }
+
+        fn post_run(
+            &mut self,
+            _state: &mut NopState<BytesInput>,
+            _input: &BytesInput,
+            exit_kind: &ExitKind,
+        ) -> Result<(), Error> {
+            assert_eq!(*exit_kind, ExitKind::Ok);
+            self.post_runs += 1;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_hooked_executor() {
+        let mut executor = HookedExecutor::new(
+            NopExecutor::<NopState<BytesInput>> {
+                phantom: PhantomData,
+            },
+            tuple_list!(CountingHook::default()),
+        );
+        for _ in 0..2 {
+            executor
+                .run_target(
+                    &mut NopFuzzer::new(),
+                    &mut NopState::new(),
+                    &mut NopEventManager::new(),
+                    &BytesInput::new(vec![0; 3]),
+                )
+                .unwrap();
+        }
+
+        let hook = &executor.hooks().0;
+        assert_eq!(hook.pre_runs, 2);
+        assert_eq!(hook.post_runs, 2);
+        assert_eq!(hook.last_len, 3);
+    }
+}
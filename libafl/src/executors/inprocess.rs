@@ -6,8 +6,12 @@
 use alloc::boxed::Box;
 #[cfg(all(unix, feature = "std"))]
 use alloc::vec::Vec;
+#[cfg(all(feature = "std", unix))]
+use core::hash::{BuildHasher, Hasher};
 #[cfg(all(feature = "std", unix, target_os = "linux"))]
 use core::ptr::addr_of_mut;
+#[cfg(unix)]
+use core::sync::atomic::AtomicUsize;
 #[cfg(all(unix, feature = "std"))]
 use core::time::Duration;
 use core::{
@@ -24,7 +28,11 @@ use core::{
 };
 #[cfg(all(feature = "std", unix))]
 use std::intrinsics::transmute;
+#[cfg(all(feature = "std", unix))]
+use std::os::unix::io::AsRawFd;
 
+#[cfg(all(feature = "std", unix))]
+use ahash::RandomState;
 #[cfg(all(feature = "std", unix))]
 use libc::siginfo_t;
 #[cfg(all(feature = "std", unix))]
@@ -32,11 +40,15 @@ use nix::{
     sys::wait::{waitpid, WaitStatus},
     unistd::{fork, ForkResult},
 };
+#[cfg(all(feature = "std", unix))]
+use serde::Serialize;
 #[cfg(windows)]
 use windows::Win32::System::Threading::SetThreadStackGuarantee;
 
 #[cfg(all(unix, not(miri)))]
 use crate::bolts::os::unix_signals::setup_signal_handler;
+#[cfg(all(feature = "std", unix, not(miri)))]
+use crate::bolts::os::unix_signals::setup_signal_handler_for;
 #[cfg(all(feature = "std", unix))]
 use crate::bolts::os::unix_signals::{ucontext_t, Handler, Signal};
 #[cfg(all(windows, feature = "std"))]
@@ -48,7 +60,7 @@ use crate::{
     executors::{Executor, ExitKind, HasObservers},
     feedbacks::Feedback,
     fuzzer::HasObjective,
-    inputs::UsesInput,
+    inputs::{Input, UsesInput},
     observers::{ObserversTuple, UsesObservers},
     state::{HasClientPerfMonitor, HasCorpus, HasSolutions, UsesState},
     Error,
@@ -66,6 +78,11 @@ pub type OwnedInProcessExecutor<OT, S> = GenericInProcessExecutor<
 >;
 
 /// The inmem executor simply calls a target function, then returns afterwards.
+///
+/// The harness may spawn threads: crashes and timeouts in any thread are attributed to the current input.
+/// The first thread to crash reports it and exits, other threads crashing meanwhile wait for it.
+/// To catch stack overflows in these threads on unix, call `bolts::os::unix_signals::setup_signal_stack`
+/// in each of them.
 #[allow(dead_code)]
 pub struct GenericInProcessExecutor<H, HB, OT, S>
 where
@@ -213,6 +230,37 @@ where
         })
     }
 
+    /// Create a new in mem executor, only handling the given `signals`.
+    /// Crashes or timeouts raising other signals are not caught, and the handlers previously
+    /// installed for them, e.g. by the host application, are kept.
+    /// For an empty list of `signals`, no signal handler is installed at all.
+    /// * `harness_fn` - the harness, executing the function
+    /// * `observers` - the observers observing the target during execution
+    /// * `signals` - the signals to handle, see [`Handler::signals`] for the default ones
+    #[cfg(all(unix, feature = "std"))]
+    pub fn with_signals<EM, OF, Z>(
+        harness_fn: HB,
+        observers: OT,
+        signals: &[Signal],
+        _fuzzer: &mut Z,
+        _state: &mut S,
+        _event_mgr: &mut EM,
+    ) -> Result<Self, Error>
+    where
+        Self: Executor<EM, Z, State = S>,
+        EM: EventFirer<State = S> + EventRestarter,
+        OF: Feedback<S>,
+        Z: HasObjective<Objective = OF, State = S>,
+    {
+        let handlers = InProcessHandlers::with_signals::<Self, EM, OF, Z>(signals)?;
+        Ok(Self {
+            harness_fn,
+            observers,
+            handlers,
+            phantom: PhantomData,
+        })
+    }
+
     /// Retrieve the harness function.
     #[inline]
     pub fn harness(&self) -> &H {
@@ -354,6 +402,8 @@ impl InProcessHandlers {
         unsafe {
             let data = &mut GLOBAL_STATE;
             #[cfg(feature = "std")]
+            CrashDir::setup(data)?;
+            #[cfg(feature = "std")]
             unix_signal_handler::setup_panic_hook::<E, EM, OF, Z>();
             #[cfg(not(miri))]
             setup_signal_handler(data)?;
@@ -369,6 +419,33 @@ impl InProcessHandlers {
         Ok(Self {})
     }
 
+    /// Create new [`InProcessHandlers`], only handling the given `signals`.
+    #[cfg(all(unix, feature = "std"))]
+    #[cfg_attr(miri, allow(unused_variables))]
+    pub fn with_signals<E, EM, OF, Z>(signals: &[Signal]) -> Result<Self, Error>
+    where
+        E: Executor<EM, Z> + HasObservers,
+        EM: EventFirer<State = E::State> + EventRestarter<State = E::State>,
+        OF: Feedback<E::State>,
+        E::State: HasSolutions + HasClientPerfMonitor + HasCorpus,
+        Z: HasObjective<Objective = OF, State = E::State>,
+    {
+        unsafe {
+            let data = &mut GLOBAL_STATE;
+            CrashDir::setup(data)?;
+            unix_signal_handler::setup_panic_hook::<E, EM, OF, Z>();
+            #[cfg(not(miri))]
+            setup_signal_handler_for(data, signals)?;
+            compiler_fence(Ordering::SeqCst);
+            Ok(Self {
+                crash_handler: unix_signal_handler::inproc_crash_handler::<E, EM, OF, Z>
+                    as *const c_void,
+                timeout_handler: unix_signal_handler::inproc_timeout_handler::<E, EM, OF, Z>
+                    as *const _,
+            })
+        }
+    }
+
     /// Create new [`InProcessHandlers`].
     #[cfg(all(windows, feature = "std"))]
     pub fn new<E, EM, OF, Z>() -> Result<Self, Error>
@@ -423,7 +500,7 @@ pub(crate) struct InProcessExecutorHandlerData {
     executor_ptr: *const c_void,
     pub current_input_ptr: *const c_void,
 
-    /// The timeout handler
+    /// The crash handler
     #[cfg(any(unix, feature = "std"))]
     crash_handler: *const c_void,
     /// The timeout handler
@@ -441,6 +518,15 @@ pub(crate) struct InProcessExecutorHandlerData {
 
     #[cfg(any(unix, feature = "std"))]
     pub(crate) timeout_executor_ptr: *mut c_void,
+
+    /// The thread running one of our unix signal handlers, or `0`, to catch crashes inside the handler itself.
+    /// Other threads receiving a signal meanwhile, e.g. threads of a multi-threaded harness, wait for it.
+    #[cfg(unix)]
+    pub(crate) handler_thread: AtomicUsize,
+
+    /// The directory the crash handler writes crashing inputs to, see [`CRASH_DIR_ENV`]
+    #[cfg(all(unix, feature = "std"))]
+    crash_dir: Option<CrashDir>,
 }
 
 unsafe impl Send for InProcessExecutorHandlerData {}
@@ -519,11 +605,25 @@ pub(crate) static mut GLOBAL_STATE: InProcessExecutorHandlerData = InProcessExec
 
     #[cfg(any(unix, feature = "std"))]
     timeout_executor_ptr: null_mut(),
+
+    #[cfg(unix)]
+    handler_thread: AtomicUsize::new(0),
+
+    #[cfg(all(unix, feature = "std"))]
+    crash_dir: None,
 };
 
-/// Get the inprocess [`crate::state::State`]
+/// Get the inprocess [`crate::state::State`].
+/// Inside the children of the fork executors, this is the child's copy of the state.
 #[must_use]
 pub fn inprocess_get_state<'a, S>() -> Option<&'a mut S> {
+    #[cfg(all(feature = "std", unix))]
+    unsafe {
+        // Only set in forked children
+        if !FORK_EXECUTOR_GLOBAL_DATA.state_ptr.is_null() {
+            return (FORK_EXECUTOR_GLOBAL_DATA.state_ptr as *mut S).as_mut();
+        }
+    }
     unsafe { (GLOBAL_STATE.state_ptr as *mut S).as_mut() }
 }
 
@@ -545,13 +645,21 @@ pub fn inprocess_get_executor<'a, E>() -> Option<&'a mut E> {
     unsafe { (GLOBAL_STATE.executor_ptr as *mut E).as_mut() }
 }
 
-/// Gets the inprocess input
+/// Gets the inprocess input, also inside the children of the fork executors
 #[must_use]
 pub fn inprocess_get_input<'a, I>() -> Option<&'a I> {
+    #[cfg(all(feature = "std", unix))]
+    unsafe {
+        // Only set in forked children
+        if !FORK_EXECUTOR_GLOBAL_DATA.current_input_ptr.is_null() {
+            return (FORK_EXECUTOR_GLOBAL_DATA.current_input_ptr as *const I).as_ref();
+        }
+    }
     unsafe { (GLOBAL_STATE.current_input_ptr as *const I).as_ref() }
 }
 
 use crate::{
+    bolts::current_time,
     corpus::{Corpus, Testcase},
     events::Event,
     state::HasMetadata,
@@ -593,15 +701,24 @@ pub fn run_observers_and_save_state<E, EM, OF, Z>(
             .objective_mut()
             .append_metadata(state, observers, &mut new_testcase)
             .expect("Failed adding metadata");
-        state
+        let idx = state
             .solutions_mut()
             .add(new_testcase)
             .expect("In run_observers_and_save_state solutions failure.");
+        let solution_name = state
+            .solutions()
+            .get(idx)
+            .ok()
+            .and_then(|solution| solution.borrow().filename().clone())
+            .unwrap_or_else(|| input.generate_name(idx.0));
         event_mgr
             .fire(
                 state,
                 Event::Objective {
                     objective_size: state.solutions().count(),
+                    solution_name,
+                    exit_kind: exitkind,
+                    time: current_time(),
                 },
             )
             .expect("Could not save state in run_observers_and_save_state");
@@ -615,24 +732,148 @@ pub fn run_observers_and_save_state<E, EM, OF, Z>(
     log::info!("Bye!");
 }
 
+/// If this `env` variable is set, the unix crash handler writes each crashing input into the
+/// directory it names before exiting, serialized like [`crate::inputs::Input::to_file`],
+/// and named after the hash of its contents.
+/// The directory is created and opened when the handlers are set up.
+/// Inputs larger than [`CRASH_INPUT_MAX_SIZE`] serialized are not written.
+#[cfg(all(unix, feature = "std"))]
+pub const CRASH_DIR_ENV: &str = "LIBAFL_CRASH_DIR";
+
+/// The maximum size of a serialized input the crash handler writes to the [`CRASH_DIR_ENV`] directory
+#[cfg(all(unix, feature = "std"))]
+pub const CRASH_INPUT_MAX_SIZE: usize = 1 << 20;
+
+/// The crash directory, opened up front, since the crash handler can't safely allocate or open paths
+#[cfg(all(unix, feature = "std"))]
+#[derive(Debug)]
+pub(crate) struct CrashDir {
+    /// The opened directory
+    dir: std::fs::File,
+    /// The buffer crashing inputs are serialized into
+    buf: Vec<u8>,
+}
+
+#[cfg(all(unix, feature = "std"))]
+impl CrashDir {
+    /// Creates and opens the directory at `path`
+    fn open<P>(path: P) -> Result<Self, Error>
+    where
+        P: AsRef<std::path::Path>,
+    {
+        std::fs::create_dir_all(&path)?;
+        Ok(Self {
+            dir: std::fs::File::open(path)?,
+            buf: vec![0; CRASH_INPUT_MAX_SIZE],
+        })
+    }
+
+    /// Opens the directory named by [`CRASH_DIR_ENV`] for the crash handler, if set
+    fn setup(data: &mut InProcessExecutorHandlerData) -> Result<(), Error> {
+        if let Ok(path) = std::env::var(CRASH_DIR_ENV) {
+            data.crash_dir = Some(Self::open(path)?);
+        }
+        Ok(())
+    }
+
+    /// Writes `input` into this directory, using raw `openat`/`write` calls only,
+    /// so that it's safe to call from the crash handler.
+    /// Returns `false` if the input could not be written.
+    unsafe fn write_input<I>(&mut self, input: &I) -> bool
+    where
+        I: Serialize,
+    {
+        const HEX: &[u8; 16] = b"0123456789abcdef";
+
+        let Ok(bytes) = postcard::to_slice(input, &mut self.buf) else {
+            return false;
+        };
+        let mut hasher = RandomState::with_seeds(0, 0, 0, 0).build_hasher();
+        hasher.write(bytes);
+        let hash = hasher.finish();
+
+        let mut name = *b"crash-0000000000000000\0";
+        for (i, c) in name[6..22].iter_mut().enumerate() {
+            *c = HEX[((hash >> (60 - 4 * i)) & 0xf) as usize];
+        }
+
+        let fd = libc::openat(
+            self.dir.as_raw_fd(),
+            name.as_ptr().cast(),
+            libc::O_WRONLY | libc::O_CREAT | libc::O_TRUNC | libc::O_CLOEXEC,
+            0o644 as libc::c_uint,
+        );
+        if fd < 0 {
+            return false;
+        }
+        let mut written = 0;
+        while written < bytes.len() {
+            let ret = libc::write(fd, bytes[written..].as_ptr().cast(), bytes.len() - written);
+            if ret < 0 && nix::errno::errno() == libc::EINTR {
+                continue;
+            }
+            match usize::try_from(ret) {
+                Ok(0) | Err(_) => break,
+                Ok(len) => written += len,
+            }
+        }
+        libc::close(fd);
+        written == bytes.len()
+    }
+}
+
+/// A function escaping from the crash handler back to a jump point recorded in the harness,
+/// for example using `siglongjmp`. It gets the signal number, and returns only if no
+/// jump point is recorded.
+#[cfg(unix)]
+pub type CrashRecoveryFn = unsafe extern "C" fn(libc::c_int);
+
+/// The function to escape from the crash handler, see [`set_crash_recovery`]
+#[cfg(unix)]
+static mut CRASH_RECOVERY: Option<CrashRecoveryFn> = None;
+
+/// Let the unix crash handler call `recovery` instead of exiting if a crash happens inside the target.
+/// If `recovery` jumps back into the harness, the harness can return [`ExitKind::Crash`] and
+/// the fuzzer keeps running in the same process.
+/// This is only sound if the target state is recoverable, and if the frames that are jumped
+/// over have nothing to drop.
+/// Crashes caught through the Mach exception port on `MacOS` are never recovered from,
+/// since their handler doesn't run on the crashing thread.
+///
+/// # Safety
+///
+/// `recovery` is called from the signal handler, and must be async-signal-safe.
+#[cfg(unix)]
+pub unsafe fn set_crash_recovery(recovery: Option<CrashRecoveryFn>) {
+    write_volatile(&mut CRASH_RECOVERY, recovery);
+}
+
 #[cfg(unix)]
 mod unix_signal_handler {
-    use alloc::vec::Vec;
     #[cfg(feature = "std")]
-    use alloc::{boxed::Box, string::String};
-    use core::mem::transmute;
+    use alloc::boxed::Box;
+    use alloc::vec::Vec;
+    use core::{
+        fmt::Write,
+        mem::transmute,
+        ptr::addr_of_mut,
+        sync::atomic::{AtomicUsize, Ordering},
+    };
     #[cfg(feature = "std")]
-    use std::{io::Write, panic};
+    use std::panic;
 
     use libc::siginfo_t;
 
-    #[cfg(feature = "std")]
-    use crate::inputs::Input;
     use crate::{
-        bolts::os::unix_signals::{ucontext_t, Handler, Signal},
+        bolts::os::unix_signals::{
+            call_previous_handler, ucontext_t, Handler, Signal, SignalSafeWriter,
+        },
         events::{EventFirer, EventRestarter},
         executors::{
-            inprocess::{run_observers_and_save_state, InProcessExecutorHandlerData, GLOBAL_STATE},
+            inprocess::{
+                run_observers_and_save_state, InProcessExecutorHandlerData, CRASH_RECOVERY,
+                GLOBAL_STATE,
+            },
             Executor, ExitKind, HasObservers,
         },
         feedbacks::Feedback,
@@ -644,6 +885,15 @@ mod unix_signal_handler {
     pub(crate) type HandlerFuncPtr =
         unsafe fn(Signal, siginfo_t, &mut ucontext_t, data: &mut InProcessExecutorHandlerData);
 
+    /// The handlers format their messages into this buffer, instead of logging, which may allocate or lock.
+    /// Only the thread in `handler_thread` uses it.
+    static mut SIGNAL_WRITER: SignalSafeWriter = SignalSafeWriter::stderr();
+
+    /// The [`SignalSafeWriter`] of the thread handling a signal
+    unsafe fn signal_writer() -> &'static mut SignalSafeWriter {
+        &mut *addr_of_mut!(SIGNAL_WRITER)
+    }
+
     /// A handler that does nothing.
     /*pub fn nop_handler(
         _signal: Signal,
@@ -653,11 +903,29 @@ mod unix_signal_handler {
     ) {
     }*/
 
+    /// Marks the current thread as the one handling a signal, in `handler_thread`.
+    /// Waits for other threads to finish handling theirs first; they usually exit the process.
+    unsafe fn enter_handler(handler_thread: &AtomicUsize, signal: Signal) {
+        let current = libc::pthread_self() as usize;
+        while let Err(owner) =
+            handler_thread.compare_exchange(0, current, Ordering::SeqCst, Ordering::SeqCst)
+        {
+            if owner == current {
+                // We crashed while handling a signal. Don't touch any state, just leave.
+                let msg = b"LibAFL: signal received inside the signal handler, exiting\n";
+                libc::write(libc::STDERR_FILENO, msg.as_ptr().cast(), msg.len());
+                libc::_exit(128 + (signal as i32));
+            }
+            libc::sched_yield();
+        }
+    }
+
     #[cfg(unix)]
     impl Handler for InProcessExecutorHandlerData {
         fn handle(&mut self, signal: Signal, info: siginfo_t, context: &mut ucontext_t) {
             unsafe {
                 let data = &mut GLOBAL_STATE;
+                enter_handler(&data.handler_thread, signal);
                 match signal {
                     Signal::SigUser2 | Signal::SigAlarm => {
                         if !data.timeout_handler.is_null() {
@@ -672,6 +940,7 @@ mod unix_signal_handler {
                         }
                     }
                 }
+                data.handler_thread.store(0, Ordering::SeqCst);
             }
         }
 
@@ -747,8 +1016,13 @@ mod unix_signal_handler {
             return;
         }
 
+        let writer = signal_writer();
         if !data.is_valid() {
-            log::warn!("TIMEOUT or SIGUSR2 happened, but currently not fuzzing.");
+            let _ = writeln!(
+                writer,
+                "LibAFL: TIMEOUT or SIGUSR2 happened, but currently not fuzzing."
+            );
+            writer.flush_buf();
             return;
         }
 
@@ -758,7 +1032,8 @@ mod unix_signal_handler {
         let fuzzer = data.fuzzer_mut::<Z>();
         let input = data.take_current_input::<<E::State as UsesInput>::Input>();
 
-        log::error!("Timeout in fuzz run.");
+        let _ = writeln!(writer, "LibAFL: Timeout in fuzz run.");
+        writer.flush_buf();
 
         run_observers_and_save_state::<E, EM, OF, Z>(
             executor,
@@ -794,8 +1069,25 @@ mod unix_signal_handler {
         let _context = &mut *(((_context as *mut _ as *mut libc::c_void as usize) + 128)
             as *mut libc::c_void as *mut ucontext_t);
 
-        log::error!("Crashed with {signal}");
+        let writer = signal_writer();
+        let _ = writeln!(writer, "LibAFL: Crashed with {signal}");
+        writer.flush_buf();
         if data.is_valid() {
+            // Mach exceptions are handled on a thread of their own, which can't jump back
+            // onto the stack of the crashing thread
+            #[cfg(all(target_vendor = "apple", feature = "std"))]
+            let recoverable = !crate::bolts::os::mach_exceptions::on_exception_thread();
+            #[cfg(not(all(target_vendor = "apple", feature = "std")))]
+            let recoverable = true;
+            if recoverable {
+                if let Some(recovery) = CRASH_RECOVERY {
+                    // Jump back into the harness, which reports the crash
+                    data.handler_thread.store(0, Ordering::SeqCst);
+                    recovery(signal as libc::c_int);
+                    enter_handler(&data.handler_thread, signal);
+                }
+            }
+
             let executor = data.executor_mut::<E>();
             // disarms timeout in case of TimeoutExecutor
             executor.post_run_reset();
@@ -804,20 +1096,25 @@ mod unix_signal_handler {
             let fuzzer = data.fuzzer_mut::<Z>();
             let input = data.take_current_input::<<E::State as UsesInput>::Input>();
 
-            log::error!("Child crashed!");
+            let _ = writeln!(writer, "LibAFL: Child crashed!");
 
-            #[cfg(all(feature = "std", unix))]
+            #[cfg(feature = "std")]
             {
-                let mut bsod = Vec::new();
-                {
-                    let mut writer = std::io::BufWriter::new(&mut bsod);
-                    writeln!(writer, "input: {:?}", input.generate_name(0)).unwrap();
-                    crate::bolts::minibsod::generate_minibsod(&mut writer, signal, _info, _context)
-                        .unwrap();
-                    writer.flush().unwrap();
+                let _ = crate::bolts::minibsod::generate_minibsod_signal_safe(
+                    writer, signal, _info, _context,
+                );
+
+                // Keep the input around even if we never make it back to the restarting manager.
+                if let Some(crash_dir) = data.crash_dir.as_mut() {
+                    if !crash_dir.write_input(input) {
+                        let _ = writeln!(
+                            writer,
+                            "LibAFL: could not write the crashing input to the crash directory"
+                        );
+                    }
                 }
-                log::error!("{}", std::str::from_utf8(&bsod).unwrap());
             }
+            writer.flush_buf();
 
             run_observers_and_save_state::<E, EM, OF, Z>(
                 executor,
@@ -828,47 +1125,29 @@ mod unix_signal_handler {
                 ExitKind::Crash,
             );
         } else {
-            {
-                log::error!("Double crash\n");
-                #[cfg(target_os = "android")]
-                let si_addr = (_info._pad[0] as i64) | ((_info._pad[1] as i64) << 32);
-                #[cfg(not(target_os = "android"))]
-                let si_addr = { _info.si_addr() as usize };
-
-                log::error!(
-                "We crashed at addr 0x{si_addr:x}, but are not in the target... Bug in the fuzzer? Exiting."
-                );
-
-                #[cfg(all(feature = "std", unix))]
-                {
-                    let mut bsod = Vec::new();
-                    {
-                        let mut writer = std::io::BufWriter::new(&mut bsod);
-                        crate::bolts::minibsod::generate_minibsod(
-                            &mut writer,
-                            signal,
-                            _info,
-                            _context,
-                        )
-                        .unwrap();
-                        writer.flush().unwrap();
-                    }
-                    log::error!("{}", std::str::from_utf8(&bsod).unwrap());
-                }
-            }
+            #[cfg(target_os = "android")]
+            let si_addr = (_info._pad[0] as i64) | ((_info._pad[1] as i64) << 32);
+            #[cfg(not(target_os = "android"))]
+            let si_addr = { _info.si_addr() as usize };
+
+            let _ = writeln!(
+                writer,
+                "LibAFL: Double crash\nWe crashed at addr 0x{si_addr:x}, but are not in the target... Bug in the fuzzer? Exiting."
+            );
 
             #[cfg(feature = "std")]
-            {
-                log::error!("Type QUIT to restart the child");
-                let mut line = String::new();
-                while line.trim() != "QUIT" {
-                    std::io::stdin().read_line(&mut line).unwrap();
-                }
-            }
+            let _ = crate::bolts::minibsod::generate_minibsod_signal_safe(
+                writer, signal, _info, _context,
+            );
+            writer.flush_buf();
 
             // TODO tell the parent to not restart
         }
 
+        // Give previously installed handlers, e.g. the host application's, a chance to run
+        let mut info = _info;
+        call_previous_handler(signal, &mut info, (_context as *mut ucontext_t).cast());
+
         libc::_exit(128 + (signal as i32));
     }
 }
@@ -1510,6 +1789,16 @@ extern "C" {
 #[cfg(all(feature = "std", unix, not(target_os = "linux")))]
 const ITIMER_REAL: libc::c_int = 0;
 
+/// The exit code a forked child uses to hand the [`ExitKind`] returned by the harness to the parent.
+#[cfg(all(feature = "std", unix))]
+fn child_exit_code(exit_kind: ExitKind) -> i32 {
+    match exit_kind {
+        ExitKind::Ok => 0,
+        ExitKind::Timeout => 128 + libc::SIGALRM,
+        ExitKind::Crash | ExitKind::Oom | ExitKind::Diff { .. } => 128 + libc::SIGABRT,
+    }
+}
+
 /// [`InProcessForkExecutor`] is an executor that forks the current process before each execution.
 #[cfg(all(feature = "std", unix))]
 pub struct InProcessForkExecutor<'a, H, OT, S, SP>
@@ -1645,13 +1934,13 @@ where
                         .pre_exec_child_all(state, input)
                         .expect("Failed to run post_exec on observers");
 
-                    (self.harness_fn)(input);
+                    let exit_kind = (self.harness_fn)(input);
 
                     self.observers
-                        .post_exec_child_all(state, input, &ExitKind::Ok)
+                        .post_exec_child_all(state, input, &exit_kind)
                         .expect("Failed to run post_exec on observers");
 
-                    libc::_exit(0);
+                    libc::_exit(child_exit_code(exit_kind));
 
                     Ok(ExitKind::Ok)
                 }
@@ -1665,7 +1954,10 @@ where
                     match res {
                         WaitStatus::Signaled(_, _, _) => Ok(ExitKind::Crash),
                         WaitStatus::Exited(_, code) => {
-                            if code > 128 && code < 160 {
+                            if code == 128 + libc::SIGALRM {
+                                // The harness itself reported a timeout
+                                Ok(ExitKind::Timeout)
+                            } else if code > 128 && code < 160 {
                                 // Signal exit codes
                                 Ok(ExitKind::Crash)
                             } else {
@@ -1737,13 +2029,13 @@ where
                         setitimer(ITIMER_REAL, &mut self.itimerval, null_mut());
                     }
                     // log::trace!("{v:#?} {}", nix::errno::errno());
-                    (self.harness_fn)(input);
+                    let exit_kind = (self.harness_fn)(input);
 
                     self.observers
-                        .post_exec_child_all(state, input, &ExitKind::Ok)
+                        .post_exec_child_all(state, input, &exit_kind)
                         .expect("Failed to run post_exec on observers");
 
-                    libc::_exit(0);
+                    libc::_exit(child_exit_code(exit_kind));
 
                     Ok(ExitKind::Ok)
                 }
@@ -2126,6 +2418,43 @@ mod tests {
             .unwrap();
     }
 
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    #[cfg(all(feature = "std", unix))]
+    fn test_crash_dir_write_input() {
+        use alloc::vec::Vec;
+
+        use crate::{
+            executors::inprocess::{CrashDir, CRASH_INPUT_MAX_SIZE},
+            inputs::{BytesInput, Input},
+        };
+
+        let path = std::env::temp_dir().join("libafl_test_crash_dir");
+        let _ = std::fs::remove_dir_all(&path);
+        let mut crash_dir = CrashDir::open(&path).unwrap();
+
+        let input = BytesInput::new(vec![1, 2, 3]);
+        assert!(unsafe { crash_dir.write_input(&input) });
+        let files: Vec<_> = std::fs::read_dir(&path)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .collect();
+        assert_eq!(files.len(), 1);
+        assert!(files[0]
+            .file_name()
+            .unwrap()
+            .to_string_lossy()
+            .starts_with("crash-"));
+        assert_eq!(BytesInput::from_file(&files[0]).unwrap(), input);
+
+        // Inputs that don't fit into the buffer are not written
+        let large = BytesInput::new(vec![0; CRASH_INPUT_MAX_SIZE]);
+        assert!(!unsafe { crash_dir.write_input(&large) });
+        assert_eq!(std::fs::read_dir(&path).unwrap().count(), 1);
+
+        std::fs::remove_dir_all(&path).unwrap();
+    }
+
     #[test]
     #[serial]
     #[cfg_attr(miri, ignore)]
@@ -2153,9 +2482,28 @@ mod tests {
         let mut fuzzer = NopFuzzer::new();
         let mut state = NopState::new();
         let mut mgr = SimpleEventManager::printing();
-        in_process_fork_executor
-            .run_target(&mut fuzzer, &mut state, &mut mgr, &input)
-            .unwrap();
+        assert_eq!(
+            in_process_fork_executor
+                .run_target(&mut fuzzer, &mut state, &mut mgr, &input)
+                .unwrap(),
+            ExitKind::Ok
+        );
+
+        // The exit kind the harness returns in the child has to make it to the parent
+        let mut crashing_harness = |_buf: &NopInput| ExitKind::Crash;
+        let mut in_process_fork_executor = InProcessForkExecutor::<_, (), _, _> {
+            harness_fn: &mut crashing_harness,
+            shmem_provider: StdShMemProvider::new().unwrap(),
+            observers: tuple_list!(),
+            handlers: InChildProcessHandlers::nop(),
+            phantom: PhantomData,
+        };
+        assert_eq!(
+            in_process_fork_executor
+                .run_target(&mut fuzzer, &mut state, &mut mgr, &input)
+                .unwrap(),
+            ExitKind::Crash
+        );
     }
 }
 
@@ -2169,7 +2517,11 @@ pub mod pybind {
 
     use crate::{
         events::pybind::PythonEventManager,
-        executors::{inprocess::OwnedInProcessExecutor, pybind::PythonExecutor, ExitKind},
+        executors::{
+            inprocess::OwnedInProcessExecutor,
+            pybind::{PythonExecutor, PythonExitKind},
+            ExitKind,
+        },
         fuzzer::pybind::PythonStdFuzzerWrapper,
         inputs::{BytesInput, HasBytesVec},
         observers::pybind::PythonObserversTuple,
@@ -2197,13 +2549,17 @@ pub mod pybind {
             Self {
                 inner: OwnedInProcessExecutor::new(
                     Box::new(move |input: &BytesInput| {
-                        Python::with_gil(|py| -> PyResult<()> {
+                        Python::with_gil(|py| -> PyResult<ExitKind> {
                             let args = (PyBytes::new(py, input.bytes()),);
-                            harness.call1(py, args)?;
-                            Ok(())
+                            let ret = harness.call1(py, args)?;
+                            // Harnesses that return nothing are assumed to have run fine
+                            if ret.is_none(py) {
+                                return Ok(ExitKind::Ok);
+                            }
+                            let ek: PythonExitKind = ret.extract(py)?;
+                            Ok(ek.inner)
                         })
-                        .unwrap();
-                        ExitKind::Ok
+                        .unwrap()
                     }),
                     py_observers,
                     py_fuzzer.unwrap_mut(),
@@ -20,18 +20,42 @@ pub mod forkserver;
 #[cfg(all(feature = "std", feature = "fork", unix))]
 pub use forkserver::{Forkserver, ForkserverExecutor, TimeoutForkserverExecutor};
 
+#[cfg(all(feature = "std", feature = "fork", unix))]
+pub mod snapshot;
+#[cfg(all(feature = "std", feature = "fork", unix))]
+pub use snapshot::SnapshotForkExecutor;
+
+#[cfg(all(feature = "std", windows))]
+pub mod windows_persistent;
+#[cfg(all(feature = "std", windows))]
+pub use windows_persistent::WindowsPersistentExecutor;
+
+#[cfg(feature = "std")]
+pub mod threaded;
+#[cfg(feature = "std")]
+pub use threaded::ThreadedExecutor;
+
 pub mod combined;
 pub use combined::CombinedExecutor;
 
 pub mod shadow;
 pub use shadow::ShadowExecutor;
 
+pub mod post_process;
+pub use post_process::{InputPostProcessor, PostProcessExecutor};
+
+pub mod hooks;
+pub use hooks::{ExecutorHook, ExecutorHooksTuple, HookedExecutor};
+
 pub mod with_observers;
 pub use with_observers::WithObservers;
 
 #[cfg(all(feature = "std", any(unix, doc)))]
 pub mod command;
-use core::{fmt::Debug, marker::PhantomData};
+use core::{
+    fmt::{self, Debug, Display, Formatter},
+    marker::PhantomData,
+};
 
 #[cfg(all(feature = "std", any(unix, doc)))]
 pub use command::CommandExecutor;
@@ -86,6 +110,32 @@ pub enum DiffExitKind {
 
 crate::impl_serdeany!(ExitKind);
 
+impl Display for ExitKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            ExitKind::Ok => write!(f, "ok"),
+            ExitKind::Crash => write!(f, "crash"),
+            ExitKind::Oom => write!(f, "oom"),
+            ExitKind::Timeout => write!(f, "timeout"),
+            ExitKind::Diff { primary, secondary } => {
+                write!(f, "diff (primary: {primary}, secondary: {secondary})")
+            }
+        }
+    }
+}
+
+impl Display for DiffExitKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            DiffExitKind::Ok => write!(f, "ok"),
+            DiffExitKind::Crash => write!(f, "crash"),
+            DiffExitKind::Oom => write!(f, "oom"),
+            DiffExitKind::Timeout => write!(f, "timeout"),
+            DiffExitKind::Diff => write!(f, "diff"),
+        }
+    }
+}
+
 impl From<ExitKind> for DiffExitKind {
     fn from(exitkind: ExitKind) -> Self {
         match exitkind {
@@ -144,10 +194,20 @@ where
 /// A simple executor that does nothing.
 /// If intput len is 0, `run_target` will return Err
 #[derive(Debug)]
-struct NopExecutor<S> {
+pub(crate) struct NopExecutor<S> {
     phantom: PhantomData<S>,
 }
 
+#[cfg(test)]
+impl<S> NopExecutor<S> {
+    /// Creates a new [`NopExecutor`]
+    pub(crate) fn new() -> Self {
+        Self {
+            phantom: PhantomData,
+        }
+    }
+}
+
 impl<S> UsesState for NopExecutor<S>
 where
     S: UsesInput,
@@ -181,7 +241,7 @@ where
 mod test {
     use core::marker::PhantomData;
 
-    use super::{Executor, NopExecutor};
+    use super::{DiffExitKind, Executor, ExitKind, NopExecutor};
     use crate::{events::NopEventManager, inputs::BytesInput, state::NopState, NopFuzzer};
 
     #[test]
@@ -212,6 +272,17 @@ mod test {
             )
             .unwrap();
     }
+
+    #[test]
+    fn test_exit_kind_display() {
+        assert_eq!(format!("{}", ExitKind::Timeout), "timeout");
+        let diff = ExitKind::Diff {
+            primary: ExitKind::Ok.into(),
+            secondary: ExitKind::Crash.into(),
+        };
+        assert_eq!(format!("{diff}"), "diff (primary: ok, secondary: crash)");
+        assert_eq!(DiffExitKind::from(diff), DiffExitKind::Diff);
+    }
 }
 
 #[cfg(feature = "python")]
@@ -0,0 +1,172 @@
+//! A `PostProcessExecutor` wraps an executor and fixes up each input right before it is executed,
+//! e.g. to recompute checksums, length fields or signatures the mutators broke.
+//! The corpus keeps the raw, unprocessed inputs.
+
+use core::fmt::{self, Debug, Formatter};
+
+use crate::{
+    executors::{Executor, ExitKind, HasObservers},
+    observers::UsesObservers,
+    state::UsesState,
+    Error,
+};
+
+/// Fixes up an input before it is handed to the target
+pub trait InputPostProcessor<I> {
+    /// Post-process the `input`, right before it is executed
+    fn post_process(&mut self, input: &mut I) -> Result<(), Error>;
+}
+
+impl<F, I> InputPostProcessor<I> for F
+where
+    F: FnMut(&mut I) -> Result<(), Error>,
+{
+    fn post_process(&mut self, input: &mut I) -> Result<(), Error> {
+        self(input)
+    }
+}
+
+/// A [`PostProcessExecutor`] wraps an executor, passing it post-processed copies of the inputs.
+/// Feedbacks and the corpus still see the original input.
+pub struct PostProcessExecutor<E, PP> {
+    /// The wrapped executor
+    executor: E,
+    /// The post-processor, run before each execution
+    post_processor: PP,
+}
+
+impl<E, PP> Debug for PostProcessExecutor<E, PP>
+where
+    E: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PostProcessExecutor")
+            .field("executor", &self.executor)
+            .field("post_processor", &"<fn>")
+            .finish()
+    }
+}
+
+impl<E, PP> PostProcessExecutor<E, PP>
+where
+    E: UsesState,
+    PP: InputPostProcessor<E::Input>,
+{
+    /// Create a new `PostProcessExecutor`, wrapping the given `executor`.
+    pub fn new(executor: E, post_processor: PP) -> Self {
+        Self {
+            executor,
+            post_processor,
+        }
+    }
+
+    /// The wrapped executor
+    #[inline]
+    pub fn executor(&self) -> &E {
+        &self.executor
+    }
+
+    /// The wrapped executor, mutable
+    #[inline]
+    pub fn executor_mut(&mut self) -> &mut E {
+        &mut self.executor
+    }
+
+    /// The post-processor
+    #[inline]
+    pub fn post_processor_mut(&mut self) -> &mut PP {
+        &mut self.post_processor
+    }
+}
+
+impl<E, EM, PP, Z> Executor<EM, Z> for PostProcessExecutor<E, PP>
+where
+    E: Executor<EM, Z>,
+    EM: UsesState<State = E::State>,
+    PP: InputPostProcessor<E::Input>,
+    Z: UsesState<State = E::State>,
+{
+    fn run_target(
+        &mut self,
+        fuzzer: &mut Z,
+        state: &mut Self::State,
+        mgr: &mut EM,
+        input: &Self::Input,
+    ) -> Result<ExitKind, Error> {
+        let mut input = input.clone();
+        self.post_processor.post_process(&mut input)?;
+        self.executor.run_target(fuzzer, state, mgr, &input)
+    }
+
+    #[inline]
+    fn post_run_reset(&mut self) {
+        self.executor.post_run_reset();
+    }
+}
+
+impl<E, PP> UsesState for PostProcessExecutor<E, PP>
+where
+    E: UsesState,
+{
+    type State = E::State;
+}
+
+impl<E, PP> UsesObservers for PostProcessExecutor<E, PP>
+where
+    E: UsesObservers,
+{
+    type Observers = E::Observers;
+}
+
+impl<E, PP> HasObservers for PostProcessExecutor<E, PP>
+where
+    E: HasObservers,
+{
+    #[inline]
+    fn observers(&self) -> &Self::Observers {
+        self.executor.observers()
+    }
+
+    #[inline]
+    fn observers_mut(&mut self) -> &mut Self::Observers {
+        self.executor.observers_mut()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::marker::PhantomData;
+
+    use crate::{
+        events::NopEventManager,
+        executors::{post_process::PostProcessExecutor, Executor, NopExecutor},
+        inputs::{BytesInput, HasBytesVec},
+        state::NopState,
+        Error, NopFuzzer,
+    };
+
+    #[test]
+    fn test_post_process_executor() {
+        let mut executor = PostProcessExecutor::new(
+            NopExecutor::<NopState<BytesInput>> {
+                phantom: PhantomData,
+            },
+            |input: &mut BytesInput| -> Result<(), Error> {
+                // The NopExecutor fails on empty inputs
+                input.bytes_mut().push(0);
+                Ok(())
+            },
+        );
+        let input = BytesInput::new(vec![]);
+        executor
+            .run_target(
+                &mut NopFuzzer::new(),
+                &mut NopState::new(),
+                &mut NopEventManager::new(),
+                &input,
+            )
+            .unwrap();
+        // Only the executed copy is post-processed
+        assert!(input.bytes().is_empty());
+    }
+}
@@ -43,7 +43,7 @@ where
         }
     }
 
-    /// The shadow observers are not considered by the feedbacks and the manager, mutable
+    /// The shadow observers are not considered by the feedbacks and the manager
     #[inline]
     pub fn shadow_observers(&self) -> &SOT {
         &self.shadow_observers
@@ -72,6 +72,11 @@ where
     ) -> Result<ExitKind, Error> {
         self.executor.run_target(fuzzer, state, mgr, input)
     }
+
+    #[inline]
+    fn post_run_reset(&mut self) {
+        self.executor.post_run_reset();
+    }
 }
 
 impl<E, SOT> UsesState for ShadowExecutor<E, SOT>
@@ -0,0 +1,450 @@
+//! The [`SnapshotForkExecutor`] forks a child process once, and runs batches of inputs in it.
+//! The fuzzer process never runs the harness, so it stays a pristine snapshot of the target
+//! right after initialization, and a new child is forked from it whenever the old one may
+//! have corrupted its global state.
+
+use alloc::vec::Vec;
+use core::{
+    fmt::{self, Debug, Formatter},
+    marker::PhantomData,
+};
+use std::{
+    io::{Read, Write},
+    panic::{self, AssertUnwindSafe},
+};
+
+use nix::{
+    sys::{
+        signal::{kill, Signal},
+        wait::{waitpid, WaitStatus},
+    },
+    unistd::{fork, ForkResult, Pid},
+};
+
+use crate::{
+    bolts::{os::pipes::Pipe, shmem::ShMemProvider},
+    events::{EventFirer, EventRestarter},
+    executors::{
+        inprocess::{child_signal_handlers, InChildProcessHandlers},
+        Executor, ExitKind, HasObservers,
+    },
+    feedbacks::Feedback,
+    fuzzer::HasObjective,
+    inputs::UsesInput,
+    observers::{ObserversTuple, UsesObservers},
+    state::{HasClientPerfMonitor, HasSolutions, UsesState},
+    Error,
+};
+
+/// Writes `bytes` to the `pipe`, prefixed with their length
+fn write_frame(pipe: &mut Pipe, bytes: &[u8]) -> Result<(), Error> {
+    let len = u32::try_from(bytes.len())
+        .map_err(|_| Error::illegal_argument("Input too large for the snapshot child"))?;
+    pipe.write_all(&len.to_le_bytes())?;
+    pipe.write_all(bytes)?;
+    Ok(())
+}
+
+/// Reads bytes written by [`write_frame`] from the `pipe`
+fn read_frame(pipe: &mut Pipe) -> Result<Vec<u8>, Error> {
+    let mut len = [0; 4];
+    pipe.read_exact(&mut len)?;
+    let mut bytes = vec![0; u32::from_le_bytes(len) as usize];
+    pipe.read_exact(&mut bytes)?;
+    Ok(bytes)
+}
+
+/// The currently running snapshot child
+#[derive(Debug)]
+struct SnapshotChild {
+    pid: Pid,
+    /// Sends the inputs to the child
+    input_pipe: Pipe,
+    /// Receives the [`ExitKind`]s from the child
+    result_pipe: Pipe,
+}
+
+/// [`SnapshotForkExecutor`] is an executor for stateful in-process targets.
+///
+/// It forks a child once, which then runs up to `execs_per_snapshot` inputs before it is
+/// replaced by a fresh fork of the fuzzer process. Children that did not exit with
+/// [`ExitKind::Ok`] are replaced right away, call [`SnapshotForkExecutor::refork`] to replace
+/// them on other occasions, for example after an objective.
+/// Like for the [`crate::executors::InProcessForkExecutor`], the observers need to live in shared memory.
+/// There is no timeout handling, the harness needs to return in time.
+pub struct SnapshotForkExecutor<'a, H, OT, S, SP>
+where
+    H: FnMut(&S::Input) -> ExitKind + ?Sized,
+    OT: ObserversTuple<S>,
+    S: UsesInput,
+    SP: ShMemProvider,
+{
+    harness_fn: &'a mut H,
+    shmem_provider: SP,
+    observers: OT,
+    handlers: InChildProcessHandlers,
+    child: Option<SnapshotChild>,
+    execs_per_snapshot: usize,
+    /// The executions of the current child
+    execs: usize,
+    phantom: PhantomData<S>,
+}
+
+impl<'a, H, OT, S, SP> Debug for SnapshotForkExecutor<'a, H, OT, S, SP>
+where
+    H: FnMut(&S::Input) -> ExitKind + ?Sized,
+    OT: ObserversTuple<S>,
+    S: UsesInput,
+    SP: ShMemProvider,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SnapshotForkExecutor")
+            .field("observers", &self.observers)
+            .field("shmem_provider", &self.shmem_provider)
+            .field("child", &self.child)
+            .field("execs_per_snapshot", &self.execs_per_snapshot)
+            .field("execs", &self.execs)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<'a, H, OT, S, SP> SnapshotForkExecutor<'a, H, OT, S, SP>
+where
+    H: FnMut(&S::Input) -> ExitKind + ?Sized,
+    OT: ObserversTuple<S>,
+    S: UsesInput,
+    SP: ShMemProvider,
+{
+    /// Creates a new [`SnapshotForkExecutor`], running `execs_per_snapshot` inputs in each child.
+    pub fn new<EM, OF, Z>(
+        harness_fn: &'a mut H,
+        observers: OT,
+        _fuzzer: &mut Z,
+        _state: &mut S,
+        _event_mgr: &mut EM,
+        shmem_provider: SP,
+        execs_per_snapshot: usize,
+    ) -> Result<Self, Error>
+    where
+        EM: EventFirer<State = S> + EventRestarter,
+        OF: Feedback<S>,
+        S: HasSolutions + HasClientPerfMonitor,
+        Z: HasObjective<Objective = OF, State = S>,
+    {
+        if execs_per_snapshot == 0 {
+            return Err(Error::illegal_argument(
+                "SnapshotForkExecutor needs to run at least one input per snapshot",
+            ));
+        }
+        let handlers = InChildProcessHandlers::new::<Self>()?;
+        Ok(Self {
+            harness_fn,
+            shmem_provider,
+            observers,
+            handlers,
+            child: None,
+            execs_per_snapshot,
+            execs: 0,
+            phantom: PhantomData,
+        })
+    }
+
+    /// Retrieve the harness function.
+    #[inline]
+    pub fn harness(&self) -> &H {
+        self.harness_fn
+    }
+
+    /// Retrieve the harness function for a mutable reference.
+    #[inline]
+    pub fn harness_mut(&mut self) -> &mut H {
+        self.harness_fn
+    }
+
+    /// Stop the current child, so that the next input runs in a fresh fork of the fuzzer process.
+    pub fn refork(&mut self) {
+        if let Some(child) = self.child.take() {
+            let _: Result<(), nix::Error> = kill(child.pid, Signal::SIGKILL);
+            let _: Result<WaitStatus, nix::Error> = waitpid(child.pid, None);
+        }
+        self.execs = 0;
+    }
+
+    /// Fork a new child, which runs the inputs it receives until its input pipe is closed
+    fn spawn_child(&mut self, state: &mut S) -> Result<(), Error> {
+        let mut input_pipe = Pipe::new()?;
+        let mut result_pipe = Pipe::new()?;
+        self.shmem_provider.pre_fork()?;
+        match unsafe { fork() }? {
+            ForkResult::Child => {
+                self.shmem_provider.post_fork(true)?;
+                input_pipe.close_write_end();
+                result_pipe.close_read_end();
+                self.run_child(state, &mut input_pipe, &mut result_pipe);
+            }
+            ForkResult::Parent { child } => {
+                self.shmem_provider.post_fork(false)?;
+                input_pipe.close_read_end();
+                result_pipe.close_write_end();
+                self.child = Some(SnapshotChild {
+                    pid: child,
+                    input_pipe,
+                    result_pipe,
+                });
+                Ok(())
+            }
+        }
+    }
+
+    /// The child, never returns
+    fn run_child(&mut self, state: &mut S, input_pipe: &mut Pipe, result_pipe: &mut Pipe) -> ! {
+        // A panicking harness exits the child like a crash
+        child_signal_handlers::setup_child_panic_hook::<Self>();
+        // Nothing may unwind out of the child, into its copy of the fuzzer loop
+        let served = panic::catch_unwind(AssertUnwindSafe(|| {
+            self.serve_inputs(state, input_pipe, result_pipe)
+        }));
+        let code = match served {
+            Ok(Ok(())) => 0,
+            Ok(Err(err)) => {
+                log::error!("Snapshot child failed: {err}");
+                1
+            }
+            Err(_) => 128 + libc::SIGABRT,
+        };
+        unsafe { libc::_exit(code) }
+    }
+
+    /// Runs the inputs the child receives, until its input pipe is closed
+    fn serve_inputs(
+        &mut self,
+        state: &mut S,
+        input_pipe: &mut Pipe,
+        result_pipe: &mut Pipe,
+    ) -> Result<(), Error> {
+        // The parent closes the pipe, or kills us, once we are done
+        while let Ok(bytes) = read_frame(input_pipe) {
+            let input: S::Input = postcard::from_bytes(&bytes)?;
+
+            self.handlers.pre_run_target(self, state, &input);
+
+            self.observers.pre_exec_child_all(state, &input)?;
+
+            let exit_kind = (self.harness_fn)(&input);
+
+            self.observers
+                .post_exec_child_all(state, &input, &exit_kind)?;
+
+            let result = postcard::to_allocvec(&exit_kind)?;
+            if write_frame(result_pipe, &result).is_err() {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    /// Reaps the child after it died, returning how it exited
+    fn reap_child(&mut self) -> Result<ExitKind, Error> {
+        let child = self.child.take().unwrap();
+        self.execs = 0;
+        match waitpid(child.pid, None)? {
+            WaitStatus::Exited(_, code) if code == 128 + libc::SIGALRM => Ok(ExitKind::Timeout),
+            // The crash handler of the child exits with signal exit codes, anything else
+            // that ends the child before it answered, like an `exit` in the harness, is a crash, too
+            WaitStatus::Exited(_, _) | WaitStatus::Signaled(_, _, _) => Ok(ExitKind::Crash),
+            status => Err(Error::unknown(format!(
+                "Snapshot child stopped unexpectedly: {status:?}"
+            ))),
+        }
+    }
+}
+
+impl<'a, EM, H, OT, S, SP, Z> Executor<EM, Z> for SnapshotForkExecutor<'a, H, OT, S, SP>
+where
+    EM: UsesState<State = S>,
+    H: FnMut(&S::Input) -> ExitKind + ?Sized,
+    OT: ObserversTuple<S>,
+    S: UsesInput,
+    SP: ShMemProvider,
+    Z: UsesState<State = S>,
+{
+    fn run_target(
+        &mut self,
+        _fuzzer: &mut Z,
+        state: &mut Self::State,
+        _mgr: &mut EM,
+        input: &Self::Input,
+    ) -> Result<ExitKind, Error> {
+        if self.execs >= self.execs_per_snapshot {
+            self.refork();
+        }
+        if self.child.is_none() {
+            self.spawn_child(state)?;
+        }
+        self.execs += 1;
+
+        let bytes = postcard::to_allocvec(input)?;
+        let child = self.child.as_mut().unwrap();
+        write_frame(&mut child.input_pipe, &bytes)?;
+
+        match read_frame(&mut child.result_pipe) {
+            Ok(result) => {
+                let exit_kind: ExitKind = postcard::from_bytes(&result)?;
+                if exit_kind != ExitKind::Ok {
+                    // The global state of the child may be broken now
+                    self.refork();
+                }
+                Ok(exit_kind)
+            }
+            // The child died while running the input
+            Err(_) => self.reap_child(),
+        }
+    }
+}
+
+impl<'a, H, OT, S, SP> Drop for SnapshotForkExecutor<'a, H, OT, S, SP>
+where
+    H: FnMut(&S::Input) -> ExitKind + ?Sized,
+    OT: ObserversTuple<S>,
+    S: UsesInput,
+    SP: ShMemProvider,
+{
+    fn drop(&mut self) {
+        self.refork();
+    }
+}
+
+impl<'a, H, OT, S, SP> UsesState for SnapshotForkExecutor<'a, H, OT, S, SP>
+where
+    H: FnMut(&S::Input) -> ExitKind + ?Sized,
+    OT: ObserversTuple<S>,
+    S: UsesInput,
+    SP: ShMemProvider,
+{
+    type State = S;
+}
+
+impl<'a, H, OT, S, SP> UsesObservers for SnapshotForkExecutor<'a, H, OT, S, SP>
+where
+    H: FnMut(&S::Input) -> ExitKind + ?Sized,
+    OT: ObserversTuple<S>,
+    S: UsesInput,
+    SP: ShMemProvider,
+{
+    type Observers = OT;
+}
+
+impl<'a, H, OT, S, SP> HasObservers for SnapshotForkExecutor<'a, H, OT, S, SP>
+where
+    H: FnMut(&S::Input) -> ExitKind + ?Sized,
+    OT: ObserversTuple<S>,
+    S: UsesInput,
+    SP: ShMemProvider,
+{
+    #[inline]
+    fn observers(&self) -> &OT {
+        &self.observers
+    }
+
+    #[inline]
+    fn observers_mut(&mut self) -> &mut OT {
+        &mut self.observers
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::marker::PhantomData;
+
+    use serial_test::serial;
+
+    use crate::{
+        bolts::{
+            shmem::{ShMemProvider, StdShMemProvider},
+            tuples::tuple_list,
+        },
+        events::NopEventManager,
+        executors::{
+            inprocess::InChildProcessHandlers, snapshot::SnapshotForkExecutor, Executor, ExitKind,
+        },
+        inputs::{BytesInput, HasBytesVec, NopInput},
+        state::NopState,
+        NopFuzzer,
+    };
+
+    #[test]
+    #[serial]
+    #[cfg_attr(miri, ignore)]
+    fn test_snapshot_fork_exec() {
+        // Each child starts over from the snapshot, so it never runs more than two inputs
+        let mut runs = 0;
+        let mut harness = |_input: &NopInput| {
+            runs += 1;
+            if runs > 2 {
+                ExitKind::Crash
+            } else {
+                ExitKind::Ok
+            }
+        };
+        let mut executor = SnapshotForkExecutor::<_, (), _, _> {
+            harness_fn: &mut harness,
+            shmem_provider: StdShMemProvider::new().unwrap(),
+            observers: tuple_list!(),
+            handlers: InChildProcessHandlers::nop(),
+            child: None,
+            execs_per_snapshot: 2,
+            execs: 0,
+            phantom: PhantomData,
+        };
+
+        let mut fuzzer = NopFuzzer::new();
+        let mut state = NopState::new();
+        let mut mgr = NopEventManager::new();
+        for _ in 0..5 {
+            assert_eq!(
+                executor
+                    .run_target(&mut fuzzer, &mut state, &mut mgr, &NopInput {})
+                    .unwrap(),
+                ExitKind::Ok
+            );
+        }
+    }
+
+    #[test]
+    #[serial]
+    #[cfg_attr(miri, ignore)]
+    fn test_snapshot_fork_exec_panic() {
+        let mut harness = |input: &BytesInput| {
+            assert!(input.bytes()[0] != 0, "harness panic");
+            ExitKind::Ok
+        };
+        let mut executor = SnapshotForkExecutor::<_, (), _, _> {
+            harness_fn: &mut harness,
+            shmem_provider: StdShMemProvider::new().unwrap(),
+            observers: tuple_list!(),
+            handlers: InChildProcessHandlers::nop(),
+            child: None,
+            execs_per_snapshot: 2,
+            execs: 0,
+            phantom: PhantomData,
+        };
+
+        let mut fuzzer = NopFuzzer::new();
+        let mut state = NopState::new();
+        let mut mgr = NopEventManager::new();
+        // A panic is a crash, and the next input runs in a new child
+        for (byte, expected) in [(0, ExitKind::Crash), (1, ExitKind::Ok)] {
+            assert_eq!(
+                executor
+                    .run_target(
+                        &mut fuzzer,
+                        &mut state,
+                        &mut mgr,
+                        &BytesInput::new(vec![byte])
+                    )
+                    .unwrap(),
+                expected
+            );
+        }
+    }
+}
@@ -0,0 +1,390 @@
+//! The [`ThreadedExecutor`] runs batches of inputs through a thread-safe harness, on multiple threads.
+//! This saturates multiple cores from a single process, which helps if the target needs too much memory
+//! (big models, big dictionaries) to run one fuzzer process per core.
+
+use alloc::vec::Vec;
+use core::{
+    fmt::{self, Debug, Formatter},
+    marker::PhantomData,
+};
+use std::thread;
+
+use crate::{
+    executors::{Executor, ExitKind, HasObservers},
+    inputs::UsesInput,
+    observers::{ObserversTuple, UsesObservers},
+    state::UsesState,
+    Error,
+};
+
+/// Merges the per-thread coverage `maps` into `target`, keeping the highest value for each entry.
+/// Use this in the merge function of a [`ThreadedExecutor`],
+/// so the observed map holds the coverage of the whole batch.
+pub fn merge_maps<'a, T, I>(target: &mut [T], maps: I)
+where
+    T: Copy + Ord + 'a,
+    I: IntoIterator<Item = &'a [T]>,
+{
+    for map in maps {
+        for (entry, value) in target.iter_mut().zip(map) {
+            *entry = (*entry).max(*value);
+        }
+    }
+}
+
+/// An executor that runs a whole batch of inputs in a single [`Executor::run_target`].
+pub trait BatchExecutor: UsesState {
+    /// The number of inputs a batch needs to keep the executor busy
+    fn batch_size(&self) -> usize;
+
+    /// Queues `inputs` to run together with the input of the next [`Executor::run_target`]
+    fn queue_batch(&mut self, inputs: Vec<<Self::State as UsesInput>::Input>);
+
+    /// The [`ExitKind`]s of the last batch, starting with the input passed to [`Executor::run_target`]
+    fn batch_exit_kinds(&self) -> &[ExitKind];
+}
+
+/// [`ThreadedExecutor`] calls a harness that can run on multiple threads at once.
+///
+/// The harness is called with the index of the thread it runs on, so that each thread can record
+/// its coverage in a map of its own, and with the input.
+/// [`Executor::run_target`] runs its input together with the batch queued by [`BatchExecutor::queue_batch`],
+/// spread over all threads, then calls the merge function with the observers.
+/// The merge function moves the per-thread maps into the observed ones, see [`merge_maps`],
+/// and resets them for the next batch.
+/// The observers then see the coverage of the whole batch in `post_exec`.
+///
+/// Without a queued batch, only the single input runs, on the thread with index `0`, so
+/// the usual fuzz loop of the [`crate::StdFuzzer`] uses one core.
+/// Use a [`crate::stages::BatchMutationalStage`] to fill all threads.
+///
+/// This executor installs no crash handlers: a crash in any thread kills the whole process,
+/// only panics are caught, see [`ThreadedExecutor::run_batch`].
+/// Run it in a process that can be restarted, and rerun the inputs of a crashing batch one by one
+/// to find the culprit.
+pub struct ThreadedExecutor<H, M, OT, S>
+where
+    H: Fn(usize, &S::Input) -> ExitKind + Sync,
+    M: FnMut(&mut OT),
+    OT: ObserversTuple<S>,
+    S: UsesInput,
+{
+    harness_fn: H,
+    merge_fn: M,
+    threads: usize,
+    observers: OT,
+    batch: Vec<S::Input>,
+    exit_kinds: Vec<ExitKind>,
+    phantom: PhantomData<S>,
+}
+
+impl<H, M, OT, S> Debug for ThreadedExecutor<H, M, OT, S>
+where
+    H: Fn(usize, &S::Input) -> ExitKind + Sync,
+    M: FnMut(&mut OT),
+    OT: ObserversTuple<S>,
+    S: UsesInput,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ThreadedExecutor")
+            .field("threads", &self.threads)
+            .field("observers", &self.observers)
+            .field("batch", &self.batch)
+            .field("exit_kinds", &self.exit_kinds)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<H, M, OT, S> ThreadedExecutor<H, M, OT, S>
+where
+    H: Fn(usize, &S::Input) -> ExitKind + Sync,
+    M: FnMut(&mut OT),
+    OT: ObserversTuple<S>,
+    S: UsesInput,
+{
+    /// Creates a new [`ThreadedExecutor`], running batches on `threads` threads.
+    /// After each batch, `merge_fn` merges the per-thread maps into the `observers`.
+    pub fn new(harness_fn: H, merge_fn: M, threads: usize, observers: OT) -> Result<Self, Error> {
+        if threads == 0 {
+            return Err(Error::illegal_argument(
+                "ThreadedExecutor needs at least one thread",
+            ));
+        }
+        Ok(Self {
+            harness_fn,
+            merge_fn,
+            threads,
+            observers,
+            batch: Vec::new(),
+            exit_kinds: Vec::new(),
+            phantom: PhantomData,
+        })
+    }
+
+    /// The number of threads batches run on
+    #[must_use]
+    pub fn threads(&self) -> usize {
+        self.threads
+    }
+
+    /// The harness
+    #[inline]
+    pub fn harness(&self) -> &H {
+        &self.harness_fn
+    }
+
+    /// Runs all `inputs`, spread over the threads, and returns their [`ExitKind`]s, in order.
+    /// Neither the observers nor the merge function are run.
+    /// If the harness panics, all inputs of its thread are reported as [`ExitKind::Crash`].
+    pub fn run_batch(&self, inputs: &[S::Input]) -> Vec<ExitKind>
+    where
+        S::Input: Sync,
+    {
+        if inputs.is_empty() {
+            return Vec::new();
+        }
+        let chunk_size = (inputs.len() + self.threads - 1) / self.threads;
+        let harness_fn = &self.harness_fn;
+
+        thread::scope(|scope| {
+            let handles: Vec<_> = inputs
+                .chunks(chunk_size)
+                .enumerate()
+                .map(|(idx, chunk)| {
+                    let handle = scope.spawn(move || {
+                        chunk
+                            .iter()
+                            .map(|input| harness_fn(idx, input))
+                            .collect::<Vec<_>>()
+                    });
+                    (chunk.len(), handle)
+                })
+                .collect();
+
+            // A panicking harness counts as a crash for the whole chunk
+            handles
+                .into_iter()
+                .flat_map(|(len, handle)| {
+                    handle.join().unwrap_or_else(|_| vec![ExitKind::Crash; len])
+                })
+                .collect()
+        })
+    }
+}
+
+impl<H, M, OT, S> BatchExecutor for ThreadedExecutor<H, M, OT, S>
+where
+    H: Fn(usize, &S::Input) -> ExitKind + Sync,
+    M: FnMut(&mut OT),
+    OT: ObserversTuple<S>,
+    S: UsesInput,
+{
+    #[inline]
+    fn batch_size(&self) -> usize {
+        self.threads
+    }
+
+    #[inline]
+    fn queue_batch(&mut self, inputs: Vec<S::Input>) {
+        self.batch = inputs;
+    }
+
+    #[inline]
+    fn batch_exit_kinds(&self) -> &[ExitKind] {
+        &self.exit_kinds
+    }
+}
+
+impl<EM, H, M, OT, S, Z> Executor<EM, Z> for ThreadedExecutor<H, M, OT, S>
+where
+    EM: UsesState<State = S>,
+    H: Fn(usize, &S::Input) -> ExitKind + Sync,
+    M: FnMut(&mut OT),
+    OT: ObserversTuple<S>,
+    S: UsesInput + Debug,
+    S::Input: Sync,
+    Z: UsesState<State = S>,
+{
+    /// Runs `input` and the queued batch, and merges the maps of all threads.
+    /// Returns the first [`ExitKind`] of the batch that is not [`ExitKind::Ok`], if any.
+    fn run_target(
+        &mut self,
+        _fuzzer: &mut Z,
+        _state: &mut Self::State,
+        _mgr: &mut EM,
+        input: &Self::Input,
+    ) -> Result<ExitKind, Error> {
+        let mut inputs = core::mem::take(&mut self.batch);
+        inputs.insert(0, input.clone());
+        self.exit_kinds = self.run_batch(&inputs);
+        (self.merge_fn)(&mut self.observers);
+
+        Ok(self
+            .exit_kinds
+            .iter()
+            .copied()
+            .find(|exit_kind| *exit_kind != ExitKind::Ok)
+            .unwrap_or(ExitKind::Ok))
+    }
+}
+
+impl<H, M, OT, S> UsesState for ThreadedExecutor<H, M, OT, S>
+where
+    H: Fn(usize, &S::Input) -> ExitKind + Sync,
+    M: FnMut(&mut OT),
+    OT: ObserversTuple<S>,
+    S: UsesInput,
+{
+    type State = S;
+}
+
+impl<H, M, OT, S> UsesObservers for ThreadedExecutor<H, M, OT, S>
+where
+    H: Fn(usize, &S::Input) -> ExitKind + Sync,
+    M: FnMut(&mut OT),
+    OT: ObserversTuple<S>,
+    S: UsesInput,
+{
+    type Observers = OT;
+}
+
+impl<H, M, OT, S> HasObservers for ThreadedExecutor<H, M, OT, S>
+where
+    H: Fn(usize, &S::Input) -> ExitKind + Sync,
+    M: FnMut(&mut OT),
+    OT: ObserversTuple<S>,
+    S: UsesInput,
+{
+    #[inline]
+    fn observers(&self) -> &OT {
+        &self.observers
+    }
+
+    #[inline]
+    fn observers_mut(&mut self) -> &mut OT {
+        &mut self.observers
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+    use core::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Mutex;
+
+    use crate::{
+        bolts::{tuples::tuple_list, AsMutSlice, AsSlice},
+        events::NopEventManager,
+        executors::{
+            threaded::{merge_maps, BatchExecutor, ThreadedExecutor},
+            Executor, ExitKind, HasObservers,
+        },
+        inputs::{BytesInput, HasBytesVec},
+        observers::StdMapObserver,
+        state::NopState,
+        NopFuzzer,
+    };
+
+    #[test]
+    fn test_threaded_batch() {
+        let runs = AtomicUsize::new(0);
+        let executor = ThreadedExecutor::<_, _, (), NopState<BytesInput>>::new(
+            |_thread, input: &BytesInput| {
+                runs.fetch_add(1, Ordering::Relaxed);
+                if input.bytes()[0] == 0 {
+                    ExitKind::Crash
+                } else {
+                    ExitKind::Ok
+                }
+            },
+            |_observers: &mut ()| {},
+            4,
+            (),
+        )
+        .unwrap();
+
+        let inputs: Vec<_> = (0..10).map(|i| BytesInput::new(vec![i % 3])).collect();
+        let exit_kinds = executor.run_batch(&inputs);
+
+        assert_eq!(runs.load(Ordering::Relaxed), 10);
+        assert_eq!(exit_kinds.len(), 10);
+        for (input, exit_kind) in inputs.iter().zip(exit_kinds) {
+            let expected = if input.bytes()[0] == 0 {
+                ExitKind::Crash
+            } else {
+                ExitKind::Ok
+            };
+            assert_eq!(exit_kind, expected);
+        }
+    }
+
+    #[test]
+    fn test_threaded_run_target() {
+        let maps: Vec<_> = (0..2).map(|_| Mutex::new(vec![0_u8; 4])).collect();
+        let mut executor = ThreadedExecutor::<_, _, _, NopState<BytesInput>>::new(
+            |thread, input: &BytesInput| {
+                let idx = usize::from(input.bytes()[0]);
+                maps[thread].lock().unwrap()[idx] = 1;
+                if idx == 3 {
+                    ExitKind::Timeout
+                } else {
+                    ExitKind::Ok
+                }
+            },
+            |observers: &mut (StdMapObserver<'static, u8, false>, ())| {
+                for map in &maps {
+                    let mut map = map.lock().unwrap();
+                    merge_maps(observers.0.as_mut_slice(), [map.as_slice()]);
+                    map.fill(0);
+                }
+            },
+            2,
+            tuple_list!(StdMapObserver::owned("map", vec![0_u8; 4])),
+        )
+        .unwrap();
+        let mut fuzzer = NopFuzzer::new();
+        let mut state = NopState::new();
+        let mut manager = NopEventManager::new();
+        assert_eq!(executor.batch_size(), 2);
+
+        // Without a batch, only the input itself runs
+        let exit_kind = executor
+            .run_target(
+                &mut fuzzer,
+                &mut state,
+                &mut manager,
+                &BytesInput::new(vec![0]),
+            )
+            .unwrap();
+        assert_eq!(exit_kind, ExitKind::Ok);
+        assert_eq!(executor.batch_exit_kinds(), [ExitKind::Ok]);
+        assert_eq!(executor.observers().0.as_slice(), [1, 0, 0, 0]);
+
+        // The batch runs next to the input, and its coverage is merged
+        executor.observers_mut().0.as_mut_slice().fill(0);
+        executor.queue_batch(vec![BytesInput::new(vec![3]), BytesInput::new(vec![2])]);
+        let exit_kind = executor
+            .run_target(
+                &mut fuzzer,
+                &mut state,
+                &mut manager,
+                &BytesInput::new(vec![1]),
+            )
+            .unwrap();
+        assert_eq!(exit_kind, ExitKind::Timeout);
+        assert_eq!(
+            executor.batch_exit_kinds(),
+            [ExitKind::Ok, ExitKind::Timeout, ExitKind::Ok]
+        );
+        assert_eq!(executor.observers().0.as_slice(), [0, 1, 1, 1]);
+    }
+
+    #[test]
+    fn test_merge_maps() {
+        let mut target = [0_u8, 1, 2, 0];
+        let first = [1_u8, 0, 0, 0];
+        let second = [0_u8, 3, 1, 0];
+        merge_maps(&mut target, [&first[..], &second[..]]);
+        assert_eq!(target, [1, 3, 2, 0]);
+    }
+}
@@ -79,6 +79,48 @@ extern "C" {
 #[cfg(all(unix, not(target_os = "linux")))]
 const ITIMER_REAL: c_int = 0;
 
+/// The one-shot timer value for the given timeout.
+/// Timeouts that round down to zero would disarm the timer, so they are rounded up to the smallest step.
+#[cfg(target_os = "linux")]
+#[allow(clippy::cast_possible_wrap)]
+fn itimerspec_for(exec_tmout: Duration) -> libc::itimerspec {
+    let mut it_value = libc::timespec {
+        tv_sec: exec_tmout.as_secs() as _,
+        tv_nsec: exec_tmout.subsec_nanos() as _,
+    };
+    if it_value.tv_sec == 0 && it_value.tv_nsec == 0 {
+        it_value.tv_nsec = 1;
+    }
+    libc::itimerspec {
+        it_interval: libc::timespec {
+            tv_sec: 0,
+            tv_nsec: 0,
+        },
+        it_value,
+    }
+}
+
+/// The one-shot timer value for the given timeout.
+/// Timeouts that round down to zero would disarm the timer, so they are rounded up to the smallest step.
+#[cfg(all(unix, not(target_os = "linux")))]
+#[allow(clippy::cast_possible_wrap)]
+fn itimerval_for(exec_tmout: Duration) -> Itimerval {
+    let mut it_value = Timeval {
+        tv_sec: exec_tmout.as_secs() as i64,
+        tv_usec: i64::from(exec_tmout.subsec_micros()),
+    };
+    if it_value.tv_sec == 0 && it_value.tv_usec == 0 {
+        it_value.tv_usec = 1;
+    }
+    Itimerval {
+        it_interval: Timeval {
+            tv_sec: 0,
+            tv_usec: 0,
+        },
+        it_value,
+    }
+}
+
 /// The timeout executor is a wrapper that sets a timeout before each run
 pub struct TimeoutExecutor<E> {
     /// The wrapped [`Executor`]
@@ -158,19 +200,7 @@ impl<E> TimeoutExecutor<E> {
     /// Create a new [`TimeoutExecutor`], wrapping the given `executor` and checking for timeouts.
     /// This should usually be used for `InProcess` fuzzing.
     pub fn new(executor: E, exec_tmout: Duration) -> Self {
-        let milli_sec = exec_tmout.as_millis();
-        let it_value = libc::timespec {
-            tv_sec: (milli_sec / 1000) as _,
-            tv_nsec: ((milli_sec % 1000) * 1000 * 1000) as _,
-        };
-        let it_interval = libc::timespec {
-            tv_sec: 0,
-            tv_nsec: 0,
-        };
-        let itimerspec = libc::itimerspec {
-            it_interval,
-            it_value,
-        };
+        let itimerspec = itimerspec_for(exec_tmout);
         let mut timerid: libc::timer_t = null_mut();
         unsafe {
             // creates a new per-process interval timer
@@ -201,30 +231,24 @@ impl<E> TimeoutExecutor<E> {
 
     /// Set the timeout for this executor
     pub fn set_timeout(&mut self, exec_tmout: Duration) {
-        let milli_sec = exec_tmout.as_millis();
-        let it_value = libc::timespec {
-            tv_sec: (milli_sec / 1000) as _,
-            tv_nsec: ((milli_sec % 1000) * 1000 * 1000) as _,
-        };
-        let it_interval = libc::timespec {
-            tv_sec: 0,
-            tv_nsec: 0,
-        };
-        let itimerspec = libc::itimerspec {
-            it_interval,
-            it_value,
-        };
+        let itimerspec = itimerspec_for(exec_tmout);
         self.itimerspec = itimerspec;
         self.exec_tmout = exec_tmout;
     }
 
     pub(crate) fn handle_timeout(&mut self, data: &mut InProcessExecutorHandlerData) -> bool {
+        self.handle_batch_timeout(data.is_valid())
+    }
+
+    /// Checks if a timeout signal, received while or after running the target (`in_target`), is a real timeout in batch mode.
+    /// In batch mode, the timer spans several runs, so the signal may come late, or before the current run timed out.
+    fn handle_batch_timeout(&mut self, in_target: bool) -> bool {
         if !self.batch_mode {
             return false;
         }
         // eprintln!("handle_timeout {:?} {}", self.avg_exec_time, self.avg_mul_k);
         let cur_time = current_time();
-        if !data.is_valid() {
+        if !in_target {
             // outside the target
             unsafe {
                 let disarmed: libc::itimerspec = zeroed();
@@ -243,9 +267,10 @@ impl<E> TimeoutExecutor<E> {
 
         let elapsed_run = cur_time - self.start_time;
         if elapsed_run < self.exec_tmout {
-            // fp, reset timeout
+            // fp, reset timeout to the time this run has left
+            let remaining = itimerspec_for(self.exec_tmout - elapsed_run);
             unsafe {
-                libc::timer_settime(self.timerid, 0, addr_of!(self.itimerspec), null_mut());
+                libc::timer_settime(self.timerid, 0, addr_of!(remaining), null_mut());
             }
             if self.executions > 0 {
                 let elapsed = cur_time - self.tmout_start_time;
@@ -267,19 +292,7 @@ impl<E> TimeoutExecutor<E> {
     /// Create a new [`TimeoutExecutor`], wrapping the given `executor` and checking for timeouts.
     /// This should usually be used for `InProcess` fuzzing.
     pub fn new(executor: E, exec_tmout: Duration) -> Self {
-        let milli_sec = exec_tmout.as_millis();
-        let it_value = Timeval {
-            tv_sec: (milli_sec / 1000) as i64,
-            tv_usec: (milli_sec % 1000) as i64,
-        };
-        let it_interval = Timeval {
-            tv_sec: 0,
-            tv_usec: 0,
-        };
-        let itimerval = Itimerval {
-            it_interval,
-            it_value,
-        };
+        let itimerval = itimerval_for(exec_tmout);
         Self {
             executor,
             itimerval,
@@ -296,19 +309,7 @@ impl<E> TimeoutExecutor<E> {
 
     /// Set the timeout for this executor
     pub fn set_timeout(&mut self, exec_tmout: Duration) {
-        let milli_sec = exec_tmout.as_millis();
-        let it_value = Timeval {
-            tv_sec: (milli_sec / 1000) as i64,
-            tv_usec: (milli_sec % 1000) as i64,
-        };
-        let it_interval = Timeval {
-            tv_sec: 0,
-            tv_usec: 0,
-        };
-        let itimerval = Itimerval {
-            it_interval,
-            it_value,
-        };
+        let itimerval = itimerval_for(exec_tmout);
         self.itimerval = itimerval;
         self.exec_tmout = exec_tmout;
     }
@@ -502,7 +503,7 @@ where
                         self.executions = 0;
                     }
                     // readjust K
-                    if self.last_signal_time > self.exec_tmout * self.avg_mul_k
+                    if current_time() - self.last_signal_time > self.exec_tmout * self.avg_mul_k
                         && self.avg_mul_k > 1
                     {
                         self.avg_mul_k -= 1;
@@ -580,3 +581,137 @@ where
         self.executor.observers_mut()
     }
 }
+
+#[cfg(all(test, unix))]
+mod tests {
+    #[cfg(target_os = "linux")]
+    use core::mem::zeroed;
+    use core::time::Duration;
+
+    #[cfg(not(target_os = "linux"))]
+    use super::itimerval_for;
+    #[cfg(target_os = "linux")]
+    use super::{itimerspec_for, TimeoutExecutor};
+    #[cfg(target_os = "linux")]
+    use crate::{
+        bolts::current_time,
+        events::NopEventManager,
+        executors::{Executor, NopExecutor},
+        inputs::BytesInput,
+        state::NopState,
+        NopFuzzer,
+    };
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn test_itimerspec_for() {
+        let value = |exec_tmout| {
+            let itimerspec = itimerspec_for(exec_tmout);
+            assert_eq!(itimerspec.it_interval.tv_sec, 0);
+            assert_eq!(itimerspec.it_interval.tv_nsec, 0);
+            (itimerspec.it_value.tv_sec, itimerspec.it_value.tv_nsec)
+        };
+        assert_eq!(value(Duration::from_micros(500)), (0, 500_000));
+        assert_eq!(value(Duration::from_secs(2)), (2, 0));
+        assert_eq!(value(Duration::from_millis(1250)), (1, 250_000_000));
+        // A zero timeout would disarm the timer
+        assert_eq!(value(Duration::ZERO), (0, 1));
+    }
+
+    #[test]
+    #[cfg(not(target_os = "linux"))]
+    fn test_itimerval_for() {
+        let value = |exec_tmout| {
+            let itimerval = itimerval_for(exec_tmout);
+            assert_eq!(itimerval.it_interval.tv_sec, 0);
+            assert_eq!(itimerval.it_interval.tv_usec, 0);
+            (itimerval.it_value.tv_sec, itimerval.it_value.tv_usec)
+        };
+        assert_eq!(value(Duration::from_micros(500)), (0, 500));
+        assert_eq!(value(Duration::from_secs(2)), (2, 0));
+        assert_eq!(value(Duration::from_millis(1250)), (1, 250_000));
+        // Below a microsecond, the timer would be disarmed
+        assert_eq!(value(Duration::from_nanos(500)), (0, 1));
+    }
+
+    #[cfg(target_os = "linux")]
+    type TestExecutor = TimeoutExecutor<NopExecutor<NopState<BytesInput>>>;
+
+    #[cfg(target_os = "linux")]
+    fn batch_executor(exec_tmout: Duration) -> TestExecutor {
+        TimeoutExecutor::batch_mode(NopExecutor::new(), exec_tmout)
+    }
+
+    #[cfg(target_os = "linux")]
+    #[allow(clippy::cast_sign_loss)]
+    fn remaining(executor: &TestExecutor) -> Duration {
+        let mut itimerspec: libc::itimerspec = unsafe { zeroed() };
+        unsafe { libc::timer_gettime(executor.timerid, &mut itimerspec) };
+        Duration::new(
+            itimerspec.it_value.tv_sec as u64,
+            itimerspec.it_value.tv_nsec as u32,
+        )
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn test_batch_timeout_rearm() {
+        let mut executor = batch_executor(Duration::from_secs(10));
+        let now = current_time();
+        executor.tmout_start_time = now - Duration::from_secs(6);
+        executor.start_time = now - Duration::from_secs(4);
+        executor.executions = 2;
+
+        // The signal came for earlier runs, the current one only gets the time it has left
+        assert!(executor.handle_batch_timeout(true));
+        let remaining = remaining(&executor);
+        assert!(remaining > Duration::from_secs(5) && remaining <= Duration::from_secs(6));
+        assert_eq!(executor.executions, 0);
+        assert_eq!(executor.avg_mul_k, 2);
+        assert_eq!(executor.avg_exec_time.as_secs(), 3);
+
+        // Outside of the target, the timer is disarmed until the next run
+        assert!(executor.handle_batch_timeout(false));
+        assert_eq!(remaining(&executor), Duration::ZERO);
+        assert_eq!(executor.avg_mul_k, 3);
+
+        // A run that took all of its time is a real timeout
+        executor.start_time = current_time() - Duration::from_secs(11);
+        assert!(!executor.handle_batch_timeout(true));
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn test_batch_timeout_decay() {
+        let post_run_reset = |executor: &mut TestExecutor| {
+            Executor::<NopEventManager<NopState<BytesInput>>, NopFuzzer<BytesInput>>::post_run_reset(
+                executor,
+            );
+        };
+        let mut executor = batch_executor(Duration::from_millis(100));
+
+        // A recent signal keeps the multiplier
+        let now = current_time();
+        executor.tmout_start_time = now - Duration::from_millis(200);
+        executor.last_signal_time = now;
+        executor.executions = 4;
+        executor.avg_mul_k = 3;
+        post_run_reset(&mut executor);
+        assert_eq!(executor.executions, 0);
+        assert_eq!(executor.avg_mul_k, 3);
+        assert!(executor.avg_exec_time >= Duration::from_millis(50));
+
+        // Without signals for longer than the multiplied timeout, it decays
+        let now = current_time();
+        executor.tmout_start_time = now - Duration::from_millis(200);
+        executor.last_signal_time = now - Duration::from_secs(1);
+        post_run_reset(&mut executor);
+        assert_eq!(executor.avg_mul_k, 2);
+
+        // Runs well within the timeout keep the timer armed
+        executor.tmout_start_time = current_time();
+        executor.avg_exec_time = Duration::from_millis(1);
+        post_run_reset(&mut executor);
+        assert_eq!(executor.executions, 1);
+    }
+}
@@ -0,0 +1,374 @@
+//! The [`WindowsPersistentExecutor`] runs a persistent target in a child process, and talks to it over shared memory.
+//! Windows has no `fork`, so there is no forkserver, this is how to fuzz out of process there instead.
+//!
+//! The child maps the shared memory described by the [`PERSISTENT_SHMEM_ENV`] env vars, as written by
+//! [`ShMem::write_to_env`]. It starts with a [`PersistentControl`], followed by the bytes of the testcase.
+//! The child waits for the `state` to become [`PERSISTENT_STATE_INPUT`], runs the `len` bytes,
+//! stores the `status` (`0` if the run went fine, else the code of the exception it caught),
+//! and sets the `state` to [`PERSISTENT_STATE_DONE`].
+//! The stdin, stdout and stderr of the child are left to the target.
+//! The observers need to live in shared memory the child can map, as for the forkserver.
+
+use alloc::string::ToString;
+use core::{
+    fmt::Debug,
+    marker::PhantomData,
+    mem::size_of,
+    sync::atomic::{AtomicU32, Ordering},
+    time::Duration,
+};
+use std::{
+    process::{Child, Command, ExitStatus},
+    thread,
+    time::Instant,
+};
+
+use crate::{
+    bolts::{
+        shmem::{ShMem, ShMemProvider, StdShMemProvider},
+        AsMutSlice, AsSlice,
+    },
+    executors::{Executor, ExitKind, HasObservers},
+    inputs::{HasTargetBytes, UsesInput},
+    observers::{ObserversTuple, UsesObservers},
+    state::UsesState,
+    Error,
+};
+
+/// The env var holding the id of the shared memory to talk to the child over, `{name}_SIZE` holds its size
+pub const PERSISTENT_SHMEM_ENV: &str = "__LIBAFL_PERSISTENT_SHM";
+
+/// The default size of the shared memory, including the [`PersistentControl`]
+pub const DEFAULT_PERSISTENT_SHMEM_SIZE: usize = 1 << 20;
+
+/// The child has no testcase to run
+pub const PERSISTENT_STATE_IDLE: u32 = 0;
+/// The child should run the testcase in the shared memory
+pub const PERSISTENT_STATE_INPUT: u32 = 1;
+/// The child ran the testcase, its `status` is set
+pub const PERSISTENT_STATE_DONE: u32 = 2;
+
+/// The header of the shared memory of a [`WindowsPersistentExecutor`], the testcase follows it
+#[derive(Debug)]
+#[repr(C)]
+pub struct PersistentControl {
+    /// One of [`PERSISTENT_STATE_IDLE`], [`PERSISTENT_STATE_INPUT`] or [`PERSISTENT_STATE_DONE`]
+    pub state: AtomicU32,
+    /// The exception code the child caught running the testcase, `0` if it went fine
+    pub status: AtomicU32,
+    /// The length of the testcase
+    pub len: AtomicU32,
+}
+
+/// The exception code the child died with, if it crashed
+#[allow(clippy::cast_sign_loss)]
+fn crash_code(status: ExitStatus) -> Option<u32> {
+    // Exceptions are `NTSTATUS` error codes, like `0xC0000005` for access violations
+    status
+        .code()
+        .map(|code| code as u32)
+        .filter(|code| code & 0xC000_0000 == 0xC000_0000)
+}
+
+/// [`WindowsPersistentExecutor`] is an executor for persistent targets running in a child process.
+///
+/// The child is started on the first run, and restarted after each crash or timeout.
+/// If the child dies while running a testcase, the exception code is taken from its exit status,
+/// see [`WindowsPersistentExecutor::last_crash_code`].
+#[derive(Debug)]
+pub struct WindowsPersistentExecutor<OT, S> {
+    command: Command,
+    timeout: Duration,
+    observers: OT,
+    shmem: <StdShMemProvider as ShMemProvider>::ShMem,
+    child: Option<Child>,
+    last_crash_code: Option<u32>,
+    phantom: PhantomData<S>,
+}
+
+impl<OT, S> WindowsPersistentExecutor<OT, S>
+where
+    OT: ObserversTuple<S>,
+    S: UsesInput,
+{
+    /// Creates a new [`WindowsPersistentExecutor`] for the target started by `command`,
+    /// with testcases of up to [`DEFAULT_PERSISTENT_SHMEM_SIZE`] bytes.
+    pub fn new(command: Command, timeout: Duration, observers: OT) -> Result<Self, Error> {
+        Self::with_shmem_size(command, timeout, observers, DEFAULT_PERSISTENT_SHMEM_SIZE)
+    }
+
+    /// Creates a new [`WindowsPersistentExecutor`] talking to the child over `shmem_size` bytes of shared memory.
+    pub fn with_shmem_size(
+        mut command: Command,
+        timeout: Duration,
+        observers: OT,
+        shmem_size: usize,
+    ) -> Result<Self, Error> {
+        if shmem_size <= size_of::<PersistentControl>() {
+            return Err(Error::illegal_argument(
+                "The shared memory is too small to hold a testcase",
+            ));
+        }
+        let mut shmem = StdShMemProvider::new()?.new_shmem(shmem_size)?;
+        unsafe {
+            *shmem.as_object_mut::<PersistentControl>() = PersistentControl {
+                state: AtomicU32::new(PERSISTENT_STATE_IDLE),
+                status: AtomicU32::new(0),
+                len: AtomicU32::new(0),
+            };
+        }
+        command
+            .env(PERSISTENT_SHMEM_ENV, shmem.id().to_string())
+            .env(
+                format!("{PERSISTENT_SHMEM_ENV}_SIZE"),
+                shmem.len().to_string(),
+            );
+        Ok(Self {
+            command,
+            timeout,
+            observers,
+            shmem,
+            child: None,
+            last_crash_code: None,
+            phantom: PhantomData,
+        })
+    }
+
+    /// The code of the last crash: the exception code reported by the child, or the one it died with.
+    #[must_use]
+    pub fn last_crash_code(&self) -> Option<u32> {
+        self.last_crash_code
+    }
+
+    /// The timeout for each run
+    #[must_use]
+    pub fn timeout(&self) -> Duration {
+        self.timeout
+    }
+
+    /// Waits for the child to exit, killing it first if `kill` is set
+    fn reap_child(&mut self, kill: bool) -> Option<ExitStatus> {
+        // A late answer of the old child must not be taken for one of the next
+        let control = unsafe { self.shmem.as_object::<PersistentControl>() };
+        control
+            .state
+            .store(PERSISTENT_STATE_IDLE, Ordering::Release);
+        let mut child = self.child.take()?;
+        if kill {
+            // This fails if the child already exited, waiting still works then
+            drop(child.kill());
+        }
+        child.wait().ok()
+    }
+}
+
+impl<EM, OT, S, Z> Executor<EM, Z> for WindowsPersistentExecutor<OT, S>
+where
+    EM: UsesState<State = S>,
+    OT: ObserversTuple<S>,
+    S: UsesInput + Debug,
+    S::Input: HasTargetBytes,
+    Z: UsesState<State = S>,
+{
+    fn run_target(
+        &mut self,
+        _fuzzer: &mut Z,
+        _state: &mut Self::State,
+        _mgr: &mut EM,
+        input: &Self::Input,
+    ) -> Result<ExitKind, Error> {
+        if self.child.is_none() {
+            self.child = Some(self.command.spawn()?);
+        }
+
+        let target_bytes = input.target_bytes();
+        let bytes = target_bytes.as_slice();
+        let buf = &mut self.shmem.as_mut_slice()[size_of::<PersistentControl>()..];
+        if bytes.len() > buf.len() {
+            return Err(Error::illegal_argument(
+                "Input too large for the shared memory of the persistent child",
+            ));
+        }
+        buf[..bytes.len()].copy_from_slice(bytes);
+
+        let control = unsafe { self.shmem.as_object::<PersistentControl>() };
+        control.len.store(bytes.len() as u32, Ordering::Relaxed);
+        control.status.store(0, Ordering::Relaxed);
+        control
+            .state
+            .store(PERSISTENT_STATE_INPUT, Ordering::Release);
+
+        let start = Instant::now();
+        let status = loop {
+            if control.state.load(Ordering::Acquire) == PERSISTENT_STATE_DONE {
+                break Some(control.status.load(Ordering::Relaxed));
+            }
+            if matches!(self.child.as_mut().unwrap().try_wait(), Ok(Some(_))) {
+                break None;
+            }
+            if start.elapsed() > self.timeout {
+                self.reap_child(true);
+                return Ok(ExitKind::Timeout);
+            }
+            thread::yield_now();
+        };
+
+        match status {
+            Some(0) => {
+                control
+                    .state
+                    .store(PERSISTENT_STATE_IDLE, Ordering::Release);
+                Ok(ExitKind::Ok)
+            }
+            Some(code) => {
+                // The child caught an exception, its state can't be trusted anymore
+                self.last_crash_code = Some(code);
+                self.reap_child(true);
+                Ok(ExitKind::Crash)
+            }
+            None => {
+                self.last_crash_code = self.reap_child(false).and_then(crash_code);
+                log::info!(
+                    "Persistent child died, crash code: {:?}",
+                    self.last_crash_code
+                );
+                Ok(ExitKind::Crash)
+            }
+        }
+    }
+}
+
+impl<OT, S> Drop for WindowsPersistentExecutor<OT, S> {
+    fn drop(&mut self) {
+        if let Some(mut child) = self.child.take() {
+            drop(child.kill());
+            drop(child.wait());
+        }
+    }
+}
+
+impl<OT, S> UsesState for WindowsPersistentExecutor<OT, S>
+where
+    S: UsesInput,
+{
+    type State = S;
+}
+
+impl<OT, S> UsesObservers for WindowsPersistentExecutor<OT, S>
+where
+    OT: ObserversTuple<S>,
+    S: UsesInput,
+{
+    type Observers = OT;
+}
+
+impl<OT, S> HasObservers for WindowsPersistentExecutor<OT, S>
+where
+    OT: ObserversTuple<S>,
+    S: UsesInput,
+{
+    #[inline]
+    fn observers(&self) -> &OT {
+        &self.observers
+    }
+
+    #[inline]
+    fn observers_mut(&mut self) -> &mut OT {
+        &mut self.observers
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+    use core::{mem::size_of, sync::atomic::Ordering, time::Duration};
+    use std::{process::Command, thread};
+
+    use crate::{
+        bolts::{
+            shmem::{ShMem, ShMemProvider, StdShMemProvider},
+            AsSlice,
+        },
+        events::NopEventManager,
+        executors::{
+            windows_persistent::{
+                PersistentControl, WindowsPersistentExecutor, PERSISTENT_STATE_DONE,
+                PERSISTENT_STATE_INPUT,
+            },
+            Executor, ExitKind,
+        },
+        inputs::BytesInput,
+        state::NopState,
+        NopFuzzer,
+    };
+
+    /// Runs a one byte testcase in a child started by `cmd /C {script}`.
+    /// If given, a thread answers with the `statuses` in place of the child, one per run.
+    fn run_script(
+        script: &str,
+        timeout: Duration,
+        statuses: &[u32],
+    ) -> Vec<(ExitKind, Option<u32>)> {
+        let mut command = Command::new("cmd");
+        command.arg("/C").arg(script);
+        let mut executor =
+            WindowsPersistentExecutor::<(), NopState<BytesInput>>::new(command, timeout, ())
+                .unwrap();
+
+        let description = executor.shmem.description();
+        let statuses = statuses.to_vec();
+        let runs = statuses.len().max(1);
+        let answer = thread::spawn(move || {
+            let shmem = StdShMemProvider::new()
+                .unwrap()
+                .shmem_from_description(description)
+                .unwrap();
+            let control = unsafe { shmem.as_object::<PersistentControl>() };
+            for status in statuses {
+                while control.state.load(Ordering::Acquire) != PERSISTENT_STATE_INPUT {
+                    thread::yield_now();
+                }
+                assert_eq!(control.len.load(Ordering::Relaxed), 1);
+                assert_eq!(shmem.as_slice()[size_of::<PersistentControl>()], 0x41);
+                control.status.store(status, Ordering::Relaxed);
+                control
+                    .state
+                    .store(PERSISTENT_STATE_DONE, Ordering::Release);
+            }
+        });
+
+        let results = (0..runs)
+            .map(|_| {
+                let exit_kind = executor
+                    .run_target(
+                        &mut NopFuzzer::new(),
+                        &mut NopState::new(),
+                        &mut NopEventManager::new(),
+                        &BytesInput::new(vec![0x41]),
+                    )
+                    .unwrap();
+                (exit_kind, executor.last_crash_code())
+            })
+            .collect();
+        answer.join().unwrap();
+        results
+    }
+
+    #[test]
+    fn test_persistent_child() {
+        let timeout = Duration::from_secs(5);
+        let alive = "ping -n 10 127.0.0.1 >NUL";
+        assert_eq!(
+            run_script(alive, timeout, &[0, 5]),
+            [(ExitKind::Ok, None), (ExitKind::Crash, Some(5))]
+        );
+        assert_eq!(
+            run_script("exit -1073741819", timeout, &[]),
+            [(ExitKind::Crash, Some(0xC000_0005))]
+        );
+        assert_eq!(
+            run_script(alive, Duration::from_millis(100), &[]),
+            [(ExitKind::Timeout, None)]
+        );
+    }
+}
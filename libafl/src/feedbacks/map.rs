@@ -8,10 +8,12 @@ use alloc::{
 use core::simd::SimdOrd;
 use core::{
     fmt::Debug,
+    hash::{BuildHasher, Hasher},
     marker::PhantomData,
     ops::{BitAnd, BitOr},
 };
 
+use ahash::RandomState;
 use num_traits::PrimInt;
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 
@@ -262,6 +264,16 @@ impl MapIndexesMetadata {
     pub fn new(list: Vec<usize>) -> Self {
         Self { list, tcref: 0 }
     }
+
+    /// A hash of the indexes, equal for all testcases covering the same entries of the map
+    #[must_use]
+    pub fn coverage_hash(&self) -> u64 {
+        let mut hasher = RandomState::with_seeds(0, 0, 0, 0).build_hasher();
+        for idx in &self.list {
+            hasher.write_usize(*idx);
+        }
+        hasher.finish()
+    }
 }
 
 /// A testcase metadata holding a list of indexes of a map
@@ -438,7 +450,11 @@ where
             let meta = MapNoveltiesMetadata::new(novelties);
             testcase.add_metadata(meta);
         }
-        let observer = observers.match_name::<O>(&self.observer_name).unwrap();
+        let observer = observers
+            .match_name::<O>(&self.observer_name)
+            .ok_or_else(|| {
+                Error::key_not_found(format!("MapObserver {} not found", self.observer_name))
+            })?;
         let initial = observer.initial();
         let map_state = state
             .named_metadata_map_mut()
@@ -501,7 +517,11 @@ where
 
         let mut interesting = false;
         // TODO Replace with match_name_type when stable
-        let observer = observers.match_name::<O>(&self.observer_name).unwrap();
+        let observer = observers
+            .match_name::<O>(&self.observer_name)
+            .ok_or_else(|| {
+                Error::key_not_found(format!("MapObserver {} not found", self.observer_name))
+            })?;
 
         let map_state = state
             .named_metadata_map_mut()
@@ -589,7 +609,7 @@ where
         }
 
         let initial = observer.initial();
-        if interesting {
+        if interesting || self.always_track {
             let len = history_map.len();
             let filled = history_map.iter().filter(|&&i| i != initial).count();
             // opt: if not tracking optimisations, we technically don't show the *current* history
@@ -751,7 +771,11 @@ where
     {
         let mut interesting = false;
         // TODO Replace with match_name_type when stable
-        let observer = observers.match_name::<O>(&self.observer_name).unwrap();
+        let observer = observers
+            .match_name::<O>(&self.observer_name)
+            .ok_or_else(|| {
+                Error::key_not_found(format!("MapObserver {} not found", self.observer_name))
+            })?;
 
         let map_state = state
             .named_metadata_map_mut()
@@ -877,7 +901,9 @@ where
         OT: ObserversTuple<S>,
     {
         // TODO Replace with match_name_type when stable
-        let observer = observers.match_name::<O>(&self.name).unwrap();
+        let observer = observers
+            .match_name::<O>(&self.name)
+            .ok_or_else(|| Error::key_not_found(format!("MapObserver {} not found", self.name)))?;
         let mut hit_target: bool = false;
         //check if we've hit any targets.
         for (i, &elem) in observer.as_iter().enumerate() {
@@ -930,9 +956,62 @@ where
     }
 }
 
+impl<O, S> HasObserverName for ReachabilityFeedback<O, S>
+where
+    O: MapObserver<Entry = usize>,
+    for<'it> O: AsIter<'it, Item = usize>,
+{
+    #[inline]
+    fn observer_name(&self) -> &str {
+        self.name.as_str()
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::feedbacks::{AllIsNovel, IsNovel, NextPow2IsNovel};
+    use core::marker::PhantomData;
+
+    use crate::{
+        bolts::{rands::StdRand, tuples::tuple_list, AsMutSlice},
+        corpus::{InMemoryCorpus, Testcase},
+        events::{Event, EventFirer},
+        executors::ExitKind,
+        feedbacks::{
+            AllIsNovel, AndReducer, ConstFeedback, Feedback, IsNovel, MapIndexesMetadata,
+            MapNoveltiesMetadata, MaxMapFeedback, MaxReducer, MinReducer, NextPow2IsNovel,
+            NopReducer, OrReducer, Reducer,
+        },
+        inputs::{BytesInput, UsesInput},
+        observers::StdMapObserver,
+        state::{HasMetadata, StdState, UsesState},
+        Error,
+    };
+
+    /// Counts the [`Event::UpdateUserStats`] fired by a feedback
+    #[derive(Debug)]
+    struct UserStatsCounter<S> {
+        fired: usize,
+        phantom: PhantomData<S>,
+    }
+
+    impl<S> UsesState for UserStatsCounter<S>
+    where
+        S: UsesInput,
+    {
+        type State = S;
+    }
+
+    impl<S> EventFirer for UserStatsCounter<S>
+    where
+        S: UsesInput,
+    {
+        fn fire(&mut self, _state: &mut S, event: Event<S::Input>) -> Result<(), Error> {
+            if let Event::UpdateUserStats { .. } = event {
+                self.fired += 1;
+            }
+            Ok(())
+        }
+    }
 
     #[test]
     fn test_map_is_novel() {
@@ -953,6 +1032,74 @@ mod tests {
         assert!(NextPow2IsNovel::is_novel(254_u8, 255));
         assert!(!NextPow2IsNovel::is_novel(255_u8, 255));
     }
+
+    #[test]
+    fn test_map_reducers() {
+        assert_eq!(MaxReducer::reduce(3_u8, 7), 7);
+        assert_eq!(MaxReducer::reduce(7_u8, 3), 7);
+        assert_eq!(MinReducer::reduce(3_u8, 7), 3);
+        assert_eq!(MinReducer::reduce(7_u8, 3), 3);
+        assert_eq!(OrReducer::reduce(0b0101_u8, 0b0011), 0b0111);
+        assert_eq!(AndReducer::reduce(0b0101_u8, 0b0011), 0b0001);
+        assert_eq!(NopReducer::reduce(7_u8, 3), 3);
+    }
+
+    #[test]
+    fn test_map_always_track() {
+        let input = BytesInput::new(vec![0]);
+        let mut observers = tuple_list!(StdMapObserver::owned("map", vec![0_u8; 4]));
+        let mut feedback = MaxMapFeedback::tracking(&observers.0, true, true);
+        feedback.set_always_track(true);
+        let mut objective = ConstFeedback::new(false);
+        let mut state = StdState::new(
+            StdRand::with_seed(0),
+            InMemoryCorpus::<BytesInput>::new(),
+            InMemoryCorpus::<BytesInput>::new(),
+            &mut feedback,
+            &mut objective,
+        )
+        .unwrap();
+        let mut manager = UserStatsCounter {
+            fired: 0,
+            phantom: PhantomData,
+        };
+
+        observers.0.as_mut_slice()[1] = 1;
+        assert!(feedback
+            .is_interesting(&mut state, &mut manager, &input, &observers, &ExitKind::Ok)
+            .unwrap());
+        let mut testcase = Testcase::new(input.clone());
+        feedback
+            .append_metadata(&mut state, &observers, &mut testcase)
+            .unwrap();
+        assert_eq!(manager.fired, 1);
+
+        // The same map again is not interesting, but is still tracked
+        assert!(!feedback
+            .is_interesting(&mut state, &mut manager, &input, &observers, &ExitKind::Ok)
+            .unwrap());
+        assert_eq!(manager.fired, 2);
+        let mut testcase = Testcase::new(input.clone());
+        feedback
+            .append_metadata(&mut state, &observers, &mut testcase)
+            .unwrap();
+        assert_eq!(
+            testcase.metadata::<MapIndexesMetadata>().unwrap().list,
+            vec![1]
+        );
+        assert!(testcase
+            .metadata::<MapNoveltiesMetadata>()
+            .unwrap()
+            .list
+            .is_empty());
+
+        // Without `always_track`, nothing is reported for non-interesting runs
+        feedback.set_always_track(false);
+        assert!(!feedback
+            .is_interesting(&mut state, &mut manager, &input, &observers, &ExitKind::Ok)
+            .unwrap());
+        assert_eq!(manager.fired, 2);
+    }
 }
 
 /// `MapFeedback` Python bindings
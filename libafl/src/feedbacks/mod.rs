@@ -14,11 +14,8 @@ pub mod concolic;
 #[cfg(feature = "std")]
 pub use concolic::ConcolicFeedback;
 
-#[cfg(feature = "std")]
 pub mod new_hash_feedback;
-#[cfg(feature = "std")]
 pub use new_hash_feedback::NewHashFeedback;
-#[cfg(feature = "std")]
 pub use new_hash_feedback::NewHashFeedbackMetadata;
 
 #[cfg(feature = "nautilus")]
@@ -710,7 +707,7 @@ macro_rules! feedback_and {
 
     ( $head:expr, $($tail:expr), +) => {
         // recursive call
-        $crate::feedbacks::EagerAndFeedback::new($head , feedback_and!($($tail),+))
+        $crate::feedbacks::EagerAndFeedback::new($head , $crate::feedback_and!($($tail),+))
     };
 }
 ///
@@ -721,7 +718,7 @@ macro_rules! feedback_and_fast {
 
     ( $head:expr, $($tail:expr), +) => {
         // recursive call
-        $crate::feedbacks::FastAndFeedback::new($head , feedback_and_fast!($($tail),+))
+        $crate::feedbacks::FastAndFeedback::new($head , $crate::feedback_and_fast!($($tail),+))
     };
 }
 
@@ -732,7 +729,7 @@ macro_rules! feedback_or {
 
     ( $head:expr, $($tail:expr), +) => {
         // recursive call
-        $crate::feedbacks::EagerOrFeedback::new($head , feedback_or!($($tail),+))
+        $crate::feedbacks::EagerOrFeedback::new($head , $crate::feedback_or!($($tail),+))
     };
 }
 
@@ -743,7 +740,7 @@ macro_rules! feedback_or_fast {
 
     ( $head:expr, $($tail:expr), +) => {
         // recursive call
-        $crate::feedbacks::FastOrFeedback::new($head , feedback_or_fast!($($tail),+))
+        $crate::feedbacks::FastOrFeedback::new($head , $crate::feedback_or_fast!($($tail),+))
     };
 }
 
@@ -837,7 +834,7 @@ impl Default for CrashFeedback {
 /// A feedback factory for crash feedbacks
 pub type CrashFeedbackFactory = DefaultFeedbackFactory<CrashFeedback>;
 
-/// A [`TimeoutFeedback`] reduces the timeout value of a run.
+/// A [`TimeoutFeedback`] reports as interesting if the target timed out.
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct TimeoutFeedback {}
 
@@ -930,7 +927,11 @@ where
     where
         OT: ObserversTuple<S>,
     {
-        let observer = observers.match_name::<TimeObserver>(self.name()).unwrap();
+        let observer = observers
+            .match_name::<TimeObserver>(self.name())
+            .ok_or_else(|| {
+                Error::key_not_found(format!("TimeObserver {} not found", self.name()))
+            })?;
         *testcase.exec_time_mut() = *observer.last_runtime();
         Ok(())
     }
@@ -949,6 +950,13 @@ impl Named for TimeFeedback {
     }
 }
 
+impl HasObserverName for TimeFeedback {
+    #[inline]
+    fn observer_name(&self) -> &str {
+        self.name.as_str()
+    }
+}
+
 impl TimeFeedback {
     /// Creates a new [`TimeFeedback`], deciding if the value of a [`TimeObserver`] with the given `name` of a run is interesting.
     #[must_use]
@@ -999,7 +1007,9 @@ where
         // TODO Replace with match_name_type when stable
         let observer = observers
             .match_name::<ListObserver<T>>(self.name())
-            .unwrap();
+            .ok_or_else(|| {
+                Error::key_not_found(format!("ListObserver {} not found", self.name()))
+            })?;
         // TODO register the list content in a testcase metadata
         Ok(!observer.list().is_empty())
     }
@@ -1015,6 +1025,16 @@ where
     }
 }
 
+impl<T> HasObserverName for ListFeedback<T>
+where
+    T: Debug + Serialize + serde::de::DeserializeOwned,
+{
+    #[inline]
+    fn observer_name(&self) -> &str {
+        self.name.as_str()
+    }
+}
+
 impl<T> ListFeedback<T>
 where
     T: Debug + Serialize + serde::de::DeserializeOwned,
@@ -1029,7 +1049,7 @@ where
         }
     }
 
-    /// Creates a new [`TimeFeedback`], deciding if the given [`ListObserver`] value of a run is interesting.
+    /// Creates a new [`ListFeedback`], deciding if the given [`ListObserver`] value of a run is interesting.
     #[must_use]
     pub fn with_observer(observer: &ListObserver<T>) -> Self {
         Self {
@@ -1693,3 +1713,65 @@ pub mod pybind {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        events::NopEventManager,
+        executors::ExitKind,
+        feedback_and, feedback_and_fast, feedback_not, feedback_or, feedback_or_fast,
+        feedbacks::{CrashFeedback, Feedback, TimeoutFeedback},
+        inputs::BytesInput,
+        state::NopState,
+    };
+
+    fn interesting<F>(feedback: &mut F, exit_kind: &ExitKind) -> bool
+    where
+        F: Feedback<NopState<BytesInput>>,
+    {
+        feedback
+            .is_interesting(
+                &mut NopState::new(),
+                &mut NopEventManager::new(),
+                &BytesInput::new(vec![]),
+                &(),
+                exit_kind,
+            )
+            .unwrap()
+    }
+
+    #[test]
+    fn test_exit_kind_feedbacks() {
+        let mut crash = CrashFeedback::new();
+        assert!(interesting(&mut crash, &ExitKind::Crash));
+        assert!(!interesting(&mut crash, &ExitKind::Ok));
+        assert!(!interesting(&mut crash, &ExitKind::Timeout));
+
+        let mut timeout = TimeoutFeedback::new();
+        assert!(interesting(&mut timeout, &ExitKind::Timeout));
+        assert!(!interesting(&mut timeout, &ExitKind::Ok));
+        assert!(!interesting(&mut timeout, &ExitKind::Crash));
+    }
+
+    #[test]
+    fn test_feedback_logic() {
+        let mut or = feedback_or!(CrashFeedback::new(), TimeoutFeedback::new());
+        let mut or_fast = feedback_or_fast!(CrashFeedback::new(), TimeoutFeedback::new());
+        for exit_kind in [ExitKind::Crash, ExitKind::Timeout] {
+            assert!(interesting(&mut or, &exit_kind));
+            assert!(interesting(&mut or_fast, &exit_kind));
+        }
+        assert!(!interesting(&mut or, &ExitKind::Ok));
+        assert!(!interesting(&mut or_fast, &ExitKind::Ok));
+
+        let mut and = feedback_and!(CrashFeedback::new(), feedback_not!(TimeoutFeedback::new()));
+        let mut and_fast =
+            feedback_and_fast!(CrashFeedback::new(), feedback_not!(TimeoutFeedback::new()));
+        assert!(interesting(&mut and, &ExitKind::Crash));
+        assert!(interesting(&mut and_fast, &ExitKind::Crash));
+        assert!(!interesting(&mut and, &ExitKind::Timeout));
+        assert!(!interesting(&mut and_fast, &ExitKind::Timeout));
+        assert!(!interesting(&mut and, &ExitKind::Ok));
+        assert!(!interesting(&mut and_fast, &ExitKind::Ok));
+    }
+}
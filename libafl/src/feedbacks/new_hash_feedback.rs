@@ -1,7 +1,7 @@
 //! The ``NewHashFeedback`` uses the backtrace hash and a hashset to only keep novel cases
 
 use alloc::string::{String, ToString};
-use std::{fmt::Debug, marker::PhantomData};
+use core::{fmt::Debug, marker::PhantomData};
 
 use hashbrown::HashSet;
 use serde::{Deserialize, Serialize};
@@ -1,11 +1,13 @@
 //! The `Fuzzer` is the main struct for a fuzz campaign.
 
-use alloc::string::ToString;
+use alloc::string::{String, ToString};
 use core::{fmt::Debug, marker::PhantomData, time::Duration};
 
 use serde::{de::DeserializeOwned, Serialize};
 
-#[cfg(test)]
+#[cfg(feature = "std")]
+use std::path::Path;
+
 use crate::inputs::Input;
 #[cfg(feature = "introspection")]
 use crate::monitors::PerfFeature;
@@ -14,14 +16,14 @@ use crate::state::NopState;
 use crate::{
     bolts::current_time,
     corpus::{Corpus, CorpusId, HasTestcase, Testcase},
-    events::{Event, EventConfig, EventFirer, EventProcessor, ProgressReporter},
+    events::{Event, EventConfig, EventFirer, EventProcessor, LogSeverity, ProgressReporter},
     executors::{Executor, ExitKind, HasObservers},
-    feedbacks::Feedback,
+    feedbacks::{Feedback, MapIndexesMetadata},
     inputs::UsesInput,
     mark_feature_time,
     observers::ObserversTuple,
     schedulers::Scheduler,
-    stages::StagesTuple,
+    stages::{StagesProgressMetadata, StagesTuple},
     start_timer,
     state::{HasClientPerfMonitor, HasCorpus, HasExecutions, HasMetadata, HasSolutions, UsesState},
     Error,
@@ -387,8 +389,13 @@ where
 
                 // Add the input to the main corpus
                 let mut testcase = Testcase::with_executions(input.clone(), *state.executions());
+                testcase.set_parent_id_optional(*state.corpus().current());
                 self.feedback_mut()
                     .append_metadata(state, observers, &mut testcase)?;
+                let coverage_hash = testcase
+                    .metadata_map()
+                    .get::<MapIndexesMetadata>()
+                    .map(MapIndexesMetadata::coverage_hash);
                 let idx = state.corpus_mut().add(testcase)?;
                 self.scheduler_mut().on_add(state, idx)?;
 
@@ -404,6 +411,7 @@ where
                         Event::NewTestcase {
                             input,
                             observers_buf,
+                            coverage_hash,
                             exit_kind: *exit_kind,
                             corpus_size: state.corpus().count(),
                             client_config: manager.configuration(),
@@ -421,16 +429,31 @@ where
 
                 // The input is a solution, add it to the respective corpus
                 let mut testcase = Testcase::with_executions(input, *state.executions());
+                // Keep the reason this input is a solution, like the crash handlers do.
+                testcase.add_metadata(*exit_kind);
                 testcase.set_parent_id_optional(*state.corpus().current());
                 self.objective_mut()
                     .append_metadata(state, observers, &mut testcase)?;
-                state.solutions_mut().add(testcase)?;
+                let idx = state.solutions_mut().add(testcase)?;
 
                 if send_events {
+                    let solution_name = {
+                        let solution = state.solutions().get(idx)?.borrow();
+                        match solution.filename() {
+                            Some(filename) => filename.clone(),
+                            None => solution
+                                .input()
+                                .as_ref()
+                                .map_or_else(String::new, |input| input.generate_name(idx.0)),
+                        }
+                    };
                     manager.fire(
                         state,
                         Event::Objective {
                             objective_size: state.solutions().count(),
+                            solution_name,
+                            exit_kind: *exit_kind,
+                            time: current_time(),
                         },
                     )?;
                 }
@@ -520,6 +543,10 @@ where
         let mut testcase = Testcase::with_executions(input.clone(), *state.executions());
         self.feedback_mut()
             .append_metadata(state, observers, &mut testcase)?;
+        let coverage_hash = testcase
+            .metadata_map()
+            .get::<MapIndexesMetadata>()
+            .map(MapIndexesMetadata::coverage_hash);
         let idx = state.corpus_mut().add(testcase)?;
         self.scheduler_mut().on_add(state, idx)?;
 
@@ -533,6 +560,7 @@ where
             Event::NewTestcase {
                 input,
                 observers_buf,
+                coverage_hash,
                 exit_kind,
                 corpus_size: state.corpus().count(),
                 client_config: manager.configuration(),
@@ -566,8 +594,17 @@ where
         #[cfg(feature = "introspection")]
         state.introspection_monitor_mut().start_timer();
 
-        // Get the next index from the scheduler
-        let idx = self.scheduler.next(state)?;
+        // Resume the entry the fuzzer restarted in the middle of, else get the next index from the scheduler
+        let resumed = state
+            .metadata_map()
+            .get::<StagesProgressMetadata>()
+            .and_then(StagesProgressMetadata::resumed_corpus_idx);
+        let idx = if let Some(idx) = resumed {
+            *state.corpus_mut().current_mut() = Some(idx);
+            idx
+        } else {
+            self.scheduler.next(state)?
+        };
 
         // Mark the elapsed time for the scheduler
         #[cfg(feature = "introspection")]
@@ -651,6 +688,81 @@ where
 
         Ok(exit_kind)
     }
+
+    /// Runs a single input once, e.g. to verify a crash or to build a regression suite.
+    /// The objective and the feedback decide whether the run is a solution or new coverage,
+    /// but the input is added to neither the corpus nor the solutions.
+    /// The feedbacks update their state, e.g. the history map, as for any other execution.
+    ///
+    /// An input that really crashes an in-process executor still ends up in its crash handler,
+    /// which adds it to the solutions and exits; use a fork or out-of-process executor to replay crashes.
+    pub fn replay<E, EM>(
+        &mut self,
+        state: &mut CS::State,
+        executor: &mut E,
+        manager: &mut EM,
+        input: &<CS::State as UsesInput>::Input,
+    ) -> Result<(ExitKind, ExecuteInputResult), Error>
+    where
+        E: Executor<EM, Self> + HasObservers<Observers = OT, State = CS::State>,
+        EM: EventFirer<State = CS::State>,
+        OT: ObserversTuple<CS::State>,
+    {
+        let exit_kind = self.execute_input(state, executor, manager, input)?;
+        let observers = executor.observers();
+
+        let res = if self
+            .objective_mut()
+            .is_interesting(state, manager, input, observers, &exit_kind)?
+        {
+            ExecuteInputResult::Solution
+        } else if self
+            .feedback_mut()
+            .is_interesting(state, manager, input, observers, &exit_kind)?
+        {
+            ExecuteInputResult::Corpus
+        } else {
+            ExecuteInputResult::None
+        };
+        self.feedback_mut().discard_metadata(state, input)?;
+        self.objective_mut().discard_metadata(state, input)?;
+
+        Ok((exit_kind, res))
+    }
+
+    /// Loads the input at `path`, as written by [`Input::to_file`], and [`Self::replay`]s it,
+    /// logging the outcome.
+    #[cfg(feature = "std")]
+    pub fn replay_file<E, EM, P>(
+        &mut self,
+        state: &mut CS::State,
+        executor: &mut E,
+        manager: &mut EM,
+        path: P,
+    ) -> Result<(ExitKind, ExecuteInputResult), Error>
+    where
+        E: Executor<EM, Self> + HasObservers<Observers = OT, State = CS::State>,
+        EM: EventFirer<State = CS::State>,
+        OT: ObserversTuple<CS::State>,
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref();
+        let input = <CS::State as UsesInput>::Input::from_file(path)?;
+        let (exit_kind, res) = self.replay(state, executor, manager, &input)?;
+
+        let outcome = match res {
+            ExecuteInputResult::None => "no new coverage",
+            ExecuteInputResult::Corpus => "new coverage",
+            ExecuteInputResult::Solution => "objective",
+        };
+        manager.log(
+            state,
+            LogSeverity::Info,
+            format!("Replayed {}: {exit_kind}, {outcome}", path.display()),
+        )?;
+
+        Ok((exit_kind, res))
+    }
 }
 
 /// Structs with this trait will execute an input
@@ -748,6 +860,92 @@ where
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use crate::{
+        bolts::{rands::StdRand, tuples::tuple_list},
+        corpus::{Corpus, InMemoryCorpus},
+        events::NopEventManager,
+        executors::{ExitKind, InProcessExecutor},
+        feedbacks::{ConstFeedback, CrashFeedback},
+        fuzzer::ExecuteInputResult,
+        inputs::{BytesInput, HasBytesVec},
+        schedulers::RandScheduler,
+        state::{HasCorpus, HasSolutions, StdState},
+        StdFuzzer,
+    };
+
+    #[test]
+    fn test_replay() {
+        let mut feedback = ConstFeedback::new(true);
+        let mut objective = CrashFeedback::new();
+        let mut state = StdState::new(
+            StdRand::with_seed(0),
+            InMemoryCorpus::<BytesInput>::new(),
+            InMemoryCorpus::new(),
+            &mut feedback,
+            &mut objective,
+        )
+        .unwrap();
+        let mut manager = NopEventManager::new();
+        let mut fuzzer = StdFuzzer::new(RandScheduler::new(), feedback, objective);
+
+        // Reports a crash without crashing, so the crash handler stays out of the way
+        let mut harness = |input: &BytesInput| {
+            if input.bytes()[0] == 0 {
+                ExitKind::Crash
+            } else {
+                ExitKind::Ok
+            }
+        };
+        let mut executor = InProcessExecutor::new(
+            &mut harness,
+            tuple_list!(),
+            &mut fuzzer,
+            &mut state,
+            &mut manager,
+        )
+        .unwrap();
+
+        let crash = BytesInput::new(vec![0]);
+        assert_eq!(
+            fuzzer
+                .replay(&mut state, &mut executor, &mut manager, &crash)
+                .unwrap(),
+            (ExitKind::Crash, ExecuteInputResult::Solution)
+        );
+        assert_eq!(
+            fuzzer
+                .replay(
+                    &mut state,
+                    &mut executor,
+                    &mut manager,
+                    &BytesInput::new(vec![1])
+                )
+                .unwrap(),
+            (ExitKind::Ok, ExecuteInputResult::Corpus)
+        );
+
+        #[cfg(feature = "std")]
+        {
+            use crate::inputs::Input;
+
+            let path = std::env::temp_dir().join("libafl_test_replay_file");
+            crash.to_file(&path).unwrap();
+            let replayed = fuzzer.replay_file(&mut state, &mut executor, &mut manager, &path);
+            std::fs::remove_file(&path).unwrap();
+            assert_eq!(
+                replayed.unwrap(),
+                (ExitKind::Crash, ExecuteInputResult::Solution)
+            );
+        }
+
+        // Replays are never added
+        assert_eq!(state.corpus().count(), 0);
+        assert_eq!(state.solutions().count(), 0);
+    }
+}
+
 #[cfg(feature = "python")]
 #[allow(missing_docs)]
 /// `Fuzzer` Python bindings
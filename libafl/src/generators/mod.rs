@@ -1,13 +1,13 @@
 //! Generators may generate bytes or, in general, data, for inputs.
 
-use alloc::{string::String, vec::Vec};
+use alloc::vec::Vec;
 use core::marker::PhantomData;
 
 use crate::{
     bolts::rands::Rand,
     inputs::{bytes::BytesInput, Input},
     state::HasRand,
-    Error, ErrorBacktrace,
+    Error,
 };
 
 pub mod gramatron;
@@ -39,9 +39,8 @@ where
     fn generate(&mut self, _state: &mut S) -> Result<I, Error> {
         match self.next() {
             Some(i) => Ok(i),
-            None => Err(Error::Empty(
-                String::from("No more items in iterator when generating inputs"),
-                ErrorBacktrace::new(),
+            None => Err(Error::empty(
+                "No more items in iterator when generating inputs",
             )),
         }
     }
@@ -101,10 +100,9 @@ where
     S: HasRand,
 {
     fn generate(&mut self, state: &mut S) -> Result<BytesInput, Error> {
-        let mut size = state.rand_mut().below(self.max_size as u64);
-        if size == 0 {
-            size = 1;
-        }
+        let size = state
+            .rand_mut()
+            .between(1, core::cmp::max(self.max_size, 1) as u64);
         let random_bytes: Vec<u8> = (0..size)
             .map(|_| state.rand_mut().below(256) as u8)
             .collect();
@@ -141,10 +139,9 @@ where
     S: HasRand,
 {
     fn generate(&mut self, state: &mut S) -> Result<BytesInput, Error> {
-        let mut size = state.rand_mut().below(self.max_size as u64);
-        if size == 0 {
-            size = 1;
-        }
+        let size = state
+            .rand_mut()
+            .between(1, core::cmp::max(self.max_size, 1) as u64);
         let printables = "0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz \t\n!\"#$%&'()*+,-./:;<=>?@[\\]^_`{|}~".as_bytes();
         let random_bytes: Vec<u8> = (0..size)
             .map(|_| *state.rand_mut().choose(printables))
@@ -341,3 +338,33 @@ pub mod pybind {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        generators::{Generator, RandBytesGenerator, RandPrintablesGenerator},
+        inputs::{BytesInput, HasBytesVec},
+        state::NopState,
+    };
+
+    #[test]
+    fn test_rand_generators_sizes() {
+        let mut state = NopState::<BytesInput>::new();
+        let mut bytes_gen = RandBytesGenerator::new(4);
+        let mut printables_gen = RandPrintablesGenerator::new(4);
+        let mut seen_max = false;
+        for _ in 0..1000 {
+            let input = bytes_gen.generate(&mut state).unwrap();
+            assert!((1..=4).contains(&input.bytes().len()));
+            seen_max |= input.bytes().len() == 4;
+
+            let input = printables_gen.generate(&mut state).unwrap();
+            assert!((1..=4).contains(&input.bytes().len()));
+            assert!(input
+                .bytes()
+                .iter()
+                .all(|b| b.is_ascii_graphic() || b.is_ascii_whitespace()));
+        }
+        assert!(seen_max);
+    }
+}
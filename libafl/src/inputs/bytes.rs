@@ -83,6 +83,13 @@ impl HasTargetBytes for BytesInput {
     }
 }
 
+impl AsRef<[u8]> for BytesInput {
+    #[inline]
+    fn as_ref(&self) -> &[u8] {
+        &self.bytes
+    }
+}
+
 impl HasLen for BytesInput {
     #[inline]
     fn len(&self) -> usize {
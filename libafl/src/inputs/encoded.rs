@@ -77,6 +77,11 @@ where
 
 impl InputDecoder for TokenInputEncoderDecoder {
     fn decode(&self, input: &EncodedInput, bytes: &mut Vec<u8>) -> Result<(), Error> {
+        if self.next_id == 0 && !input.codes().is_empty() {
+            return Err(Error::illegal_state(
+                "Cannot decode an input with an empty decoder table",
+            ));
+        }
         for id in input.codes() {
             let tok = self
                 .id_table
@@ -266,7 +271,7 @@ mod tests {
     use core::str::from_utf8;
 
     use crate::inputs::encoded::{
-        InputDecoder, InputEncoder, NaiveTokenizer, TokenInputEncoderDecoder,
+        EncodedInput, InputDecoder, InputEncoder, NaiveTokenizer, TokenInputEncoderDecoder,
     };
 
     #[test]
@@ -283,4 +288,12 @@ mod tests {
             "a = 'pippo baudo' ; b = c + a ".to_owned()
         );
     }
+
+    #[test]
+    fn test_decode_empty_table() {
+        let ed = TokenInputEncoderDecoder::new();
+        let mut bytes = vec![];
+        assert!(ed.decode(&EncodedInput::new(vec![]), &mut bytes).is_ok());
+        assert!(ed.decode(&EncodedInput::new(vec![1]), &mut bytes).is_err());
+    }
 }
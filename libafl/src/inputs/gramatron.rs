@@ -9,7 +9,11 @@ use core::{
 use ahash::RandomState;
 use serde::{Deserialize, Serialize};
 
-use crate::{bolts::HasLen, inputs::Input, Error};
+use crate::{
+    bolts::{ownedref::OwnedSlice, HasLen},
+    inputs::{HasTargetBytes, Input},
+    Error,
+};
 
 /// A terminal for gramatron grammar fuzzing
 #[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq, Hash)]
@@ -67,6 +71,16 @@ impl HasLen for GramatronInput {
     }
 }
 
+impl HasTargetBytes for GramatronInput {
+    /// The terminals' symbols, concatenated
+    #[inline]
+    fn target_bytes(&self) -> OwnedSlice<u8> {
+        let mut bytes = vec![];
+        self.unparse(&mut bytes);
+        OwnedSlice::from(bytes)
+    }
+}
+
 impl GramatronInput {
     /// Creates a new codes input using the given terminals
     #[must_use]
@@ -97,9 +111,9 @@ impl GramatronInput {
     /// Crop the value to the given length
     pub fn crop(&self, from: usize, to: usize) -> Result<Self, Error> {
         if from < to && to <= self.terms.len() {
-            let mut terms = vec![];
-            terms.clone_from_slice(&self.terms[from..to]);
-            Ok(Self { terms })
+            Ok(Self {
+                terms: self.terms[from..to].to_vec(),
+            })
         } else {
             Err(Error::illegal_argument("Invalid from or to argument"))
         }
@@ -12,6 +12,9 @@ pub use gramatron::*;
 pub mod generalized;
 pub use generalized::*;
 
+pub mod multi;
+pub use multi::*;
+
 #[cfg(feature = "nautilus")]
 pub mod nautilus;
 use alloc::{
@@ -21,7 +24,7 @@ use alloc::{
 };
 use core::{clone::Clone, fmt::Debug, marker::PhantomData};
 #[cfg(feature = "std")]
-use std::{fs::File, hash::Hash, io::Read, path::Path};
+use std::{fs, hash::Hash, path::Path};
 
 #[cfg(feature = "nautilus")]
 pub use nautilus::*;
@@ -39,9 +42,9 @@ pub trait Input: Clone + Serialize + serde::de::DeserializeOwned + Debug {
         Err(Error::not_implemented("Not supported in no_std"))
     }
 
-    /// Write this input to the file
+    /// Load the content of this input from a file
     fn from_file<P>(_path: P) -> Result<Self, Error> {
-        Err(Error::not_implemented("Not supprted in no_std"))
+        Err(Error::not_implemented("Not supported in no_std"))
     }
 
     /// Generate a name for this input
@@ -54,7 +57,7 @@ pub trait Input: Clone + Serialize + serde::de::DeserializeOwned + Debug {
 /// An input for the target
 #[cfg(feature = "std")]
 pub trait Input: Clone + Serialize + serde::de::DeserializeOwned + Debug {
-    /// Write this input to the file
+    /// Write this input to the file, serialized with `postcard`
     fn to_file<P>(&self, path: P) -> Result<(), Error>
     where
         P: AsRef<Path>,
@@ -62,15 +65,12 @@ pub trait Input: Clone + Serialize + serde::de::DeserializeOwned + Debug {
         write_file_atomic(path, &postcard::to_allocvec(self)?)
     }
 
-    /// Load the content of this input from a file
+    /// Load the content of this input from a file, as written by [`Input::to_file`]
     fn from_file<P>(path: P) -> Result<Self, Error>
     where
         P: AsRef<Path>,
     {
-        let mut file = File::open(path)?;
-        let mut bytes: Vec<u8> = vec![];
-        file.read_to_end(&mut bytes)?;
-        Ok(postcard::from_bytes(&bytes)?)
+        Ok(postcard::from_bytes(&fs::read(path)?)?)
     }
 
     /// Generate a name for this input, the user is responsible for making each name of testcase unique.
@@ -207,3 +207,22 @@ where
         (self.convert_cb)(input)
     }
 }
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use std::{env::temp_dir, fs};
+
+    use crate::inputs::{BytesInput, Input};
+
+    #[test]
+    fn test_input_file_roundtrip() {
+        let path = temp_dir().join(format!("libafl-input-{}", std::process::id()));
+        let input = BytesInput::new(vec![1, 2, 3]);
+
+        input.to_file(&path).unwrap();
+        let restored = BytesInput::from_file(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(input, restored);
+    }
+}
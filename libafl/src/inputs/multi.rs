@@ -0,0 +1,132 @@
+//! The `MultipartInput` is an input made of several named parts, for harnesses that take more
+//! than one buffer (e.g. key and value, header and body, or a set of files).
+//!
+//! All parts share the same input type `I`, since [`Clone`] and [`serde::de::Deserialize`]
+//! both require [`Sized`] and the parts can't be dynamically typed.
+
+use alloc::{string::String, vec::Vec};
+
+use serde::{Deserialize, Serialize};
+
+use crate::inputs::Input;
+
+/// An input composed of multiple parts, each identified by a (not necessarily unique) name
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub struct MultipartInput<I> {
+    /// The parts of this input
+    parts: Vec<I>,
+    /// The names of the parts, in the same order as `parts`
+    names: Vec<String>,
+}
+
+impl<I> Default for MultipartInput<I> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<I> MultipartInput<I> {
+    /// Creates a new, empty [`MultipartInput`]
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            parts: Vec::new(),
+            names: Vec::new(),
+        }
+    }
+
+    /// The parts of this input
+    #[must_use]
+    pub fn parts(&self) -> &[I] {
+        &self.parts
+    }
+
+    /// The parts of this input, mutable.
+    /// Parts can't be added or removed this way, so they always stay aligned with their names.
+    #[must_use]
+    pub fn parts_mut(&mut self) -> &mut [I] {
+        &mut self.parts
+    }
+
+    /// Get the part at the given index, mutable
+    #[must_use]
+    pub fn part_mut(&mut self, idx: usize) -> Option<&mut I> {
+        self.parts.get_mut(idx)
+    }
+
+    /// The names of the parts of this input
+    #[must_use]
+    pub fn names(&self) -> &[String] {
+        &self.names
+    }
+
+    /// Iterate over the parts with the given name, and their indices
+    pub fn parts_by_name<'a, 'b>(
+        &'b self,
+        name: &'a str,
+    ) -> impl Iterator<Item = (usize, &'b I)> + 'a
+    where
+        'b: 'a,
+    {
+        self.names
+            .iter()
+            .zip(&self.parts)
+            .enumerate()
+            .filter(move |(_, (part_name, _))| *part_name == name)
+            .map(|(idx, (_, part))| (idx, part))
+    }
+
+    /// Add a part to this input
+    pub fn add_part(&mut self, name: String, part: I) {
+        self.parts.push(part);
+        self.names.push(name);
+    }
+}
+
+impl<I> FromIterator<(String, I)> for MultipartInput<I> {
+    fn from_iter<T: IntoIterator<Item = (String, I)>>(iter: T) -> Self {
+        let (names, parts) = iter.into_iter().unzip();
+        Self { parts, names }
+    }
+}
+
+impl<I> Input for MultipartInput<I>
+where
+    I: Input,
+{
+    /// Generate a name for this input, from the names of its parts
+    fn generate_name(&self, idx: usize) -> String {
+        self.names
+            .iter()
+            .zip(&self.parts)
+            .map(|(name, part)| format!("{name}-{}", part.generate_name(idx)))
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{string::ToString, vec::Vec};
+
+    use crate::inputs::{BytesInput, MultipartInput};
+
+    #[test]
+    fn test_multipart_roundtrip() {
+        let input: MultipartInput<BytesInput> = [
+            ("key".to_string(), BytesInput::new(vec![1, 2])),
+            ("value".to_string(), BytesInput::new(vec![])),
+            ("key".to_string(), BytesInput::new(vec![3])),
+        ]
+        .into_iter()
+        .collect();
+
+        let bytes = postcard::to_allocvec(&input).unwrap();
+        let restored: MultipartInput<BytesInput> = postcard::from_bytes(&bytes).unwrap();
+        assert_eq!(input, restored);
+        assert_eq!(restored.parts()[1], BytesInput::new(vec![]));
+
+        let keys: Vec<usize> = restored.parts_by_name("key").map(|(idx, _)| idx).collect();
+        assert_eq!(keys, [0, 2]);
+    }
+}
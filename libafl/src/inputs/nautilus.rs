@@ -1,13 +1,14 @@
 //! Input for the [`Nautilus`](https://github.com/RUB-SysSec/nautilus) grammar fuzzer methods
 //!
 
-//use ahash::AHasher;
-//use core::hash::Hasher;
-
 use alloc::{rc::Rc, string::String, vec::Vec};
-use core::{cell::RefCell, convert::From};
-use std::hash::{Hash, Hasher};
+use core::{
+    cell::RefCell,
+    convert::From,
+    hash::{BuildHasher, Hash, Hasher},
+};
 
+use ahash::RandomState;
 use grammartec::{
     newtypes::NodeID,
     rule::RuleIDOrCustom,
@@ -32,13 +33,10 @@ pub struct NautilusInput {
 impl Input for NautilusInput {
     /// Generate a name for this input
     #[must_use]
-    fn generate_name(&self, idx: usize) -> String {
-        /*let mut hasher = AHasher::new_with_keys(0, 0);
-        for term in &self.terms {
-            hasher.write(term.symbol.as_bytes());
-        }
-        format!("{:016x}", hasher.finish())*/
-        format!("id:{idx}")
+    fn generate_name(&self, _idx: usize) -> String {
+        let mut hasher = RandomState::with_seeds(0, 0, 0, 0).build_hasher();
+        self.hash(&mut hasher);
+        format!("{:016x}", hasher.finish())
     }
 }
 
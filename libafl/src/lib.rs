@@ -154,6 +154,9 @@ pub enum Error {
     /// File related error
     #[cfg(feature = "std")]
     File(io::Error, ErrorBacktrace),
+    /// A call into the operating system failed, with the given context
+    #[cfg(feature = "std")]
+    OsError(io::Error, String, ErrorBacktrace),
     /// Optional val was supposed to be set, but isn't.
     EmptyOptional(String, ErrorBacktrace),
     /// Key not in Map
@@ -197,6 +200,24 @@ impl Error {
     pub fn file(arg: io::Error) -> Self {
         Error::File(arg, ErrorBacktrace::new())
     }
+    #[cfg(feature = "std")]
+    /// A call into the operating system failed
+    #[must_use]
+    pub fn os_error<S>(err: io::Error, msg: S) -> Self
+    where
+        S: Into<String>,
+    {
+        Error::OsError(err, msg.into(), ErrorBacktrace::new())
+    }
+    #[cfg(feature = "std")]
+    /// A call into the operating system failed, the reason is taken from `errno`
+    #[must_use]
+    pub fn last_os_error<S>(msg: S) -> Self
+    where
+        S: Into<String>,
+    {
+        Self::os_error(io::Error::last_os_error(), msg)
+    }
     /// Optional val was supposed to be set, but isn't.
     #[must_use]
     pub fn empty_optional<S>(arg: S) -> Self
@@ -293,6 +314,11 @@ impl fmt::Display for Error {
                 write!(f, "File IO failed: {:?}", &err)?;
                 display_error_backtrace(f, b)
             }
+            #[cfg(feature = "std")]
+            Self::OsError(err, s, b) => {
+                write!(f, "OS error: {0}: {1}", &s, &err)?;
+                display_error_backtrace(f, b)
+            }
             Self::EmptyOptional(s, b) => {
                 write!(f, "Optional value `{0}` was not set", &s)?;
                 display_error_backtrace(f, b)
@@ -356,7 +382,7 @@ impl From<serde_json::Error> for Error {
 #[cfg(all(unix, feature = "std"))]
 impl From<nix::Error> for Error {
     fn from(err: nix::Error) -> Self {
-        Self::unknown(format!("Unix error: {err:?}"))
+        Self::os_error(io::Error::from_raw_os_error(err as i32), "Unix error")
     }
 }
 
@@ -423,7 +449,14 @@ impl From<pyo3::PyErr> for Error {
 }
 
 #[cfg(feature = "std")]
-impl std::error::Error for Error {}
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::File(err, _) | Self::OsError(err, _, _) => Some(err),
+            _ => None,
+        }
+    }
+}
 
 /// The purpose of this module is to alleviate imports of many components by adding a glob import.
 #[cfg(feature = "prelude")]
@@ -465,9 +498,22 @@ mod tests {
         schedulers::RandScheduler,
         stages::StdMutationalStage,
         state::{HasCorpus, StdState},
-        StdFuzzer,
+        Error, StdFuzzer,
     };
 
+    #[test]
+    fn test_error_source() {
+        use std::{error::Error as _, io};
+
+        let err = Error::os_error(
+            io::Error::new(io::ErrorKind::NotFound, "missing"),
+            "open failed",
+        );
+        let source = err.source().unwrap().downcast_ref::<io::Error>().unwrap();
+        assert_eq!(source.kind(), io::ErrorKind::NotFound);
+        assert!(Error::illegal_state("no cause").source().is_none());
+    }
+
     #[test]
     #[allow(clippy::similar_names)]
     fn test_fuzzer() {
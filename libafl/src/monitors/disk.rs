@@ -93,6 +93,19 @@ exec_sec = {}
                 )
                 .expect("Failed to write to the TOML file");
 
+                if let (Some(name), Some(exit_kind), Some(time)) = (
+                    &client.last_objective_name,
+                    client.last_objective_exit_kind,
+                    client.last_objective_time,
+                ) {
+                    write!(
+                        &mut file,
+                        "last_objective = \"{name}\"\nlast_objective_exit_kind = \"{exit_kind}\"\nlast_objective_time = \"{}\"\n",
+                        format_duration_hms(&time)
+                    )
+                    .expect("Failed to write to the TOML file");
+                }
+
                 for (key, val) in &client.user_monitor {
                     let k: String = key
                         .chars()
@@ -205,7 +218,8 @@ where
                 "objectives": self.base.objective_size(),
                 "executions": self.base.total_execs(),
                 "exec_sec": self.base.execs_per_sec(),
-                "clients": &self.client_stats()[1..]
+                // The first entry is the broker, skip it like the TOML monitor does
+                "client_stats": self.client_stats().get(1..).unwrap_or_default(),
             });
             writeln!(&file, "{line}").expect("Unable to write JSON to file");
         }
@@ -23,7 +23,10 @@ pub use disk::{OnDiskJSONMonitor, OnDiskTOMLMonitor};
 use hashbrown::HashMap;
 use serde::{Deserialize, Serialize};
 
-use crate::bolts::{current_time, format_duration_hms, ClientId};
+use crate::{
+    bolts::{current_time, format_duration_hms, ClientId},
+    executors::ExitKind,
+};
 
 #[cfg(feature = "afl_exec_sec")]
 const CLIENT_STATS_TIME_WINDOW_SECS: u64 = 5; // 5 seconds
@@ -94,6 +97,12 @@ pub struct ClientStats {
     pub prev_state_executions: u64,
     /// The size of the objectives corpus for this client
     pub objective_size: u64,
+    /// The name of the last objective of this client, to reproduce it
+    pub last_objective_name: Option<String>,
+    /// How the execution of the last objective of this client finished
+    pub last_objective_exit_kind: Option<ExitKind>,
+    /// When the last objective of this client was found, since the start of the fuzzing run
+    pub last_objective_time: Option<Duration>,
     /// The last reported executions for this client
     #[cfg(feature = "afl_exec_sec")]
     pub last_window_executions: u64,
@@ -148,6 +157,20 @@ impl ClientStats {
         self.objective_size = objective_size;
     }
 
+    /// We got a new objective `name` for this client, found at `time` by an execution that finished with `exit_kind`.
+    /// Both `time` and `start_time` are since the epoch, as returned by [`current_time`].
+    pub fn update_last_objective(
+        &mut self,
+        name: &str,
+        exit_kind: ExitKind,
+        time: Duration,
+        start_time: Duration,
+    ) {
+        self.last_objective_name = Some(name.into());
+        self.last_objective_exit_kind = Some(exit_kind);
+        self.last_objective_time = Some(time.saturating_sub(start_time));
+    }
+
     /// Get the calculated executions per second for this client
     #[allow(clippy::cast_precision_loss, clippy::cast_sign_loss)]
     #[cfg(feature = "afl_exec_sec")]
@@ -208,7 +231,7 @@ impl ClientStats {
     }
 
     /// Get a user-defined stat using the name
-    pub fn get_user_stats(&mut self, name: &str) -> Option<&UserStats> {
+    pub fn get_user_stats(&self, name: &str) -> Option<&UserStats> {
         self.user_monitor.get(name)
     }
 
@@ -703,6 +726,10 @@ impl ClientPerfMonitor {
         self.update_scheduler(monitor.scheduler);
         self.update_manager(monitor.manager);
         self.update_stages(&monitor.stages);
+        // `update_stages` resized our stages to fit, mark the ones used by the other monitor
+        for (used, other_used) in self.stages_used.iter_mut().zip(&monitor.stages_used) {
+            *used |= *other_used;
+        }
         self.update_feedbacks(&monitor.feedbacks);
     }
 
@@ -1115,7 +1142,66 @@ pub mod pybind {
 
 #[cfg(test)]
 mod test {
-    use crate::monitors::prettify_float;
+    use core::time::Duration;
+
+    #[cfg(feature = "introspection")]
+    use crate::monitors::{ClientPerfMonitor, PerfFeature};
+    use crate::{
+        executors::ExitKind,
+        monitors::{prettify_float, ClientStats, UserStats},
+    };
+
+    #[test]
+    fn test_client_stats_executions_after_restart() {
+        let mut stats = ClientStats::default();
+        stats.update_executions(100, Duration::from_secs(1));
+        assert_eq!(stats.executions, 100);
+        stats.update_executions(150, Duration::from_secs(2));
+        assert_eq!(stats.executions, 150);
+
+        // The client restarted without keeping its state, keep counting from where we left off
+        stats.update_executions(20, Duration::from_secs(3));
+        assert_eq!(stats.executions, 170);
+    }
+
+    #[test]
+    fn test_client_last_objective() {
+        let mut stats = ClientStats::default();
+        assert!(stats.last_objective_exit_kind.is_none());
+        stats.update_last_objective(
+            "crash-0",
+            ExitKind::Timeout,
+            Duration::from_secs(1005),
+            Duration::from_secs(1000),
+        );
+        assert_eq!(stats.last_objective_name.as_deref(), Some("crash-0"));
+        assert_eq!(stats.last_objective_exit_kind, Some(ExitKind::Timeout));
+        assert_eq!(stats.last_objective_time, Some(Duration::from_secs(5)));
+    }
+
+    #[test]
+    fn test_client_user_stats() {
+        let mut stats = ClientStats::default();
+        assert!(stats.get_user_stats("stability").is_none());
+        stats.update_user_stats("stability".into(), UserStats::Ratio(1, 2));
+        assert!(matches!(
+            stats.get_user_stats("stability"),
+            Some(UserStats::Ratio(1, 2))
+        ));
+    }
+
+    #[cfg(feature = "introspection")]
+    #[test]
+    fn test_client_perf_monitor_update() {
+        let mut client = ClientPerfMonitor::new();
+        client.finish_stage();
+        client.update_feature(PerfFeature::Mutate, 42);
+
+        let mut aggregated = ClientPerfMonitor::new();
+        aggregated.update(&client);
+        assert!(aggregated.used_stages().map(|(idx, _)| idx).eq([1]));
+    }
+
     #[test]
     fn test_prettify_float() {
         assert_eq!(prettify_float(123423123.0), "123.4M");
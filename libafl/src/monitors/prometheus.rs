@@ -173,6 +173,8 @@ where
                 UserStats::Number(n) => n as f64,
                 UserStats::Float(f) => f,
                 UserStats::String(_s) => 0.0,
+                // Don't export NaN or inf for ratios of nothing, e.g. before the first execution
+                UserStats::Ratio(_, 0) => 0.0,
                 UserStats::Ratio(a, b) => (a as f64 / b as f64) * 100.0,
             };
             self.custom_stat
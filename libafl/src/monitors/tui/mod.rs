@@ -58,8 +58,10 @@ impl TimedStats {
 
     pub fn add(&mut self, time: Duration, item: u64) {
         if self.series.is_empty() || self.series.back().unwrap().item != item {
-            if self.series.front().is_some()
-                && time - self.series.front().unwrap().time > self.window
+            while self
+                .series
+                .front()
+                .map_or(false, |front| time.saturating_sub(front.time) > self.window)
             {
                 self.series.pop_front();
             }
@@ -68,21 +70,19 @@ impl TimedStats {
     }
 
     pub fn add_now(&mut self, item: u64) {
-        if self.series.is_empty() || self.series[self.series.len() - 1].item != item {
-            let time = current_time();
-            if self.series.front().is_some()
-                && time - self.series.front().unwrap().time > self.window
-            {
-                self.series.pop_front();
-            }
-            self.series.push_back(TimedStat { time, item });
-        }
+        self.add(current_time(), item);
     }
 
     pub fn update_window(&mut self, window: Duration) {
         self.window = window;
         while !self.series.is_empty()
-            && self.series.back().unwrap().time - self.series.front().unwrap().time > window
+            && self
+                .series
+                .back()
+                .unwrap()
+                .time
+                .saturating_sub(self.series.front().unwrap().time)
+                > window
         {
             self.series.pop_front();
         }
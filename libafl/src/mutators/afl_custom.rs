@@ -0,0 +1,249 @@
+//! A bridge to use `AFL++` custom mutators as [`Mutator`]s.
+//! The custom mutator is a shared object exporting the `afl_custom_*` functions,
+//! see [the `AFL++` docs](https://github.com/AFLplusplus/AFLplusplus/blob/stable/docs/custom_mutators.md).
+
+use alloc::{string::String, vec::Vec};
+use core::{
+    ffi::c_void,
+    fmt::{self, Debug, Formatter},
+    mem::transmute,
+    ptr, slice,
+};
+use std::{
+    ffi::{CStr, CString},
+    os::unix::ffi::OsStrExt,
+    path::Path,
+};
+
+use libc::c_uint;
+
+use crate::{
+    bolts::{rands::Rand, tuples::Named},
+    corpus::Corpus,
+    executors::InputPostProcessor,
+    inputs::HasBytesVec,
+    mutators::{MutationResult, Mutator},
+    random_corpus_id,
+    state::{HasCorpus, HasMaxSize, HasRand},
+    Error,
+};
+
+/// `void *afl_custom_init(afl_state_t *afl, unsigned int seed)`
+type InitFn = unsafe extern "C" fn(*mut c_void, c_uint) -> *mut c_void;
+/// `size_t afl_custom_fuzz(void *data, u8 *buf, size_t buf_size, u8 **out_buf, u8 *add_buf, size_t add_buf_size, size_t max_size)`
+type FuzzFn =
+    unsafe extern "C" fn(*mut c_void, *mut u8, usize, *mut *mut u8, *mut u8, usize, usize) -> usize;
+/// `size_t afl_custom_post_process(void *data, u8 *buf, size_t buf_size, u8 **out_buf)`
+type PostProcessFn = unsafe extern "C" fn(*mut c_void, *mut u8, usize, *mut *mut u8) -> usize;
+/// `void afl_custom_deinit(void *data)`
+type DeinitFn = unsafe extern "C" fn(*mut c_void);
+
+/// Returns the last `dlerror`, if any
+fn dl_error() -> String {
+    unsafe {
+        let err = libc::dlerror();
+        if err.is_null() {
+            "unknown error".into()
+        } else {
+            CStr::from_ptr(err).to_string_lossy().into_owned()
+        }
+    }
+}
+
+/// A [`Mutator`] calling into an `AFL++` custom mutator shared object.
+///
+/// `afl_custom_init` and `afl_custom_fuzz` are required, `afl_custom_post_process`
+/// and `afl_custom_deinit` are optional.
+/// `afl_custom_init` is called with a `NULL` `afl_state_t`, so the custom mutator must not rely on it.
+/// To run `afl_custom_post_process` before each execution, use a second instance as the
+/// post-processor of a [`crate::executors::PostProcessExecutor`].
+pub struct CustomMutatorBridge {
+    /// The handle returned by `dlopen`
+    handle: *mut c_void,
+    /// The data returned by `afl_custom_init`
+    data: *mut c_void,
+    fuzz_fn: FuzzFn,
+    post_process_fn: Option<PostProcessFn>,
+    deinit_fn: Option<DeinitFn>,
+    name: String,
+}
+
+impl Debug for CustomMutatorBridge {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CustomMutatorBridge")
+            .field("name", &self.name)
+            .field("post_process", &self.post_process_fn.is_some())
+            .finish_non_exhaustive()
+    }
+}
+
+impl CustomMutatorBridge {
+    /// Load the custom mutator at `path` and initialize it with the given `seed`
+    pub fn new<P>(path: P, seed: u32) -> Result<Self, Error>
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref();
+        let c_path = CString::new(path.as_os_str().as_bytes()).map_err(|_| {
+            Error::illegal_argument(format!("Invalid custom mutator path {}", path.display()))
+        })?;
+
+        unsafe {
+            let handle = libc::dlopen(c_path.as_ptr(), libc::RTLD_NOW | libc::RTLD_LOCAL);
+            if handle.is_null() {
+                return Err(Error::illegal_argument(format!(
+                    "Could not load custom mutator {}: {}",
+                    path.display(),
+                    dl_error()
+                )));
+            }
+
+            let sym = |name: &[u8]| -> *mut c_void { libc::dlsym(handle, name.as_ptr().cast()) };
+
+            let init_fn = sym(b"afl_custom_init\0");
+            let fuzz_fn = sym(b"afl_custom_fuzz\0");
+            if init_fn.is_null() || fuzz_fn.is_null() {
+                libc::dlclose(handle);
+                return Err(Error::illegal_argument(format!(
+                    "Custom mutator {} does not export afl_custom_init and afl_custom_fuzz",
+                    path.display()
+                )));
+            }
+            let init_fn = transmute::<*mut c_void, InitFn>(init_fn);
+            let fuzz_fn = transmute::<*mut c_void, FuzzFn>(fuzz_fn);
+
+            let post_process_fn = sym(b"afl_custom_post_process\0");
+            let post_process_fn = (!post_process_fn.is_null())
+                .then(|| transmute::<*mut c_void, PostProcessFn>(post_process_fn));
+            let deinit_fn = sym(b"afl_custom_deinit\0");
+            let deinit_fn =
+                (!deinit_fn.is_null()).then(|| transmute::<*mut c_void, DeinitFn>(deinit_fn));
+
+            let data = init_fn(ptr::null_mut(), seed);
+            if data.is_null() {
+                libc::dlclose(handle);
+                return Err(Error::illegal_state(format!(
+                    "afl_custom_init of {} failed",
+                    path.display()
+                )));
+            }
+
+            Ok(Self {
+                handle,
+                data,
+                fuzz_fn,
+                post_process_fn,
+                deinit_fn,
+                name: format!("CustomMutatorBridge({})", path.display()),
+            })
+        }
+    }
+
+    /// Returns `true` if the custom mutator exports `afl_custom_post_process`
+    #[must_use]
+    pub fn has_post_process(&self) -> bool {
+        self.post_process_fn.is_some()
+    }
+
+    /// Run `afl_custom_post_process` on the given input, if the custom mutator exports it.
+    /// The input is left untouched if the post processing reports an empty result.
+    pub fn post_process<I>(&mut self, input: &mut I)
+    where
+        I: HasBytesVec,
+    {
+        let Some(post_process_fn) = self.post_process_fn else {
+            return;
+        };
+        let mut out_buf: *mut u8 = ptr::null_mut();
+        let bytes = input.bytes_mut();
+        unsafe {
+            let new_size =
+                post_process_fn(self.data, bytes.as_mut_ptr(), bytes.len(), &mut out_buf);
+            if new_size != 0 && !out_buf.is_null() {
+                // `out_buf` may point into `bytes`, copy it out before replacing them
+                *bytes = slice::from_raw_parts(out_buf, new_size).to_vec();
+            }
+        }
+    }
+}
+
+impl<S> Mutator<S::Input, S> for CustomMutatorBridge
+where
+    S: HasCorpus + HasRand + HasMaxSize,
+    S::Input: HasBytesVec,
+{
+    fn mutate(
+        &mut self,
+        state: &mut S,
+        input: &mut S::Input,
+        _stage_idx: i32,
+    ) -> Result<MutationResult, Error> {
+        let max_size = state.max_size();
+
+        // The custom mutator may splice with another testcase
+        let mut add_buf: Vec<u8> = if state.corpus().is_empty() {
+            Vec::new()
+        } else {
+            let idx = random_corpus_id!(state.corpus(), state.rand_mut());
+            let mut testcase = state.corpus().get(idx)?.borrow_mut();
+            testcase.load_input(state.corpus())?.bytes().to_vec()
+        };
+
+        let mut out_buf: *mut u8 = ptr::null_mut();
+        let bytes = input.bytes_mut();
+        unsafe {
+            let new_size = (self.fuzz_fn)(
+                self.data,
+                bytes.as_mut_ptr(),
+                bytes.len(),
+                &mut out_buf,
+                add_buf.as_mut_ptr(),
+                add_buf.len(),
+                max_size,
+            );
+            if new_size == 0 || out_buf.is_null() {
+                return Ok(MutationResult::Skipped);
+            }
+            // `out_buf` may point into `bytes`, copy it out before replacing them
+            *bytes = slice::from_raw_parts(out_buf, new_size.min(max_size)).to_vec();
+        }
+        Ok(MutationResult::Mutated)
+    }
+}
+
+impl<I> InputPostProcessor<I> for CustomMutatorBridge
+where
+    I: HasBytesVec,
+{
+    fn post_process(&mut self, input: &mut I) -> Result<(), Error> {
+        CustomMutatorBridge::post_process(self, input);
+        Ok(())
+    }
+}
+
+impl Named for CustomMutatorBridge {
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl Drop for CustomMutatorBridge {
+    fn drop(&mut self) {
+        unsafe {
+            if let Some(deinit_fn) = self.deinit_fn {
+                deinit_fn(self.data);
+            }
+            libc::dlclose(self.handle);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::mutators::CustomMutatorBridge;
+
+    #[test]
+    fn test_custom_mutator_missing() {
+        assert!(CustomMutatorBridge::new("/nonexistent/libcustom_mutator.so", 0).is_err());
+    }
+}
@@ -19,6 +19,12 @@ pub mod grimoire;
 pub use grimoire::*;
 pub mod tuneable;
 pub use tuneable::*;
+pub mod multi;
+pub use multi::*;
+#[cfg(all(unix, feature = "std"))]
+pub mod afl_custom;
+#[cfg(all(unix, feature = "std"))]
+pub use afl_custom::*;
 
 #[cfg(feature = "nautilus")]
 pub mod nautilus;
@@ -140,6 +140,11 @@ const PERIOD_PILOT_COEF: f64 = 5000.0;
 impl MOpt {
     /// Creates a new [`struct@MOpt`] instance.
     pub fn new(operator_num: usize, swarm_num: usize, rand_seed: u64) -> Result<Self, Error> {
+        if operator_num == 0 || swarm_num == 0 {
+            return Err(Error::illegal_argument(
+                "MOpt needs at least one mutation operator and one swarm".to_string(),
+            ));
+        }
         let mut mopt = Self {
             rand: StdRand::with_seed(rand_seed),
             total_finds: 0,
@@ -0,0 +1,166 @@
+//! Mutators for [`MultipartInput`]s, the inputs made of several named parts.
+
+use alloc::{string::String, vec::Vec};
+
+use crate::{
+    bolts::{rands::Rand, tuples::Named},
+    corpus::{Corpus, CorpusId},
+    inputs::{Input, MultipartInput},
+    mutators::{MutationResult, Mutator},
+    random_corpus_id,
+    state::{HasCorpus, HasRand},
+    Error,
+};
+
+/// A [`Mutator`] that applies the wrapped [`Mutator`] to one randomly chosen part of a [`MultipartInput`]
+#[derive(Debug)]
+pub struct MultipartRandomPartMutator<M> {
+    mutator: M,
+    name: String,
+}
+
+impl<I, M, S> Mutator<MultipartInput<I>, S> for MultipartRandomPartMutator<M>
+where
+    M: Mutator<I, S>,
+    S: HasRand,
+{
+    fn mutate(
+        &mut self,
+        state: &mut S,
+        input: &mut MultipartInput<I>,
+        stage_idx: i32,
+    ) -> Result<MutationResult, Error> {
+        let count = input.parts().len();
+        if count == 0 {
+            return Ok(MutationResult::Skipped);
+        }
+        let idx = state.rand_mut().below(count as u64) as usize;
+        self.mutator
+            .mutate(state, &mut input.parts_mut()[idx], stage_idx)
+    }
+
+    fn post_exec(
+        &mut self,
+        state: &mut S,
+        stage_idx: i32,
+        corpus_idx: Option<CorpusId>,
+    ) -> Result<(), Error> {
+        self.mutator.post_exec(state, stage_idx, corpus_idx)
+    }
+}
+
+impl<M> Named for MultipartRandomPartMutator<M> {
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl<M> MultipartRandomPartMutator<M>
+where
+    M: Named,
+{
+    /// Creates a new [`MultipartRandomPartMutator`], wrapping the given `mutator`.
+    #[must_use]
+    pub fn new(mutator: M) -> Self {
+        let name = format!("MultipartRandomPartMutator<{}>", mutator.name());
+        Self { mutator, name }
+    }
+}
+
+/// A [`Mutator`] replacing a random part of a [`MultipartInput`] with a part of the same name
+/// taken from another testcase in the corpus.
+#[derive(Debug, Default)]
+pub struct MultipartCrossoverReplaceMutator;
+
+impl<I, S> Mutator<MultipartInput<I>, S> for MultipartCrossoverReplaceMutator
+where
+    I: Input,
+    S: HasCorpus<Input = MultipartInput<I>> + HasRand,
+{
+    fn mutate(
+        &mut self,
+        state: &mut S,
+        input: &mut MultipartInput<I>,
+        _stage_idx: i32,
+    ) -> Result<MutationResult, Error> {
+        let count = input.parts().len();
+        if count == 0 {
+            return Ok(MutationResult::Skipped);
+        }
+
+        // We don't want to use the testcase we're already using for splicing
+        let idx = random_corpus_id!(state.corpus(), state.rand_mut());
+        if let Some(cur) = state.corpus().current() {
+            if idx == *cur {
+                return Ok(MutationResult::Skipped);
+            }
+        }
+
+        let part_idx = state.rand_mut().below(count as u64) as usize;
+        let rand_num = state.rand_mut().next() as usize;
+
+        let mut other_testcase = state.corpus().get(idx)?.borrow_mut();
+        let other = other_testcase.load_input(state.corpus())?;
+
+        let candidates: Vec<&I> = other
+            .parts_by_name(&input.names()[part_idx])
+            .map(|(_, part)| part)
+            .collect();
+        if candidates.is_empty() {
+            return Ok(MutationResult::Skipped);
+        }
+
+        input.parts_mut()[part_idx] = candidates[rand_num % candidates.len()].clone();
+        Ok(MutationResult::Mutated)
+    }
+}
+
+impl Named for MultipartCrossoverReplaceMutator {
+    fn name(&self) -> &str {
+        "MultipartCrossoverReplaceMutator"
+    }
+}
+
+impl MultipartCrossoverReplaceMutator {
+    /// Creates a new [`MultipartCrossoverReplaceMutator`].
+    #[must_use]
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::string::ToString;
+
+    use crate::{
+        inputs::{BytesInput, HasBytesVec, MultipartInput},
+        mutators::{BitFlipMutator, MultipartRandomPartMutator, MutationResult, Mutator},
+        state::NopState,
+    };
+
+    #[test]
+    fn test_multipart_random_part() {
+        let mut state = NopState::<MultipartInput<BytesInput>>::new();
+        let mut input: MultipartInput<BytesInput> = [
+            ("key".to_string(), BytesInput::new(vec![0; 4])),
+            ("value".to_string(), BytesInput::new(vec![0; 4])),
+        ]
+        .into_iter()
+        .collect();
+
+        let mut mutator = MultipartRandomPartMutator::new(BitFlipMutator::new());
+        assert_eq!(
+            mutator.mutate(&mut state, &mut input, 0).unwrap(),
+            MutationResult::Mutated
+        );
+
+        let mutated = input
+            .parts()
+            .iter()
+            .filter(|part| part.bytes() != [0; 4])
+            .count();
+        assert_eq!(mutated, 1);
+        assert_eq!(input.names(), ["key", "value"]);
+    }
+}
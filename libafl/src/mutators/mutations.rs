@@ -1270,30 +1270,24 @@ where
         input: &mut S::Input,
         _stage_idx: i32,
     ) -> Result<MutationResult, Error> {
-        // We don't want to use the testcase we're already using for splicing
-        let idx = random_corpus_id!(state.corpus(), state.rand_mut());
-        if let Some(cur) = state.corpus().current() {
-            if idx == *cur {
-                return Ok(MutationResult::Skipped);
-            }
-        }
-
-        let (first_diff, last_diff) = {
-            let mut other_testcase = state.corpus().get(idx)?.borrow_mut();
-            let other = other_testcase.load_input(state.corpus())?;
-
-            let mut counter: u32 = 0;
-            loop {
+        // Try a few other testcases, until we find one that differs enough from the input
+        let mut counter: u32 = 0;
+        let (idx, first_diff, last_diff) = loop {
+            // We don't want to use the testcase we're already using for splicing
+            let idx = random_corpus_id!(state.corpus(), state.rand_mut());
+            if *state.corpus().current() != Some(idx) {
+                let mut other_testcase = state.corpus().get(idx)?.borrow_mut();
+                let other = other_testcase.load_input(state.corpus())?;
                 let (f, l) = locate_diffs(input.bytes(), other.bytes());
 
                 if f != l && f >= 0 && l >= 2 {
-                    break (f as u64, l as u64);
+                    break (idx, f as u64, l as u64);
                 }
-                if counter == 3 {
-                    return Ok(MutationResult::Skipped);
-                }
-                counter += 1;
             }
+            if counter == 3 {
+                return Ok(MutationResult::Skipped);
+            }
+            counter += 1;
         };
 
         let split_at = state.rand_mut().between(first_diff, last_diff) as usize;
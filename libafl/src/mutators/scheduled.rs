@@ -360,18 +360,15 @@ where
     S: HasRand + HasCorpus,
     SM: ScheduledMutator<I, MT, S>,
 {
-    /// Compute the number of iterations used to apply stacked mutations
-    fn iterations(&self, state: &mut S, _: &I) -> u64 {
-        1 << (1 + state.rand_mut().below(6))
+    /// Compute the number of iterations used to apply stacked mutations,
+    /// as decided by the wrapped [`ScheduledMutator`]
+    fn iterations(&self, state: &mut S, input: &I) -> u64 {
+        self.scheduled.iterations(state, input)
     }
 
-    /// Get the next mutation to apply
-    fn schedule(&self, state: &mut S, _: &I) -> MutationId {
-        debug_assert!(!self.scheduled.mutations().is_empty());
-        state
-            .rand_mut()
-            .below(self.scheduled.mutations().len() as u64)
-            .into()
+    /// Get the next mutation to apply, as decided by the wrapped [`ScheduledMutator`]
+    fn schedule(&self, state: &mut S, input: &I) -> MutationId {
+        self.scheduled.schedule(state, input)
     }
 
     fn scheduled_mutate(
@@ -75,25 +75,27 @@ impl Tokens {
     }
 
     /// Parse autodict section
+    ///
+    /// Each token is prefixed by its length as a single byte.
+    /// A truncated last entry is ignored.
     pub fn parse_autodict(&mut self, slice: &[u8], size: usize) {
+        let slice = &slice[..size.min(slice.len())];
         let mut head = 0;
-        loop {
-            if head >= size {
-                // Sanity Check
-                assert!(head == size);
-                break;
-            }
+        while head < slice.len() {
             let size = slice[head] as usize;
             head += 1;
-            if size > 0 {
-                self.add_token(&slice[head..head + size].to_vec());
-                log::info!(
-                    "Token size: {} content: {:x?}",
-                    size,
-                    &slice[head..head + size].to_vec()
+            let Some(token) = slice.get(head..head + size) else {
+                log::warn!(
+                    "Autodict token at offset {} exceeds the section, ignoring it",
+                    head - 1
                 );
-                head += size;
+                break;
+            };
+            if size > 0 {
+                self.add_token(&token.to_vec());
+                log::info!("Token size: {} content: {:x?}", size, token);
             }
+            head += size;
         }
     }
 
@@ -161,7 +163,7 @@ impl Tokens {
         let reader = BufReader::new(file);
 
         for line in reader.lines() {
-            let line = line.unwrap();
+            let line = line?;
             let line = line.trim_start().trim_end();
 
             // we are only interested in '"..."', not prefixed 'foo = '
@@ -170,7 +172,7 @@ impl Tokens {
                 continue;
             }
             let Some(pos_quote) = line.find('\"') else { return Err(Error::illegal_argument(format!("Illegal line: {line}"))) };
-            if line.chars().nth(line.len() - 1) != Some('"') {
+            if !line.ends_with('"') {
                 return Err(Error::illegal_argument(format!("Illegal line: {line}")));
             }
 
@@ -564,10 +566,14 @@ where
                     let mut size = core::cmp::min(v.0.len(), len - i);
                     while size != 0 {
                         if v.0[0..size] == input.bytes()[i..i + size] {
-                            unsafe {
-                                buffer_copy(input.bytes_mut(), &v.1, 0, i, size);
+                            // Never copy more than the replacement has to offer
+                            let size = core::cmp::min(size, v.1.len());
+                            if size > 0 {
+                                unsafe {
+                                    buffer_copy(input.bytes_mut(), &v.1, 0, i, size);
+                                }
+                                result = MutationResult::Mutated;
                             }
-                            result = MutationResult::Mutated;
                             break 'outer;
                         }
                         size -= 1;
@@ -575,10 +581,14 @@ where
                     size = core::cmp::min(v.1.len(), len - i);
                     while size != 0 {
                         if v.1[0..size] == input.bytes()[i..i + size] {
-                            unsafe {
-                                buffer_copy(input.bytes_mut(), &v.0, 0, i, size);
+                            // Never copy more than the replacement has to offer
+                            let size = core::cmp::min(size, v.0.len());
+                            if size > 0 {
+                                unsafe {
+                                    buffer_copy(input.bytes_mut(), &v.0, 0, i, size);
+                                }
+                                result = MutationResult::Mutated;
                             }
-                            result = MutationResult::Mutated;
                             break 'outer;
                         }
                         size -= 1;
@@ -1576,8 +1586,16 @@ mod tests {
     #[cfg(feature = "std")]
     use std::fs;
 
-    #[cfg(feature = "std")]
-    use super::Tokens;
+    use super::{I2SRandReplace, Tokens};
+    use crate::{
+        bolts::rands::StdRand,
+        corpus::InMemoryCorpus,
+        feedbacks::ConstFeedback,
+        inputs::{BytesInput, HasBytesVec},
+        mutators::{MutationResult, Mutator},
+        observers::cmp::{CmpValues, CmpValuesMetadata},
+        state::{HasMetadata, StdState},
+    };
 
     #[cfg(feature = "std")]
     #[test]
@@ -1589,11 +1607,43 @@ token1@123="AAA"
 token1="A\x41A"
 "A\AA"
 token2="B"
+token3="äöü"
         "###;
         fs::write("test.tkns", data).expect("Unable to write test.tkns");
         let tokens = Tokens::from_file("test.tkns").unwrap();
         log::info!("Token file entries: {:?}", tokens.tokens());
-        assert_eq!(tokens.tokens().len(), 2);
+        assert_eq!(tokens.tokens().len(), 3);
+        assert_eq!(tokens.tokens()[2], "äöü".as_bytes());
         let _res = fs::remove_file("test.tkns");
     }
+
+    #[test]
+    fn test_parse_autodict() {
+        let mut tokens = Tokens::new();
+        tokens.parse_autodict(b"\x03abc\x00\x01d\x05ef", 10);
+        assert_eq!(tokens.tokens(), &[b"abc".to_vec(), b"d".to_vec()]);
+    }
+
+    #[test]
+    fn test_i2s_replace_shorter_operand() {
+        let mut state = StdState::new(
+            StdRand::with_seed(0),
+            InMemoryCorpus::<BytesInput>::new(),
+            InMemoryCorpus::new(),
+            &mut ConstFeedback::new(false),
+            &mut ConstFeedback::new(false),
+        )
+        .unwrap();
+        state.add_metadata(CmpValuesMetadata {
+            list: vec![CmpValues::Bytes((b"aaaa".to_vec(), b"x".to_vec()))],
+        });
+
+        let mut input = BytesInput::new(b"aaaa".to_vec());
+        let result = I2SRandReplace::new()
+            .mutate(&mut state, &mut input, 0)
+            .unwrap();
+        assert_eq!(result, MutationResult::Mutated);
+        assert_eq!(input.bytes().len(), 4);
+        assert_eq!(input.bytes().iter().filter(|&&b| b == b'x').count(), 1);
+    }
 }
@@ -73,7 +73,7 @@ fn init_count_class_16() {
 fn hash_slice<T>(slice: &[T]) -> u64 {
     let mut hasher = RandomState::with_seeds(0, 0, 0, 0).build_hasher();
     let ptr = slice.as_ptr() as *const u8;
-    let map_size = slice.len() / core::mem::size_of::<T>();
+    let map_size = slice.len() * core::mem::size_of::<T>();
     unsafe {
         hasher.write(from_raw_parts(ptr, map_size));
     }
@@ -1227,7 +1227,6 @@ where
     }
 
     #[inline]
-    #[allow(clippy::cast_ptr_alignment)]
     fn post_exec(
         &mut self,
         state: &mut S,
@@ -1235,24 +1234,35 @@ where
         exit_kind: &ExitKind,
     ) -> Result<(), Error> {
         let map = self.as_mut_slice();
-        let len = map.len();
-        if (len & 1) != 0 {
-            unsafe {
-                *map.get_unchecked_mut(len - 1) =
-                    *COUNT_CLASS_LOOKUP.get_unchecked(*map.get_unchecked(len - 1) as usize);
-            }
+        // The map may not be aligned for `u16` access, classify unaligned bytes one by one.
+        let (prefix, map16, suffix) = unsafe { map.align_to_mut::<u16>() };
+        for item in prefix.iter_mut().chain(suffix.iter_mut()) {
+            *item = COUNT_CLASS_LOOKUP[*item as usize];
         }
 
-        let cnt = len / 2;
-        let map16 = unsafe { core::slice::from_raw_parts_mut(map.as_mut_ptr() as *mut u16, cnt) };
         // 2022-07: Adding `enumerate` here increases execution speed/register allocation on x86_64.
-        for (_i, item) in map16[0..cnt].iter_mut().enumerate() {
+        for (_i, item) in map16.iter_mut().enumerate() {
             unsafe {
                 *item = *COUNT_CLASS_LOOKUP_16.get_unchecked(*item as usize);
             }
         }
         self.base.post_exec(state, input, exit_kind)
     }
+
+    #[inline]
+    fn pre_exec_child(&mut self, state: &mut S, input: &S::Input) -> Result<(), Error> {
+        self.base.pre_exec_child(state, input)
+    }
+
+    #[inline]
+    fn post_exec_child(
+        &mut self,
+        state: &mut S,
+        input: &S::Input,
+        exit_kind: &ExitKind,
+    ) -> Result<(), Error> {
+        self.base.post_exec_child(state, input, exit_kind)
+    }
 }
 
 impl<M> Named for HitcountsMapObserver<M>
@@ -1480,6 +1490,21 @@ where
 
         self.base.post_exec(state, input, exit_kind)
     }
+
+    #[inline]
+    fn pre_exec_child(&mut self, state: &mut S, input: &S::Input) -> Result<(), Error> {
+        self.base.pre_exec_child(state, input)
+    }
+
+    #[inline]
+    fn post_exec_child(
+        &mut self,
+        state: &mut S,
+        input: &S::Input,
+        exit_kind: &ExitKind,
+    ) -> Result<(), Error> {
+        self.base.post_exec_child(state, input, exit_kind)
+    }
 }
 
 impl<M> Named for HitcountsIterableMapObserver<M>
@@ -1777,7 +1802,7 @@ where
         for map in &self.maps {
             let slice = map.as_slice();
             let ptr = slice.as_ptr() as *const u8;
-            let map_size = slice.len() / core::mem::size_of::<T>();
+            let map_size = slice.len() * core::mem::size_of::<T>();
             unsafe {
                 hasher.write(from_raw_parts(ptr, map_size));
             }
@@ -35,7 +35,7 @@ use std::time::Instant;
 use serde::{Deserialize, Serialize};
 pub use value::*;
 
-#[cfg(feature = "no_std")]
+#[cfg(not(feature = "std"))]
 use crate::bolts::current_time;
 use crate::{
     bolts::{
@@ -59,6 +59,12 @@ where
 
 /// Observers observe different information about the target.
 /// They can then be used by various sorts of feedback.
+///
+/// For each execution, [`Observer::pre_exec`] is called before the executor runs the target,
+/// and [`Observer::post_exec`] after it returned, both in the fuzzer process.
+/// Executors that run the target in a forked child additionally call [`Observer::pre_exec_child`]
+/// and [`Observer::post_exec_child`] in the child, in between.
+/// Observers wrapping other observers should forward all of them.
 pub trait Observer<S>: Named + Debug
 where
     S: UsesInput,
@@ -428,7 +434,7 @@ pub struct TimeObserver {
     #[serde(with = "instant_serializer")]
     start_time: Instant,
 
-    #[cfg(feature = "no_std")]
+    #[cfg(not(feature = "std"))]
     start_time: Duration,
 
     last_runtime: Option<Duration>,
@@ -455,7 +461,8 @@ mod instant_serializer {
         D: Deserializer<'de>,
     {
         let duration = Duration::deserialize(deserializer)?;
-        let instant = Instant::now().checked_sub(duration).unwrap();
+        let now = Instant::now();
+        let instant = now.checked_sub(duration).unwrap_or(now);
         Ok(instant)
     }
 }
@@ -470,7 +477,7 @@ impl TimeObserver {
             #[cfg(feature = "std")]
             start_time: Instant::now(),
 
-            #[cfg(feature = "no_std")]
+            #[cfg(not(feature = "std"))]
             start_time: Duration::from_secs(0),
 
             last_runtime: None,
@@ -495,7 +502,7 @@ where
         Ok(())
     }
 
-    #[cfg(feature = "no_std")]
+    #[cfg(not(feature = "std"))]
     fn pre_exec(&mut self, _state: &mut S, _input: &S::Input) -> Result<(), Error> {
         self.last_runtime = None;
         self.start_time = current_time();
@@ -513,7 +520,7 @@ where
         Ok(())
     }
 
-    #[cfg(feature = "no_std")]
+    #[cfg(not(feature = "std"))]
     fn post_exec(
         &mut self,
         _state: &mut S,
@@ -521,6 +528,7 @@ where
         _exit_kind: &ExitKind,
     ) -> Result<(), Error> {
         self.last_runtime = current_time().checked_sub(self.start_time);
+        Ok(())
     }
 }
 
@@ -1394,8 +1402,14 @@ pub mod pybind {
 mod tests {
 
     use crate::{
-        bolts::tuples::{tuple_list, tuple_list_type, Named},
-        observers::{StdMapObserver, TimeObserver},
+        bolts::{
+            tuples::{tuple_list, tuple_list_type, Named},
+            AsSlice,
+        },
+        executors::ExitKind,
+        inputs::BytesInput,
+        observers::{HitcountsMapObserver, MapObserver, Observer, StdMapObserver, TimeObserver},
+        state::NopState,
     };
 
     static mut MAP: [u32; 4] = [0; 4];
@@ -1411,4 +1425,32 @@ mod tests {
             postcard::from_bytes(&vec).unwrap();
         assert_eq!(obv.0.name(), obv2.0.name());
     }
+
+    #[test]
+    fn test_map_observer_hash_wide_entries() {
+        let mut obv = StdMapObserver::owned("map", vec![0_u32; 4]);
+        let empty_hash = obv.hash();
+        *obv.get_mut(3) = 1;
+        assert_ne!(obv.hash(), empty_hash);
+        assert_eq!(obv.count_bytes(), 1);
+
+        obv.reset_map().unwrap();
+        assert_eq!(obv.hash(), empty_hash);
+        assert_eq!(obv.count_bytes(), 0);
+    }
+
+    #[test]
+    fn test_hitcounts_map_observer_buckets() {
+        let mut obv = HitcountsMapObserver::new(StdMapObserver::owned(
+            "map",
+            vec![0_u8, 1, 2, 3, 4, 7, 8, 15, 16, 31, 32, 127, 128, 255, 5],
+        ));
+        let mut state = NopState::<BytesInput>::new();
+        let input = BytesInput::new(vec![]);
+        obv.post_exec(&mut state, &input, &ExitKind::Ok).unwrap();
+        assert_eq!(
+            obv.as_slice(),
+            &[0, 1, 2, 4, 8, 8, 16, 16, 32, 32, 64, 64, 128, 128, 8]
+        );
+    }
 }
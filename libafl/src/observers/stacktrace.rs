@@ -46,6 +46,18 @@ use crate::{
     Error,
 };
 
+#[cfg(not(feature = "casr"))]
+/// Hashes the frame addresses of a backtrace, outermost frame last.
+/// Rotates, so that the frame order matters and repeated (recursive) frames don't cancel out.
+fn hash_frames<I>(frames: I) -> u64
+where
+    I: IntoIterator<Item = u64>,
+{
+    frames
+        .into_iter()
+        .fold(0, |hash, addr| hash.rotate_left(5) ^ addr)
+}
+
 #[cfg(not(feature = "casr"))]
 /// Collects the backtrace via [`Backtrace`] and [`Debug`]
 /// ([`Debug`] is currently used for dev purposes, symbols hash will be used eventually)
@@ -55,10 +67,7 @@ pub fn collect_backtrace() -> u64 {
     if b.frames().is_empty() {
         return 0;
     }
-    let mut hash = 0;
-    for frame in &b.frames()[1..] {
-        hash ^= frame.ip() as u64;
-    }
+    let hash = hash_frames(b.frames()[1..].iter().map(|frame| frame.ip() as u64));
     // will use symbols later
     // let trace = format!("{:?}", b);
     // log::trace!("{}", trace);
@@ -83,7 +92,7 @@ pub fn collect_backtrace() -> u64 {
     for frame in &b.frames()[1..] {
         let mut strace_entry = StacktraceEntry::default();
         let symbols = frame.symbols();
-        if symbols.len() > 1 {
+        if !symbols.is_empty() {
             let symbol = &symbols[0];
             if let Some(name) = symbol.name() {
                 strace_entry.function = name.as_str().unwrap_or("").to_string();
@@ -313,12 +322,13 @@ impl AsanBacktraceObserver {
     #[cfg(not(feature = "casr"))]
     /// parse ASAN error output emited by the target command and compute the hash
     pub fn parse_asan_output(&mut self, output: &str) {
-        let mut hash = 0;
-        let matcher = Regex::new("\\s*#[0-9]*\\s0x([0-9a-f]*)\\s.*").unwrap();
-        matcher.captures_iter(output).for_each(|m| {
-            let g = m.get(1).unwrap();
-            hash ^= u64::from_str_radix(g.as_str(), 16).unwrap();
-        });
+        let matcher = Regex::new("\\s*#[0-9]*\\s0x([0-9a-f]+)\\s.*").unwrap();
+        // Frames that don't fit into an `u64` can't be real addresses, skip them.
+        let hash = hash_frames(
+            matcher
+                .captures_iter(output)
+                .filter_map(|m| u64::from_str_radix(&m[1], 16).ok()),
+        );
         self.update_hash(hash);
     }
 
@@ -362,6 +372,7 @@ where
     S: UsesInput,
 {
     fn pre_exec(&mut self, _state: &mut S, _input: &S::Input) -> Result<(), Error> {
+        self.hash = None;
         Ok(())
     }
 
@@ -391,3 +402,69 @@ impl Named for AsanBacktraceObserver {
         &self.observer_name
     }
 }
+
+#[cfg(test)]
+#[cfg(not(feature = "casr"))]
+mod tests {
+    use super::{hash_frames, AsanBacktraceObserver, BacktraceObserver, HarnessType};
+    use crate::{
+        executors::ExitKind,
+        inputs::BytesInput,
+        observers::{Observer, ObserverWithHashField},
+        state::NopState,
+    };
+
+    #[test]
+    fn test_backtrace_observer_in_process() {
+        let mut state = NopState::<BytesInput>::new();
+        let input = BytesInput::new(vec![]);
+        let mut hash = None;
+        let mut observer =
+            BacktraceObserver::new("BacktraceObserver", &mut hash, HarnessType::InProcess);
+
+        observer
+            .post_exec(&mut state, &input, &ExitKind::Crash)
+            .unwrap();
+        assert!(observer.hash().is_some());
+
+        observer
+            .post_exec(&mut state, &input, &ExitKind::Ok)
+            .unwrap();
+        assert!(observer.hash().is_none());
+
+        // The same frames in a different order are a different backtrace
+        let frames = [0x4f2a10, 0x4f2a10, 0x4f2b20];
+        let hash = hash_frames(frames);
+        assert_ne!(hash, 0x4f2b20);
+        assert_ne!(hash, hash_frames([0x4f2b20, 0x4f2a10, 0x4f2a10]));
+        assert_ne!(hash, hash_frames([0x4f2a10, 0x4f2b20, 0x4f2a10]));
+        assert_eq!(hash, hash_frames(frames));
+    }
+
+    #[test]
+    fn test_asan_backtrace_hash() {
+        let report = "==1==ERROR: AddressSanitizer: heap-buffer-overflow\n\
+                      #0 0x4f2a10 in foo\n\
+                      #1 0x4f2a10 in foo\n\
+                      #2 0x4f2b20 in main\n";
+        let swapped = "==1==ERROR: AddressSanitizer: heap-buffer-overflow\n\
+                       #0 0x4f2b20 in main\n\
+                       #1 0x4f2a10 in foo\n\
+                       #2 0x4f2a10 in foo\n";
+
+        let mut observer = AsanBacktraceObserver::default();
+        observer.parse_asan_output(report);
+        let hash = observer.hash().unwrap();
+        // The recursive frames must not cancel each other out
+        assert_ne!(hash, 0x4f2b20);
+
+        observer.parse_asan_output(report);
+        assert_eq!(observer.hash(), Some(hash));
+        observer.parse_asan_output(swapped);
+        assert_ne!(observer.hash(), Some(hash));
+
+        // A bogus, overlong frame must not panic
+        observer.parse_asan_output("    #0 0x123456789abcdef0123 in bar\n");
+        assert_eq!(observer.hash(), Some(0));
+    }
+}
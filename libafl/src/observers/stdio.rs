@@ -7,7 +7,7 @@ use std::vec::Vec;
 
 use serde::{Deserialize, Serialize};
 
-use crate::{bolts::tuples::Named, inputs::UsesInput, observers::Observer};
+use crate::{bolts::tuples::Named, inputs::UsesInput, observers::Observer, Error};
 
 /// An observer that captures stdout of a target.
 /// Only works for supported executors.
@@ -17,6 +17,10 @@ pub struct StdOutObserver {
     pub name: String,
     /// The stdout of the target during its last execution.
     pub stdout: Option<Vec<u8>>,
+    /// The maximum number of bytes of `stdout` to keep, the rest is dropped.
+    pub max_len: usize,
+    /// If the `stdout` of the last execution was longer than `max_len`, and got truncated.
+    pub truncated: bool,
 }
 
 /// An observer that captures stdout of a target.
@@ -24,7 +28,18 @@ impl StdOutObserver {
     /// Create a new [`StdOutObserver`] with the given name.
     #[must_use]
     pub fn new(name: String) -> Self {
-        Self { name, stdout: None }
+        Self::with_max_len(name, usize::MAX)
+    }
+
+    /// Create a new [`StdOutObserver`] with the given name, keeping at most `max_len` bytes of `stdout`.
+    #[must_use]
+    pub fn with_max_len(name: String, max_len: usize) -> Self {
+        Self {
+            name,
+            stdout: None,
+            max_len,
+            truncated: false,
+        }
     }
 }
 
@@ -37,9 +52,19 @@ where
         true
     }
 
+    /// Forget the `stdout` of the previous run, so that it's never mistaken for the current one
+    #[inline]
+    fn pre_exec(&mut self, _state: &mut S, _input: &S::Input) -> Result<(), Error> {
+        self.stdout = None;
+        self.truncated = false;
+        Ok(())
+    }
+
     /// React to new `stdout`
     fn observe_stdout(&mut self, stdout: &[u8]) {
-        self.stdout = Some(stdout.into());
+        let len = stdout.len().min(self.max_len);
+        self.stdout = Some(stdout[..len].into());
+        self.truncated = len < stdout.len();
     }
 }
 
@@ -57,6 +82,10 @@ pub struct StdErrObserver {
     pub name: String,
     /// The stderr of the target during its last execution.
     pub stderr: Option<Vec<u8>>,
+    /// The maximum number of bytes of `stderr` to keep, the rest is dropped.
+    pub max_len: usize,
+    /// If the `stderr` of the last execution was longer than `max_len`, and got truncated.
+    pub truncated: bool,
 }
 
 /// An observer that captures stderr of a target.
@@ -64,7 +93,18 @@ impl StdErrObserver {
     /// Create a new [`StdErrObserver`] with the given name.
     #[must_use]
     pub fn new(name: String) -> Self {
-        Self { name, stderr: None }
+        Self::with_max_len(name, usize::MAX)
+    }
+
+    /// Create a new [`StdErrObserver`] with the given name, keeping at most `max_len` bytes of `stderr`.
+    #[must_use]
+    pub fn with_max_len(name: String, max_len: usize) -> Self {
+        Self {
+            name,
+            stderr: None,
+            max_len,
+            truncated: false,
+        }
     }
 }
 
@@ -77,9 +117,19 @@ where
         true
     }
 
+    /// Forget the `stderr` of the previous run, so that it's never mistaken for the current one
+    #[inline]
+    fn pre_exec(&mut self, _state: &mut S, _input: &S::Input) -> Result<(), Error> {
+        self.stderr = None;
+        self.truncated = false;
+        Ok(())
+    }
+
     /// React to new `stderr`
     fn observe_stderr(&mut self, stderr: &[u8]) {
-        self.stderr = Some(stderr.into());
+        let len = stderr.len().min(self.max_len);
+        self.stderr = Some(stderr[..len].into());
+        self.truncated = len < stderr.len();
     }
 }
 
@@ -88,3 +138,47 @@ impl Named for StdErrObserver {
         &self.name
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use alloc::string::ToString;
+
+    use super::{StdErrObserver, StdOutObserver};
+    use crate::{inputs::BytesInput, observers::Observer, state::NopState};
+
+    #[test]
+    fn test_stdio_observers_reset() {
+        let mut state = NopState::<BytesInput>::new();
+        let input = BytesInput::new(vec![]);
+
+        let mut stdout = StdOutObserver::new("stdout".to_string());
+        let mut stderr = StdErrObserver::new("stderr".to_string());
+        Observer::<NopState<BytesInput>>::observe_stdout(&mut stdout, b"out");
+        Observer::<NopState<BytesInput>>::observe_stderr(&mut stderr, b"err");
+        assert_eq!(stdout.stdout.as_deref(), Some(&b"out"[..]));
+        assert_eq!(stderr.stderr.as_deref(), Some(&b"err"[..]));
+
+        stdout.pre_exec(&mut state, &input).unwrap();
+        stderr.pre_exec(&mut state, &input).unwrap();
+        assert!(stdout.stdout.is_none());
+        assert!(stderr.stderr.is_none());
+    }
+
+    #[test]
+    fn test_stdio_observers_max_len() {
+        let mut state = NopState::<BytesInput>::new();
+        let input = BytesInput::new(vec![]);
+
+        let mut stdout = StdOutObserver::with_max_len("stdout".to_string(), 3);
+        let mut stderr = StdErrObserver::with_max_len("stderr".to_string(), 3);
+        Observer::<NopState<BytesInput>>::observe_stdout(&mut stdout, b"output");
+        Observer::<NopState<BytesInput>>::observe_stderr(&mut stderr, b"err");
+        assert_eq!(stdout.stdout.as_deref(), Some(&b"out"[..]));
+        assert!(stdout.truncated);
+        assert_eq!(stderr.stderr.as_deref(), Some(&b"err"[..]));
+        assert!(!stderr.truncated);
+
+        stdout.pre_exec(&mut state, &input).unwrap();
+        assert!(!stdout.truncated);
+    }
+}
@@ -286,7 +286,9 @@ where
                     };
 
                     if must_remove {
+                        // The old entry is no longer top rated for any index, so it's not favored anymore
                         drop(old.metadata_map_mut().remove::<M>());
+                        drop(old.metadata_map_mut().remove::<IsFavoredMetadata>());
                     }
                 }
 
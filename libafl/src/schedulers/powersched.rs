@@ -203,11 +203,11 @@ where
         let psmeta = state.metadata_mut::<SchedulerMetadata>()?;
 
         // We won't add new one because it'll get added when it gets executed in calirbation next time.
-        psmeta.set_exec_time(psmeta.exec_time() - prev_total_time);
-        psmeta.set_cycles(psmeta.cycles() - (prev_cycles as u64));
-        psmeta.set_bitmap_size(psmeta.bitmap_size() - prev_bitmap_size);
+        psmeta.set_exec_time(psmeta.exec_time().saturating_sub(prev_total_time));
+        psmeta.set_cycles(psmeta.cycles().saturating_sub(prev_cycles as u64));
+        psmeta.set_bitmap_size(psmeta.bitmap_size().saturating_sub(prev_bitmap_size));
         psmeta.set_bitmap_size_log(psmeta.bitmap_size_log() - prev_bitmap_size_log);
-        psmeta.set_bitmap_entries(psmeta.bitmap_entries() - 1);
+        psmeta.set_bitmap_entries(psmeta.bitmap_entries().saturating_sub(1));
 
         state
             .testcase_mut(idx)?
@@ -237,11 +237,11 @@ where
 
         let psmeta = state.metadata_mut::<SchedulerMetadata>()?;
 
-        psmeta.set_exec_time(psmeta.exec_time() - prev_total_time);
-        psmeta.set_cycles(psmeta.cycles() - (prev_cycles as u64));
-        psmeta.set_bitmap_size(psmeta.bitmap_size() - prev_bitmap_size);
+        psmeta.set_exec_time(psmeta.exec_time().saturating_sub(prev_total_time));
+        psmeta.set_cycles(psmeta.cycles().saturating_sub(prev_cycles as u64));
+        psmeta.set_bitmap_size(psmeta.bitmap_size().saturating_sub(prev_bitmap_size));
         psmeta.set_bitmap_size_log(psmeta.bitmap_size_log() - prev_bitmap_size_log);
-        psmeta.set_bitmap_entries(psmeta.bitmap_entries() - 1);
+        psmeta.set_bitmap_entries(psmeta.bitmap_entries().saturating_sub(1));
 
         Ok(())
     }
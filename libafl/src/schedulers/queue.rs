@@ -50,8 +50,7 @@ where
             let id = state
                 .corpus()
                 .current()
-                .map(|id| state.corpus().next(id))
-                .flatten()
+                .and_then(|id| state.corpus().next(id))
                 .unwrap_or_else(|| state.corpus().first().unwrap());
             self.set_current_scheduled(state, Some(id))?;
             Ok(id)
@@ -83,7 +82,7 @@ mod tests {
 
     use crate::{
         bolts::rands::StdRand,
-        corpus::{Corpus, OnDiskCorpus, Testcase},
+        corpus::{Corpus, InMemoryCorpus, OnDiskCorpus, Testcase},
         feedbacks::ConstFeedback,
         inputs::bytes::BytesInput,
         schedulers::{QueueScheduler, Scheduler},
@@ -124,4 +123,26 @@ mod tests {
 
         fs::remove_dir_all("target/.test/fancy/path").unwrap();
     }
+
+    #[test]
+    fn test_queue_wraps_around() {
+        let mut scheduler = QueueScheduler::new();
+
+        let mut corpus = InMemoryCorpus::<BytesInput>::new();
+        let first = corpus.add(Testcase::new(vec![0_u8].into())).unwrap();
+        let second = corpus.add(Testcase::new(vec![1_u8].into())).unwrap();
+
+        let mut state = StdState::new(
+            StdRand::with_seed(0),
+            corpus,
+            InMemoryCorpus::new(),
+            &mut ConstFeedback::new(false),
+            &mut ConstFeedback::new(false),
+        )
+        .unwrap();
+
+        assert_eq!(scheduler.next(&mut state).unwrap(), first);
+        assert_eq!(scheduler.next(&mut state).unwrap(), second);
+        assert_eq!(scheduler.next(&mut state).unwrap(), first);
+    }
 }
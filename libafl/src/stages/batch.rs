@@ -0,0 +1,287 @@
+//! The [`BatchMutationalStage`] mutates batches of inputs, to run them on a [`BatchExecutor`] at once.
+
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+
+#[cfg(feature = "introspection")]
+use crate::monitors::PerfFeature;
+use crate::{
+    bolts::rands::Rand,
+    corpus::{Corpus, CorpusId},
+    events::EventFirer,
+    executors::{threaded::BatchExecutor, ExitKind, HasObservers},
+    feedbacks::Feedback,
+    fuzzer::{Evaluator, ExecutesInput, HasFeedback, HasObjective},
+    mark_feature_time,
+    mutators::Mutator,
+    stages::{mutational::DEFAULT_MUTATIONAL_MAX_ITERATIONS, Stage},
+    start_timer,
+    state::{HasClientPerfMonitor, HasCorpus, HasExecutions, HasRand, UsesState},
+    Error,
+};
+
+/// A mutational stage that runs a whole batch of mutated inputs with each execution,
+/// for example on all threads of a [`crate::executors::ThreadedExecutor`].
+///
+/// The feedbacks judge the merged coverage of each batch.
+/// Only if the batch is interesting, or did not exit with [`ExitKind::Ok`],
+/// its inputs are evaluated again, one at a time, so that each new corpus entry or objective
+/// is attributed to the input that caused it.
+#[derive(Clone, Debug)]
+pub struct BatchMutationalStage<E, EM, M, Z> {
+    mutator: M,
+    max_batches: u64,
+    #[allow(clippy::type_complexity)]
+    phantom: PhantomData<(E, EM, Z)>,
+}
+
+impl<E, EM, M, Z> BatchMutationalStage<E, EM, M, Z>
+where
+    E: BatchExecutor + HasObservers<State = Z::State>,
+    EM: EventFirer<State = Z::State>,
+    M: Mutator<Z::Input, Z::State>,
+    Z: Evaluator<E, EM> + ExecutesInput<E, EM> + HasFeedback + HasObjective,
+    Z::State: HasClientPerfMonitor + HasCorpus + HasExecutions + HasRand,
+{
+    /// Creates a new [`BatchMutationalStage`]
+    pub fn new(mutator: M) -> Self {
+        Self {
+            mutator,
+            max_batches: DEFAULT_MUTATIONAL_MAX_ITERATIONS,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Creates a new [`BatchMutationalStage`] running at most `max_batches` batches per testcase
+    pub fn with_max_batches(mutator: M, max_batches: u64) -> Result<Self, Error> {
+        if max_batches == 0 {
+            return Err(Error::illegal_argument(
+                "BatchMutationalStage needs at least one batch",
+            ));
+        }
+        Ok(Self {
+            mutator,
+            max_batches,
+            phantom: PhantomData,
+        })
+    }
+
+    /// The mutator registered for this stage
+    #[inline]
+    pub fn mutator(&self) -> &M {
+        &self.mutator
+    }
+
+    /// The mutator registered for this stage (mutable)
+    #[inline]
+    pub fn mutator_mut(&mut self) -> &mut M {
+        &mut self.mutator
+    }
+
+    /// Runs the batches for the given testcase
+    #[allow(clippy::cast_possible_wrap)] // more than i32 stages on 32 bit system - highly unlikely...
+    fn perform_batches(
+        &mut self,
+        fuzzer: &mut Z,
+        executor: &mut E,
+        state: &mut Z::State,
+        manager: &mut EM,
+        corpus_idx: CorpusId,
+    ) -> Result<(), Error> {
+        let batches = 1 + state.rand_mut().below(self.max_batches);
+
+        start_timer!(state);
+        let input = {
+            let mut testcase = state.corpus().get(corpus_idx)?.borrow_mut();
+            state.corpus().load_input_into(&mut testcase)?;
+            testcase.input().as_ref().unwrap().clone()
+        };
+        mark_feature_time!(state, PerfFeature::GetInputFromCorpus);
+
+        let batch_size = executor.batch_size();
+        let mut i: u64 = 0;
+        for _ in 0..batches {
+            let mut inputs = Vec::with_capacity(batch_size);
+            start_timer!(state);
+            for _ in 0..batch_size {
+                let mut mutated = input.clone();
+                self.mutator.mutate(state, &mut mutated, i as i32)?;
+                inputs.push(mutated);
+            }
+            mark_feature_time!(state, PerfFeature::Mutate);
+
+            executor.queue_batch(inputs[1..].to_vec());
+            let exit_kind = fuzzer.execute_input(state, executor, manager, &inputs[0])?;
+            // `execute_input` counts a single execution
+            *state.executions_mut() += inputs.len() - 1;
+
+            let observers = executor.observers();
+            let interesting = exit_kind != ExitKind::Ok
+                || fuzzer
+                    .objective_mut()
+                    .is_interesting(state, manager, &inputs[0], observers, &exit_kind)?
+                || fuzzer
+                    .feedback_mut()
+                    .is_interesting(state, manager, &inputs[0], observers, &exit_kind)?;
+            fuzzer.objective_mut().discard_metadata(state, &inputs[0])?;
+            fuzzer.feedback_mut().discard_metadata(state, &inputs[0])?;
+
+            for input in inputs {
+                let corpus_idx = if interesting {
+                    // Find out which inputs of the batch are worth keeping
+                    fuzzer.evaluate_input(state, executor, manager, input)?.1
+                } else {
+                    None
+                };
+
+                start_timer!(state);
+                self.mutator.post_exec(state, i as i32, corpus_idx)?;
+                mark_feature_time!(state, PerfFeature::MutatePostExec);
+                i += 1;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<E, EM, M, Z> UsesState for BatchMutationalStage<E, EM, M, Z>
+where
+    E: BatchExecutor + HasObservers<State = Z::State>,
+    EM: EventFirer<State = Z::State>,
+    M: Mutator<Z::Input, Z::State>,
+    Z: Evaluator<E, EM> + ExecutesInput<E, EM> + HasFeedback + HasObjective,
+    Z::State: HasClientPerfMonitor + HasCorpus + HasExecutions + HasRand,
+{
+    type State = Z::State;
+}
+
+impl<E, EM, M, Z> Stage<E, EM, Z> for BatchMutationalStage<E, EM, M, Z>
+where
+    E: BatchExecutor + HasObservers<State = Z::State>,
+    EM: EventFirer<State = Z::State>,
+    M: Mutator<Z::Input, Z::State>,
+    Z: Evaluator<E, EM> + ExecutesInput<E, EM> + HasFeedback + HasObjective,
+    Z::State: HasClientPerfMonitor + HasCorpus + HasExecutions + HasRand,
+{
+    #[inline]
+    #[allow(clippy::let_and_return)]
+    fn perform(
+        &mut self,
+        fuzzer: &mut Z,
+        executor: &mut E,
+        state: &mut Z::State,
+        manager: &mut EM,
+        corpus_idx: CorpusId,
+    ) -> Result<(), Error> {
+        let ret = self.perform_batches(fuzzer, executor, state, manager, corpus_idx);
+
+        #[cfg(feature = "introspection")]
+        state.introspection_monitor_mut().finish_stage();
+
+        ret
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+    use core::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Mutex;
+
+    use crate::{
+        bolts::{rands::StdRand, tuples::tuple_list, AsMutSlice, AsSlice},
+        corpus::{Corpus, InMemoryCorpus, Testcase},
+        events::NopEventManager,
+        executors::{
+            threaded::{merge_maps, ThreadedExecutor},
+            ExitKind,
+        },
+        feedbacks::{CrashFeedback, MaxMapFeedback},
+        inputs::{BytesInput, HasBytesVec},
+        mutators::mutations::ByteRandMutator,
+        observers::StdMapObserver,
+        schedulers::RandScheduler,
+        stages::{BatchMutationalStage, Stage},
+        state::{HasCorpus, HasExecutions, StdState},
+        StdFuzzer,
+    };
+
+    #[test]
+    fn test_batch_mutational_stage() {
+        let maps: Vec<_> = (0..2).map(|_| Mutex::new(vec![0_u8; 4])).collect();
+        let threads_used = AtomicUsize::new(0);
+        let runs = AtomicUsize::new(0);
+
+        let observer = StdMapObserver::owned("map", vec![0_u8; 4]);
+        let mut feedback = MaxMapFeedback::new(&observer);
+        let mut objective = CrashFeedback::new();
+
+        let mut corpus = InMemoryCorpus::<BytesInput>::new();
+        let corpus_idx = corpus.add(Testcase::new(vec![0].into())).unwrap();
+        let mut state = StdState::new(
+            StdRand::with_seed(0),
+            corpus,
+            InMemoryCorpus::<BytesInput>::new(),
+            &mut feedback,
+            &mut objective,
+        )
+        .unwrap();
+        let mut manager = NopEventManager::new();
+        let mut fuzzer = StdFuzzer::new(RandScheduler::new(), feedback, objective);
+
+        let mut executor = ThreadedExecutor::<
+            _,
+            _,
+            _,
+            StdState<BytesInput, InMemoryCorpus<BytesInput>, StdRand, InMemoryCorpus<BytesInput>>,
+        >::new(
+            |thread, input: &BytesInput| {
+                threads_used.fetch_or(1 << thread, Ordering::Relaxed);
+                runs.fetch_add(1, Ordering::Relaxed);
+                maps[thread].lock().unwrap()[usize::from(input.bytes()[0] % 4)] = 1;
+                ExitKind::Ok
+            },
+            |observers: &mut (StdMapObserver<'static, u8, false>, ())| {
+                for map in &maps {
+                    let mut map = map.lock().unwrap();
+                    merge_maps(observers.0.as_mut_slice(), [map.as_slice()]);
+                    map.fill(0);
+                }
+            },
+            2,
+            tuple_list!(observer),
+        )
+        .unwrap();
+
+        let mut stage = BatchMutationalStage::with_max_batches(ByteRandMutator::new(), 8).unwrap();
+        stage
+            .perform(
+                &mut fuzzer,
+                &mut executor,
+                &mut state,
+                &mut manager,
+                corpus_idx,
+            )
+            .unwrap();
+
+        // Every batch ran on both threads, and each input counts as an execution
+        assert_eq!(threads_used.load(Ordering::Relaxed), 0b11);
+        assert_eq!(*state.executions(), runs.load(Ordering::Relaxed));
+
+        // The first batch always finds new coverage. Its inputs are rerun one by one,
+        // so each new entry covers a bucket of its own.
+        let buckets: Vec<_> = state
+            .corpus()
+            .ids()
+            .filter(|idx| *idx != corpus_idx)
+            .map(|idx| {
+                let testcase = state.corpus().get(idx).unwrap().borrow();
+                testcase.input().as_ref().unwrap().bytes()[0] % 4
+            })
+            .collect();
+        assert!(!buckets.is_empty());
+        for (i, bucket) in buckets.iter().enumerate() {
+            assert!(!buckets[i + 1..].contains(bucket));
+        }
+    }
+}
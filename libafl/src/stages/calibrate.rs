@@ -1,9 +1,6 @@
 //! The calibration stage. The fuzzer measures the average exec time and the bitmap size.
 
-use alloc::{
-    string::{String, ToString},
-    vec::Vec,
-};
+use alloc::string::{String, ToString};
 use core::{fmt::Debug, marker::PhantomData, time::Duration};
 
 use hashbrown::HashSet;
@@ -66,9 +63,16 @@ pub struct CalibrationStage<O, OT, S> {
     map_name: String,
     stage_max: usize,
     track_stability: bool,
+    /// If unstable entries are masked in the history map of the feedback
+    mask_unstable: bool,
+    /// If we already warned about the density of the map
+    density_warned: bool,
     phantom: PhantomData<(O, OT, S)>,
 }
 
+/// Maps filled more than this percentage are likely too small for the target
+const DENSITY_WARN_PERCENT: usize = 70;
+
 const CAL_STAGE_START: usize = 4; // AFL++'s CAL_CYCLES_FAST + 1
 const CAL_STAGE_MAX: usize = 8; // AFL++'s CAL_CYCLES + 1
 
@@ -139,13 +143,19 @@ where
             .observers_mut()
             .post_exec_all(state, &input, &exit_kind)?;
 
-        let map_first = &executor
+        let map = executor
             .observers()
             .match_name::<O>(&self.map_observer_name)
-            .ok_or_else(|| Error::key_not_found("MapObserver not found".to_string()))?
-            .to_vec();
+            .ok_or_else(|| Error::key_not_found("MapObserver not found".to_string()))?;
+        let map_first = &map.to_vec();
+        let bitmap_size = map.count_bytes();
+        let usable_count = map.usable_count();
+
+        if !self.density_warned {
+            self.check_density(state, mgr, bitmap_size as usize, usable_count)?;
+        }
 
-        let mut unstable_entries: Vec<usize> = vec![];
+        let mut unstable_entries = HashSet::new();
         let map_len: usize = map_first.len();
         // Run CAL_STAGE_START - 1 times, increase by 2 for every time a new
         // run is found to be unstable or to crash with CAL_STAGE_MAX total runs.
@@ -191,7 +201,12 @@ where
                 let history_map = &mut state
                     .named_metadata_map_mut()
                     .get_mut::<MapFeedbackMetadata<O::Entry>>(&self.map_name)
-                    .unwrap()
+                    .ok_or_else(|| {
+                        Error::key_not_found(format!(
+                            "MapFeedbackMetadata {} not found, is the MapFeedback initialized?",
+                            self.map_name
+                        ))
+                    })?
                     .history_map;
 
                 if history_map.len() < map_len {
@@ -204,8 +219,11 @@ where
                     .enumerate()
                 {
                     if *first != *cur && *history != O::Entry::max_value() {
-                        *history = O::Entry::max_value();
-                        unstable_entries.push(idx);
+                        // A maxed out history entry can't be novel anymore, so it is ignored
+                        if self.mask_unstable {
+                            *history = O::Entry::max_value();
+                        }
+                        unstable_entries.insert(idx);
                     };
                 }
 
@@ -223,13 +241,11 @@ where
                     .metadata_map_mut()
                     .get_mut::<UnstableEntriesMetadata>()
                     .unwrap();
-                for item in unstable_entries {
-                    existing.unstable_entries.insert(item); // Insert newly found items
-                }
+                existing.unstable_entries.extend(unstable_entries); // Insert newly found items
                 existing.map_len = map_len;
             } else {
                 state.add_metadata::<UnstableEntriesMetadata>(UnstableEntriesMetadata::new(
-                    HashSet::from_iter(unstable_entries),
+                    unstable_entries,
                     map_len,
                 ));
             }
@@ -237,13 +253,6 @@ where
 
         // If weighted scheduler or powerscheduler is used, update it
         if state.has_metadata::<SchedulerMetadata>() {
-            let map = executor
-                .observers()
-                .match_name::<O>(&self.map_observer_name)
-                .ok_or_else(|| Error::key_not_found("MapObserver not found".to_string()))?;
-
-            let bitmap_size = map.count_bytes();
-
             let psmeta = state
                 .metadata_map_mut()
                 .get_mut::<SchedulerMetadata>()
@@ -289,7 +298,16 @@ where
             data.set_handicap(handicap);
         }
 
-        // Send the stability event to the broker
+        // Send the density and stability events to the broker
+        mgr.fire(
+            state,
+            Event::UpdateUserStats {
+                name: "map density".to_string(),
+                value: UserStats::Ratio(bitmap_size, usable_count as u64),
+                phantom: PhantomData,
+            },
+        )?;
+
         if let Some(meta) = state.metadata_map().get::<UnstableEntriesMetadata>() {
             let unstable_entries = meta.unstable_entries().len();
             let map_len = meta.map_len();
@@ -313,6 +331,36 @@ where
     OT: ObserversTuple<S>,
     S: HasCorpus + HasMetadata + HasNamedMetadata,
 {
+    /// Warns if `filled` out of `len` map entries mean the map is (almost) full, or empty.
+    /// Full maps lose coverage to collisions, empty maps hint at a target without instrumentation.
+    fn check_density<EM>(
+        &mut self,
+        state: &mut S,
+        mgr: &mut EM,
+        filled: usize,
+        len: usize,
+    ) -> Result<(), Error>
+    where
+        EM: EventFirer<State = S>,
+    {
+        let message = if filled == 0 {
+            format!(
+                "The map of {} is empty, is the target instrumented?",
+                self.map_observer_name
+            )
+        } else if filled * 100 > len * DENSITY_WARN_PERCENT {
+            format!(
+                "The map of {} is {}% full, consider a larger map",
+                self.map_observer_name,
+                filled * 100 / len
+            )
+        } else {
+            return Ok(());
+        };
+        self.density_warned = true;
+        mgr.log(state, LogSeverity::Warn, message)
+    }
+
     /// Create a new [`CalibrationStage`].
     #[must_use]
     pub fn new<F>(map_feedback: &F) -> Self
@@ -325,10 +373,21 @@ where
             map_name: map_feedback.name().to_string(),
             stage_max: CAL_STAGE_START,
             track_stability: true,
+            mask_unstable: true,
+            density_warned: false,
             phantom: PhantomData,
         }
     }
 
+    /// Set if entries found to be unstable are masked in the history map of the feedback, the default.
+    /// Masked entries are never novel again, so nondeterministic targets don't fill the corpus with noise.
+    /// This only works for feedbacks maximizing the map, like the [`crate::feedbacks::MaxMapFeedback`].
+    /// Unmasked, the unstable entries are still tracked in the [`struct@UnstableEntriesMetadata`],
+    /// and reported in the stability stats.
+    pub fn set_mask_unstable(&mut self, mask_unstable: bool) {
+        self.mask_unstable = mask_unstable;
+    }
+
     /// Create a new [`CalibrationStage`], but without checking stability.
     #[must_use]
     pub fn ignore_stability<F>(map_feedback: &F) -> Self
@@ -341,7 +400,203 @@ where
             map_name: map_feedback.name().to_string(),
             stage_max: CAL_STAGE_START,
             track_stability: false,
+            mask_unstable: true,
+            density_warned: false,
             phantom: PhantomData,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use alloc::{
+        string::{String, ToString},
+        vec::Vec,
+    };
+
+    use super::{CalibrationStage, UnstableEntriesMetadata};
+    use crate::{
+        bolts::{rands::StdRand, tuples::tuple_list, AsMutSlice},
+        corpus::{Corpus, InMemoryCorpus, Testcase},
+        events::{Event, EventFirer},
+        executors::{Executor, ExitKind, HasObservers},
+        feedbacks::{map::MapFeedbackMetadata, ConstFeedback, MaxMapFeedback},
+        inputs::BytesInput,
+        monitors::UserStats,
+        observers::{StdMapObserver, UsesObservers},
+        schedulers::RandScheduler,
+        stages::Stage,
+        state::{HasMetadata, HasNamedMetadata, StdState, UsesState},
+        Error, StdFuzzer,
+    };
+
+    type TestState =
+        StdState<BytesInput, InMemoryCorpus<BytesInput>, StdRand, InMemoryCorpus<BytesInput>>;
+    type TestObservers = (StdMapObserver<'static, u8, false>, ());
+
+    /// Records the logs and user stats fired by the stage
+    #[derive(Debug, Default)]
+    struct RecordingManager {
+        logs: Vec<String>,
+        user_stats: Vec<(String, UserStats)>,
+    }
+
+    impl UsesState for RecordingManager {
+        type State = TestState;
+    }
+
+    impl EventFirer for RecordingManager {
+        fn fire(&mut self, _state: &mut TestState, event: Event<BytesInput>) -> Result<(), Error> {
+            match event {
+                Event::Log { message, .. } => self.logs.push(message),
+                Event::UpdateUserStats { name, value, .. } => self.user_stats.push((name, value)),
+                _ => {}
+            }
+            Ok(())
+        }
+    }
+
+    /// Sets the first map entry on every run, and the second one on every other run
+    #[derive(Debug)]
+    struct FlakyExecutor {
+        observers: TestObservers,
+        runs: usize,
+    }
+
+    impl UsesState for FlakyExecutor {
+        type State = TestState;
+    }
+
+    impl UsesObservers for FlakyExecutor {
+        type Observers = TestObservers;
+    }
+
+    impl HasObservers for FlakyExecutor {
+        fn observers(&self) -> &TestObservers {
+            &self.observers
+        }
+
+        fn observers_mut(&mut self) -> &mut TestObservers {
+            &mut self.observers
+        }
+    }
+
+    impl<EM, Z> Executor<EM, Z> for FlakyExecutor
+    where
+        EM: UsesState<State = TestState>,
+        Z: UsesState<State = TestState>,
+    {
+        fn run_target(
+            &mut self,
+            _fuzzer: &mut Z,
+            _state: &mut TestState,
+            _mgr: &mut EM,
+            _input: &BytesInput,
+        ) -> Result<ExitKind, Error> {
+            let map = self.observers.0.as_mut_slice();
+            map[0] = 1;
+            map[1] = u8::from(self.runs % 2 == 1);
+            self.runs += 1;
+            Ok(ExitKind::Ok)
+        }
+    }
+
+    /// Calibrates a single corpus entry on the [`FlakyExecutor`]
+    fn calibrate(
+        configure: impl FnOnce(
+            &mut CalibrationStage<StdMapObserver<'static, u8, false>, TestObservers, TestState>,
+        ),
+    ) -> (TestState, RecordingManager) {
+        let observer = StdMapObserver::owned("map", vec![0_u8; 4]);
+        let mut feedback = MaxMapFeedback::new(&observer);
+        let mut objective = ConstFeedback::new(false);
+
+        let mut corpus = InMemoryCorpus::<BytesInput>::new();
+        let corpus_idx = corpus.add(Testcase::new(vec![0].into())).unwrap();
+        let mut state = StdState::new(
+            StdRand::with_seed(0),
+            corpus,
+            InMemoryCorpus::<BytesInput>::new(),
+            &mut feedback,
+            &mut objective,
+        )
+        .unwrap();
+
+        let mut stage = CalibrationStage::new(&feedback);
+        configure(&mut stage);
+        let mut fuzzer = StdFuzzer::new(RandScheduler::new(), feedback, objective);
+        let mut executor = FlakyExecutor {
+            observers: tuple_list!(observer),
+            runs: 0,
+        };
+        let mut mgr = RecordingManager::default();
+        stage
+            .perform(&mut fuzzer, &mut executor, &mut state, &mut mgr, corpus_idx)
+            .unwrap();
+        (state, mgr)
+    }
+
+    #[test]
+    fn test_calibration_density() {
+        let (mut state, mut mgr) = calibrate(|_| {});
+        // One out of four entries is filled, which is neither empty nor (almost) full
+        assert!(mgr.logs.is_empty());
+        assert!(mgr
+            .user_stats
+            .iter()
+            .any(|(name, value)| name == "map density" && matches!(value, UserStats::Ratio(1, 4))));
+
+        let observer = StdMapObserver::<u8, false>::owned("map", vec![0_u8; 4]);
+        let feedback = MaxMapFeedback::<_, TestState, _>::new(&observer);
+        let mut stage = CalibrationStage::<_, TestObservers, _>::new(&feedback);
+
+        stage.check_density(&mut state, &mut mgr, 0, 4).unwrap();
+        assert_eq!(
+            mgr.logs,
+            ["The map of map is empty, is the target instrumented?".to_string()]
+        );
+        assert!(stage.density_warned);
+
+        mgr.logs.clear();
+        stage.check_density(&mut state, &mut mgr, 3, 4).unwrap();
+        assert_eq!(
+            mgr.logs,
+            ["The map of map is 75% full, consider a larger map".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_calibration_mask_unstable() {
+        for mask_unstable in [true, false] {
+            let (state, mgr) = calibrate(|stage| stage.set_mask_unstable(mask_unstable));
+
+            // Only the second entry flips between runs
+            let unstable = state.metadata::<UnstableEntriesMetadata>().unwrap();
+            assert_eq!(unstable.unstable_entries().len(), 1);
+            assert!(unstable.unstable_entries().contains(&1));
+            assert_eq!(unstable.map_len(), 4);
+            assert!(mgr.user_stats.iter().any(
+                |(name, value)| name == "stability" && matches!(value, UserStats::Ratio(3, 4))
+            ));
+
+            let history_map = &state
+                .named_metadata::<MapFeedbackMetadata<u8>>("mapfeedback_metadata_map")
+                .unwrap()
+                .history_map;
+            if mask_unstable {
+                assert_eq!(history_map, &[0, u8::MAX, 0, 0]);
+            } else {
+                assert_eq!(history_map, &[0, 0, 0, 0]);
+            }
+        }
+    }
+
+    #[test]
+    fn test_calibration_ignore_stability() {
+        let observer = StdMapObserver::<u8, false>::owned("map", vec![0_u8; 4]);
+        let feedback = MaxMapFeedback::<_, TestState, _>::new(&observer);
+        let stage = CalibrationStage::<_, TestObservers, _>::ignore_stability(&feedback);
+        assert!(!stage.track_stability);
+        assert!(stage.mask_unstable);
+    }
+}
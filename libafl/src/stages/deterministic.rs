@@ -0,0 +1,330 @@
+//! The [`DeterministicStage`] walks the classic `AFL` deterministic mutations over a testcase, once.
+//! These are bit and byte flips, arithmetics, and interesting values, applied at each position in order.
+
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "introspection")]
+use crate::monitors::PerfFeature;
+use crate::{
+    corpus::{Corpus, CorpusId},
+    fuzzer::Evaluator,
+    inputs::{HasBytesVec, UsesInput},
+    mark_feature_time,
+    mutators::mutations::{ARITH_MAX, INTERESTING_16, INTERESTING_32, INTERESTING_8},
+    stages::Stage,
+    start_timer,
+    state::{HasClientPerfMonitor, HasCorpus, HasMetadata, UsesState},
+    Error,
+};
+
+/// Marks a testcase for which the [`DeterministicStage`] already ran.
+/// It is added before the mutations run, so a crash partway through skips the rest of them.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default)]
+pub struct DeterministicDoneMetadata;
+
+crate::impl_serdeany!(DeterministicDoneMetadata);
+
+/// Reads the `width` bytes at `pos` as an unsigned number
+fn read_num(bytes: &[u8], pos: usize, width: usize, big_endian: bool) -> u64 {
+    let num_bytes = &bytes[pos..pos + width];
+    if big_endian {
+        num_bytes
+            .iter()
+            .fold(0, |acc, &byte| (acc << 8) | u64::from(byte))
+    } else {
+        num_bytes
+            .iter()
+            .rev()
+            .fold(0, |acc, &byte| (acc << 8) | u64::from(byte))
+    }
+}
+
+/// Writes the lowest `width` bytes of `num` at `pos`
+fn write_num(bytes: &mut [u8], pos: usize, width: usize, num: u64, big_endian: bool) {
+    for i in 0..width {
+        let byte = (num >> (8 * i)) as u8;
+        if big_endian {
+            bytes[pos + width - 1 - i] = byte;
+        } else {
+            bytes[pos + i] = byte;
+        }
+    }
+}
+
+/// The byte orders to try for numbers of the given `width`
+fn byte_orders(width: usize) -> &'static [bool] {
+    if width == 1 {
+        &[false]
+    } else {
+        &[false, true]
+    }
+}
+
+/// A stage running the deterministic `AFL` mutations on each testcase, the first time it is scheduled.
+///
+/// Each position of the input is mutated in order, so this takes hundreds of executions per byte
+/// and is only worth it for small inputs.
+#[derive(Clone, Debug)]
+pub struct DeterministicStage<E, EM, Z> {
+    phantom: PhantomData<(E, EM, Z)>,
+}
+
+impl<E, EM, Z> UsesState for DeterministicStage<E, EM, Z>
+where
+    E: UsesState,
+{
+    type State = E::State;
+}
+
+impl<E, EM, Z> Stage<E, EM, Z> for DeterministicStage<E, EM, Z>
+where
+    E: UsesState<State = Z::State>,
+    EM: UsesState<State = Z::State>,
+    Z: Evaluator<E, EM>,
+    Z::State: HasClientPerfMonitor + HasCorpus,
+    <Z::State as UsesInput>::Input: HasBytesVec,
+{
+    fn perform(
+        &mut self,
+        fuzzer: &mut Z,
+        executor: &mut E,
+        state: &mut Z::State,
+        manager: &mut EM,
+        corpus_idx: CorpusId,
+    ) -> Result<(), Error> {
+        start_timer!(state);
+        let base = {
+            let corpus = state.corpus();
+            let mut testcase = corpus.get(corpus_idx)?.borrow_mut();
+            if testcase.has_metadata::<DeterministicDoneMetadata>() {
+                None
+            } else {
+                let base = testcase.load_input(corpus)?.clone();
+                // Marked before the walk, so a crash partway through doesn't restart it over and over
+                testcase.add_metadata(DeterministicDoneMetadata);
+                Some(base)
+            }
+        };
+        mark_feature_time!(state, PerfFeature::GetInputFromCorpus);
+
+        if let Some(base) = base {
+            Self::walk(fuzzer, executor, state, manager, &base)?;
+        }
+
+        #[cfg(feature = "introspection")]
+        state.introspection_monitor_mut().finish_stage();
+
+        Ok(())
+    }
+}
+
+impl<E, EM, Z> DeterministicStage<E, EM, Z>
+where
+    E: UsesState<State = Z::State>,
+    EM: UsesState<State = Z::State>,
+    Z: Evaluator<E, EM>,
+    Z::State: HasClientPerfMonitor + HasCorpus,
+    <Z::State as UsesInput>::Input: HasBytesVec,
+{
+    /// Creates a new [`DeterministicStage`]
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            phantom: PhantomData,
+        }
+    }
+
+    /// Runs all deterministic mutations of `base`
+    #[allow(clippy::cast_sign_loss)]
+    fn walk(
+        fuzzer: &mut Z,
+        executor: &mut E,
+        state: &mut Z::State,
+        manager: &mut EM,
+        base: &<Z::State as UsesInput>::Input,
+    ) -> Result<(), Error> {
+        let len = base.bytes().len();
+
+        // Walking bit flips, of 1, 2 and 4 consecutive bits
+        for width in [1, 2, 4] {
+            for bit in 0..(len * 8).saturating_sub(width - 1) {
+                Self::evaluate(fuzzer, executor, state, manager, base, |bytes| {
+                    for i in bit..bit + width {
+                        bytes[i >> 3] ^= 128 >> (i & 7);
+                    }
+                })?;
+            }
+        }
+
+        // Walking byte flips, of 1, 2 and 4 consecutive bytes
+        for width in [1, 2, 4] {
+            for pos in 0..(len + 1).saturating_sub(width) {
+                Self::evaluate(fuzzer, executor, state, manager, base, |bytes| {
+                    for byte in &mut bytes[pos..pos + width] {
+                        *byte ^= 0xff;
+                    }
+                })?;
+            }
+        }
+
+        // Add and subtract up to `ARITH_MAX` to 8, 16 and 32 bit numbers, in both byte orders
+        for width in [1, 2, 4] {
+            for pos in 0..(len + 1).saturating_sub(width) {
+                for &big_endian in byte_orders(width) {
+                    for delta in 1..=ARITH_MAX {
+                        for delta in [delta, delta.wrapping_neg()] {
+                            Self::evaluate(fuzzer, executor, state, manager, base, |bytes| {
+                                let num = read_num(bytes, pos, width, big_endian);
+                                write_num(bytes, pos, width, num.wrapping_add(delta), big_endian);
+                            })?;
+                        }
+                    }
+                }
+            }
+        }
+
+        // Overwrite 8, 16 and 32 bit numbers with interesting values, in both byte orders
+        let interesting: [(usize, Vec<u64>); 3] = [
+            (1, INTERESTING_8.iter().map(|&v| v as u64).collect()),
+            (2, INTERESTING_16.iter().map(|&v| v as u64).collect()),
+            (4, INTERESTING_32.iter().map(|&v| v as u64).collect()),
+        ];
+        for (width, values) in &interesting {
+            let width = *width;
+            for pos in 0..(len + 1).saturating_sub(width) {
+                for &big_endian in byte_orders(width) {
+                    for &value in values {
+                        Self::evaluate(fuzzer, executor, state, manager, base, |bytes| {
+                            write_num(bytes, pos, width, value, big_endian);
+                        })?;
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Evaluates a copy of `base`, changed by `mutate`, skipping mutations that change nothing
+    fn evaluate<F>(
+        fuzzer: &mut Z,
+        executor: &mut E,
+        state: &mut Z::State,
+        manager: &mut EM,
+        base: &<Z::State as UsesInput>::Input,
+        mutate: F,
+    ) -> Result<(), Error>
+    where
+        F: FnOnce(&mut [u8]),
+    {
+        let mut input = base.clone();
+        mutate(input.bytes_mut());
+        if input.bytes() != base.bytes() {
+            fuzzer.evaluate_input(state, executor, manager, input)?;
+        }
+        Ok(())
+    }
+}
+
+impl<E, EM, Z> Default for DeterministicStage<E, EM, Z>
+where
+    E: UsesState<State = Z::State>,
+    EM: UsesState<State = Z::State>,
+    Z: Evaluator<E, EM>,
+    Z::State: HasClientPerfMonitor + HasCorpus,
+    <Z::State as UsesInput>::Input: HasBytesVec,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        bolts::{rands::StdRand, tuples::tuple_list},
+        corpus::{Corpus, InMemoryCorpus, Testcase},
+        events::NopEventManager,
+        executors::{ExitKind, InProcessExecutor},
+        feedbacks::ConstFeedback,
+        inputs::BytesInput,
+        schedulers::RandScheduler,
+        stages::{
+            deterministic::{read_num, write_num, DeterministicDoneMetadata, DeterministicStage},
+            Stage,
+        },
+        state::{HasCorpus, HasMetadata, StdState},
+        StdFuzzer,
+    };
+
+    #[test]
+    fn test_read_write_num() {
+        let mut bytes = [0x12, 0x34, 0x56, 0x78];
+        assert_eq!(read_num(&bytes, 0, 4, false), 0x7856_3412);
+        assert_eq!(read_num(&bytes, 1, 2, true), 0x3456);
+
+        write_num(&mut bytes, 0, 2, 0xffff_abcd, true);
+        assert_eq!(bytes, [0xab, 0xcd, 0x56, 0x78]);
+        write_num(&mut bytes, 2, 2, 0xfffe, false);
+        assert_eq!(bytes, [0xab, 0xcd, 0xfe, 0xff]);
+    }
+
+    #[test]
+    fn test_deterministic_stage() {
+        let mut corpus = InMemoryCorpus::<BytesInput>::new();
+        let corpus_idx = corpus.add(Testcase::new(vec![0].into())).unwrap();
+
+        let mut feedback = ConstFeedback::new(false);
+        let mut objective = ConstFeedback::new(false);
+        let mut state = StdState::new(
+            StdRand::with_seed(0),
+            corpus,
+            InMemoryCorpus::<BytesInput>::new(),
+            &mut feedback,
+            &mut objective,
+        )
+        .unwrap();
+        let mut manager = NopEventManager::new();
+        let mut fuzzer = StdFuzzer::new(RandScheduler::new(), feedback, objective);
+
+        let mut runs = 0;
+        let mut harness = |_input: &BytesInput| {
+            runs += 1;
+            ExitKind::Ok
+        };
+        let mut executor = InProcessExecutor::new(
+            &mut harness,
+            tuple_list!(),
+            &mut fuzzer,
+            &mut state,
+            &mut manager,
+        )
+        .unwrap();
+
+        let mut stage = DeterministicStage::new();
+        // Scheduled twice, the mutations only run the first time
+        for _ in 0..2 {
+            stage
+                .perform(
+                    &mut fuzzer,
+                    &mut executor,
+                    &mut state,
+                    &mut manager,
+                    corpus_idx,
+                )
+                .unwrap();
+        }
+
+        // 20 bit flips, 1 byte flip, 70 arithmetics and 8 interesting values that change the byte
+        assert_eq!(runs, 99);
+        assert!(state
+            .corpus()
+            .get(corpus_idx)
+            .unwrap()
+            .borrow()
+            .has_metadata::<DeterministicDoneMetadata>());
+    }
+}
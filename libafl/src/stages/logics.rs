@@ -1,11 +1,13 @@
 //! Stage wrappers that add logics to stage list
 
+use alloc::boxed::Box;
 use core::marker::PhantomData;
 
 use crate::{
+    bolts::rands::Rand,
     corpus::CorpusId,
-    stages::{Stage, StagesTuple},
-    state::UsesState,
+    stages::{Stage, StagesProgressMetadata, StagesTuple},
+    state::{HasMetadata, HasRand, UsesState},
     Error,
 };
 
@@ -108,6 +110,7 @@ impl<CB, E, EM, ST, Z> Stage<E, EM, Z> for IfStage<CB, E, EM, ST, Z>
 where
     CB: FnMut(&mut Z, &mut E, &mut E::State, &mut EM, CorpusId) -> Result<bool, Error>,
     E: UsesState,
+    E::State: HasMetadata,
     EM: UsesState<State = E::State>,
     ST: StagesTuple<E, EM, E::State, Z>,
     Z: UsesState<State = E::State>,
@@ -120,7 +123,12 @@ where
         manager: &mut EM,
         corpus_idx: CorpusId,
     ) -> Result<(), Error> {
-        if (self.closure)(fuzzer, executor, state, manager, corpus_idx)? {
+        // If the fuzzer restarted in the middle of the wrapped stages, the closure allowed them before
+        let resuming = state
+            .metadata_map()
+            .get::<StagesProgressMetadata>()
+            .map_or(false, StagesProgressMetadata::is_resuming);
+        if resuming || (self.closure)(fuzzer, executor, state, manager, corpus_idx)? {
             self.if_stages
                 .perform_all(fuzzer, executor, state, manager, corpus_idx)?;
         }
@@ -223,3 +231,210 @@ where
         }
     }
 }
+
+/// The condition of a [`SkippableStage`]
+pub type SkippableCondition<'a, E, EM, Z> = Box<
+    dyn FnMut(
+            &mut Z,
+            &mut E,
+            &mut <E as UsesState>::State,
+            &mut EM,
+            CorpusId,
+        ) -> Result<bool, Error>
+        + 'a,
+>;
+
+/// A randomly skipped stage, an [`IfStage`] rolling the dice.
+/// The wrapped stages are executed with the given probability, else they will be skipped.
+pub type SkippableStage<'a, E, EM, ST, Z> = IfStage<SkippableCondition<'a, E, EM, Z>, E, EM, ST, Z>;
+
+impl<'a, E, EM, ST, Z> SkippableStage<'a, E, EM, ST, Z>
+where
+    E: UsesState + 'a,
+    E::State: HasRand,
+    EM: UsesState<State = E::State> + 'a,
+    ST: StagesTuple<E, EM, E::State, Z> + 'a,
+    Z: UsesState<State = E::State> + 'a,
+{
+    /// Constructor for this randomly skipped stage.
+    /// The wrapped stages will be executed with the given `probability`, between `0.0` and `1.0`.
+    pub fn with_probability(probability: f64, stages: ST) -> Result<Self, Error> {
+        if !(0.0..=1.0).contains(&probability) {
+            return Err(Error::illegal_argument(format!(
+                "Invalid probability {probability} for SkippableStage, must be between 0 and 1"
+            )));
+        }
+        let condition: SkippableCondition<'a, E, EM, Z> = Box::new(
+            move |_fuzzer: &mut Z,
+                  _executor: &mut E,
+                  state: &mut E::State,
+                  _manager: &mut EM,
+                  _corpus_idx: CorpusId| {
+                // Exactly representable rolls in `[0, 1)`, so that `1.0` always runs the stages
+                #[allow(clippy::cast_precision_loss)]
+                let roll = state.rand_mut().below(1 << 53) as f64 / (1_u64 << 53) as f64;
+                Ok(roll < probability)
+            },
+        );
+        Ok(Self::new(condition, stages))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+    use core::cell::{Cell, RefCell};
+
+    use crate::{
+        bolts::tuples::tuple_list,
+        corpus::CorpusId,
+        events::NopEventManager,
+        executors::NopExecutor,
+        inputs::BytesInput,
+        stages::{
+            ClosureStage, IfStage, SkippableStage, Stage, StagesProgressMetadata, StagesTuple,
+        },
+        state::{HasMetadata, NopState},
+        Error, NopFuzzer,
+    };
+
+    type TestState = NopState<BytesInput>;
+    type TestExecutor = NopExecutor<TestState>;
+    type TestManager = NopEventManager<TestState>;
+    type TestFuzzer = NopFuzzer<BytesInput>;
+
+    /// A stage logging its `name` each time it runs
+    fn logging_stage<'a>(
+        log: &'a RefCell<Vec<&'static str>>,
+        name: &'static str,
+    ) -> impl Stage<TestExecutor, TestManager, TestFuzzer, State = TestState> + 'a {
+        ClosureStage::new(
+            move |_fuzzer: &mut TestFuzzer,
+                  _executor: &mut TestExecutor,
+                  _state: &mut TestState,
+                  _manager: &mut TestManager,
+                  _corpus_idx: CorpusId| {
+                log.borrow_mut().push(name);
+                Ok(())
+            },
+        )
+    }
+
+    #[test]
+    fn test_if_stage_resume() {
+        let log = RefCell::new(Vec::new());
+        let saved = RefCell::new(None);
+        let mut stages = tuple_list!(
+            logging_stage(&log, "first"),
+            IfStage::new(
+                |_fuzzer: &mut TestFuzzer,
+                 _executor: &mut TestExecutor,
+                 _state: &mut TestState,
+                 _manager: &mut TestManager,
+                 _corpus_idx: CorpusId| {
+                    log.borrow_mut().push("if");
+                    Ok(true)
+                },
+                tuple_list!(
+                    logging_stage(&log, "a"),
+                    ClosureStage::new(
+                        |_fuzzer: &mut TestFuzzer,
+                         _executor: &mut TestExecutor,
+                         state: &mut TestState,
+                         _manager: &mut TestManager,
+                         _corpus_idx: CorpusId| {
+                            log.borrow_mut().push("b");
+                            if saved.borrow().is_some() {
+                                return Ok(());
+                            }
+                            // Crash: the state is saved, and the fuzzer restarts
+                            let progress = state.metadata::<StagesProgressMetadata>()?;
+                            *saved.borrow_mut() = Some(postcard::to_allocvec(progress)?);
+                            Err(Error::shutting_down())
+                        },
+                    ),
+                ),
+            ),
+            logging_stage(&log, "last"),
+        );
+
+        let corpus_idx = CorpusId::from(0_usize);
+        let mut fuzzer = TestFuzzer::new();
+        let mut executor = TestExecutor::new();
+        let mut state = TestState::new();
+        let mut manager = TestManager::new();
+        stages
+            .perform_all(
+                &mut fuzzer,
+                &mut executor,
+                &mut state,
+                &mut manager,
+                corpus_idx,
+            )
+            .unwrap_err();
+
+        let progress: StagesProgressMetadata =
+            postcard::from_bytes(saved.borrow().as_ref().unwrap()).unwrap();
+        assert_eq!(progress.resumed_corpus_idx(), Some(corpus_idx));
+        state.add_metadata(progress);
+        stages
+            .perform_all(
+                &mut fuzzer,
+                &mut executor,
+                &mut state,
+                &mut manager,
+                corpus_idx,
+            )
+            .unwrap();
+
+        // The finished stages and the decision of the `IfStage` are not redone
+        assert_eq!(*log.borrow(), ["first", "if", "a", "b", "b", "last"]);
+        assert_eq!(
+            state
+                .metadata::<StagesProgressMetadata>()
+                .unwrap()
+                .resumed_corpus_idx(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_skippable_stage() {
+        assert!(
+            SkippableStage::<TestExecutor, TestManager, (), TestFuzzer>::with_probability(1.5, ())
+                .is_err()
+        );
+
+        for (probability, expected) in [(0.0, 0), (1.0, 100)] {
+            let runs = Cell::new(0);
+            let mut stages = tuple_list!(SkippableStage::with_probability(
+                probability,
+                tuple_list!(ClosureStage::new(
+                    |_fuzzer: &mut TestFuzzer,
+                     _executor: &mut TestExecutor,
+                     _state: &mut TestState,
+                     _manager: &mut TestManager,
+                     _corpus_idx: CorpusId| {
+                        runs.set(runs.get() + 1);
+                        Ok(())
+                    }
+                )),
+            )
+            .unwrap());
+
+            let mut state = TestState::new();
+            for _ in 0..100 {
+                stages
+                    .perform_all(
+                        &mut TestFuzzer::new(),
+                        &mut TestExecutor::new(),
+                        &mut state,
+                        &mut TestManager::new(),
+                        CorpusId::from(0_usize),
+                    )
+                    .unwrap();
+            }
+            assert_eq!(runs.get(), expected);
+        }
+    }
+}
@@ -27,6 +27,9 @@ pub use power::{PowerMutationalStage, StdPowerMutationalStage};
 pub mod generalization;
 pub use generalization::GeneralizationStage;
 
+pub mod deterministic;
+pub use deterministic::{DeterministicDoneMetadata, DeterministicStage};
+
 pub mod owned;
 pub use owned::StagesOwnedList;
 
@@ -51,12 +54,19 @@ pub mod sync;
 #[cfg(feature = "std")]
 pub use sync::*;
 
+#[cfg(feature = "std")]
+pub mod batch;
+#[cfg(feature = "std")]
+pub use batch::BatchMutationalStage;
+
 #[cfg(feature = "std")]
 pub mod dump;
+use alloc::vec::Vec;
 use core::{convert::From, marker::PhantomData};
 
 #[cfg(feature = "std")]
 pub use dump::*;
+use serde::{Deserialize, Serialize};
 
 use self::push::PushStage;
 use crate::{
@@ -78,6 +88,19 @@ where
     EM: UsesState<State = Self::State>,
     Z: UsesState<State = Self::State>,
 {
+    /// Whether to run this stage again, after the fuzzer restarted in the middle of it.
+    /// Stages tracking their own progress can return `false` if they are done already.
+    #[inline]
+    fn should_restart(&mut self, _state: &mut Self::State) -> Result<bool, Error> {
+        Ok(true)
+    }
+
+    /// Clears the progress this stage tracked, once it finished
+    #[inline]
+    fn clear_progress(&mut self, _state: &mut Self::State) -> Result<(), Error> {
+        Ok(())
+    }
+
     /// Run the stage
     fn perform(
         &mut self,
@@ -89,6 +112,134 @@ where
     ) -> Result<(), Error>;
 }
 
+/// How often a stage is resumed after restarts in the middle of it, before it is skipped
+pub const STAGE_MAX_RESTARTS: usize = 3;
+
+/// The stages running for the current corpus entry, stored in the state.
+/// If the fuzzer restarts in the middle of them, e.g. after a crash,
+/// it resumes the same entry, and skips the stages that finished before.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct StagesProgressMetadata {
+    /// The corpus entry the stages run for
+    corpus_idx: Option<CorpusId>,
+    /// For each nesting level, the index of the running stage and how often it was restarted
+    stack: Vec<(usize, usize)>,
+    /// The nesting level of the stages running in this process
+    #[serde(skip)]
+    depth: usize,
+}
+
+crate::impl_serdeany!(StagesProgressMetadata);
+
+/// What to do with a stage, according to the [`StagesProgressMetadata`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum StageEntry {
+    /// The stage finished before the restart
+    Skip,
+    /// The stage was running when the fuzzer restarted
+    Resume,
+    /// The stage is run the first time
+    Run,
+}
+
+impl StagesProgressMetadata {
+    /// The corpus entry to resume, if the fuzzer restarted in the middle of its stages
+    #[must_use]
+    pub fn resumed_corpus_idx(&self) -> Option<CorpusId> {
+        if self.stack.is_empty() {
+            None
+        } else {
+            self.corpus_idx
+        }
+    }
+
+    /// Whether the stages nested in the running stage are resumed after a restart
+    #[must_use]
+    pub fn is_resuming(&self) -> bool {
+        self.stack.len() > self.depth
+    }
+
+    /// Enters the stage at index `idx` of the running [`StagesTuple`]
+    fn enter(&mut self, corpus_idx: CorpusId, idx: usize) -> StageEntry {
+        if self.depth == 0 && self.corpus_idx != Some(corpus_idx) {
+            self.corpus_idx = Some(corpus_idx);
+            self.stack.clear();
+        }
+        let entry = match self.stack.get_mut(self.depth) {
+            Some((running, _)) if idx < *running => return StageEntry::Skip,
+            Some((running, restarts)) if idx == *running => {
+                *restarts += 1;
+                if *restarts > STAGE_MAX_RESTARTS {
+                    // Don't get stuck on a stage that keeps crashing the fuzzer
+                    self.stack.truncate(self.depth);
+                    return StageEntry::Skip;
+                }
+                StageEntry::Resume
+            }
+            _ => {
+                self.stack.truncate(self.depth);
+                self.stack.push((idx, 0));
+                StageEntry::Run
+            }
+        };
+        self.depth += 1;
+        entry
+    }
+
+    /// Leaves the running stage, once it finished
+    fn exit(&mut self) {
+        self.depth -= 1;
+        self.stack.truncate(self.depth);
+    }
+}
+
+/// Performs the stage at index `idx` of its [`StagesTuple`], unless it finished before a restart
+fn perform_tracked<ST, E, EM, Z>(
+    stage: &mut ST,
+    fuzzer: &mut Z,
+    executor: &mut E,
+    state: &mut ST::State,
+    manager: &mut EM,
+    corpus_idx: CorpusId,
+    idx: usize,
+) -> Result<(), Error>
+where
+    ST: Stage<E, EM, Z> + ?Sized,
+    ST::State: HasMetadata,
+    E: UsesState<State = ST::State>,
+    EM: UsesState<State = ST::State>,
+    Z: UsesState<State = ST::State>,
+{
+    if !state.has_metadata::<StagesProgressMetadata>() {
+        state.add_metadata(StagesProgressMetadata::default());
+    }
+    let entry = state
+        .metadata_map_mut()
+        .get_mut::<StagesProgressMetadata>()
+        .unwrap()
+        .enter(corpus_idx, idx);
+
+    let ret = match entry {
+        StageEntry::Skip => return Ok(()),
+        StageEntry::Resume => stage.should_restart(state).and_then(|restart| {
+            if restart {
+                stage.perform(fuzzer, executor, state, manager, corpus_idx)
+            } else {
+                Ok(())
+            }
+        }),
+        StageEntry::Run => stage.perform(fuzzer, executor, state, manager, corpus_idx),
+    };
+
+    state
+        .metadata_map_mut()
+        .get_mut::<StagesProgressMetadata>()
+        .unwrap()
+        .exit();
+    ret?;
+    stage.clear_progress(state)
+}
+
 /// A tuple holding all `Stages` used for fuzzing.
 pub trait StagesTuple<E, EM, S, Z>
 where
@@ -105,6 +256,20 @@ where
         state: &mut S,
         manager: &mut EM,
         corpus_idx: CorpusId,
+    ) -> Result<(), Error> {
+        self.perform_from(fuzzer, executor, state, manager, corpus_idx, 0)
+    }
+
+    /// Performs the `Stages` in this tuple, numbering them from `idx` on.
+    /// The stages that finished before a restart are skipped, see [`StagesProgressMetadata`].
+    fn perform_from(
+        &mut self,
+        fuzzer: &mut Z,
+        executor: &mut E,
+        state: &mut S,
+        manager: &mut EM,
+        corpus_idx: CorpusId,
+        idx: usize,
     ) -> Result<(), Error>;
 }
 
@@ -115,13 +280,14 @@ where
     Z: UsesState<State = S>,
     S: UsesInput,
 {
-    fn perform_all(
+    fn perform_from(
         &mut self,
         _: &mut Z,
         _: &mut E,
         _: &mut S,
         _: &mut EM,
         _: CorpusId,
+        _: usize,
     ) -> Result<(), Error> {
         Ok(())
     }
@@ -130,26 +296,35 @@ where
 impl<Head, Tail, E, EM, Z> StagesTuple<E, EM, Head::State, Z> for (Head, Tail)
 where
     Head: Stage<E, EM, Z>,
+    Head::State: HasMetadata,
     Tail: StagesTuple<E, EM, Head::State, Z>,
     E: UsesState<State = Head::State>,
     EM: UsesState<State = Head::State>,
     Z: UsesState<State = Head::State>,
 {
-    fn perform_all(
+    fn perform_from(
         &mut self,
         fuzzer: &mut Z,
         executor: &mut E,
         state: &mut Head::State,
         manager: &mut EM,
         corpus_idx: CorpusId,
+        idx: usize,
     ) -> Result<(), Error> {
         // Perform the current stage
-        self.0
-            .perform(fuzzer, executor, state, manager, corpus_idx)?;
+        perform_tracked(
+            &mut self.0,
+            fuzzer,
+            executor,
+            state,
+            manager,
+            corpus_idx,
+            idx,
+        )?;
 
         // Execute the remaining stages
         self.1
-            .perform_all(fuzzer, executor, state, manager, corpus_idx)
+            .perform_from(fuzzer, executor, state, manager, corpus_idx, idx + 1)
     }
 }
 
@@ -313,7 +488,9 @@ pub mod pybind {
         events::pybind::PythonEventManager,
         executors::pybind::PythonExecutor,
         fuzzer::pybind::{PythonStdFuzzer, PythonStdFuzzerWrapper},
-        stages::{mutational::pybind::PythonStdMutationalStage, Stage, StagesTuple},
+        stages::{
+            mutational::pybind::PythonStdMutationalStage, perform_tracked, Stage, StagesTuple,
+        },
         state::{
             pybind::{PythonStdState, PythonStdStateWrapper},
             UsesState,
@@ -467,16 +644,17 @@ pub mod pybind {
     impl StagesTuple<PythonExecutor, PythonEventManager, PythonStdState, PythonStdFuzzer>
         for PythonStagesTuple
     {
-        fn perform_all(
+        fn perform_from(
             &mut self,
             fuzzer: &mut PythonStdFuzzer,
             executor: &mut PythonExecutor,
             state: &mut PythonStdState,
             manager: &mut PythonEventManager,
             corpus_idx: CorpusId,
+            idx: usize,
         ) -> Result<(), Error> {
-            for s in &mut self.list {
-                s.perform(fuzzer, executor, state, manager, corpus_idx)?;
+            for (i, s) in self.list.iter_mut().enumerate() {
+                perform_tracked(s, fuzzer, executor, state, manager, corpus_idx, idx + i)?;
             }
             Ok(())
         }
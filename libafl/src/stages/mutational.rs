@@ -1,4 +1,4 @@
-//| The [`MutationalStage`] is the default stage used during fuzzing.
+//! The [`MutationalStage`] is the default stage used during fuzzing.
 //! For the current input, it will perform a range of random mutations, and then run them in the executor.
 
 use core::marker::PhantomData;
@@ -151,6 +151,7 @@ pub static DEFAULT_MUTATIONAL_MAX_ITERATIONS: u64 = 128;
 #[derive(Clone, Debug)]
 pub struct StdMutationalStage<E, EM, I, M, Z> {
     mutator: M,
+    max_iterations: u64,
     #[allow(clippy::type_complexity)]
     phantom: PhantomData<(E, EM, I, Z)>,
 }
@@ -176,9 +177,9 @@ where
         &mut self.mutator
     }
 
-    /// Gets the number of iterations as a random number
+    /// Gets the number of iterations as a random number, bounded by `max_iterations`
     fn iterations(&self, state: &mut Z::State, _corpus_idx: CorpusId) -> Result<u64, Error> {
-        Ok(1 + state.rand_mut().below(DEFAULT_MUTATIONAL_MAX_ITERATIONS))
+        Ok(1 + state.rand_mut().below(self.max_iterations))
     }
 }
 
@@ -233,6 +234,11 @@ where
     pub fn new(mutator: M) -> Self {
         Self::transforming(mutator)
     }
+
+    /// Creates a new mutational stage with at most `max_iterations` mutations per run
+    pub fn with_max_iterations(mutator: M, max_iterations: u64) -> Result<Self, Error> {
+        Self::transforming_with_max_iterations(mutator, max_iterations)
+    }
 }
 
 impl<E, EM, I, M, Z> StdMutationalStage<E, EM, I, M, Z>
@@ -247,9 +253,27 @@ where
     pub fn transforming(mutator: M) -> Self {
         Self {
             mutator,
+            max_iterations: DEFAULT_MUTATIONAL_MAX_ITERATIONS,
             phantom: PhantomData,
         }
     }
+
+    /// Creates a new transforming mutational stage with at most `max_iterations` mutations per run
+    pub fn transforming_with_max_iterations(
+        mutator: M,
+        max_iterations: u64,
+    ) -> Result<Self, Error> {
+        if max_iterations == 0 {
+            return Err(Error::illegal_argument(
+                "StdMutationalStage needs at least one iteration",
+            ));
+        }
+        Ok(Self {
+            mutator,
+            max_iterations,
+            phantom: PhantomData,
+        })
+    }
 }
 
 #[cfg(feature = "python")]
@@ -301,3 +325,79 @@ pub mod pybind {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use core::cell::Cell;
+
+    use crate::{
+        bolts::{rands::StdRand, tuples::tuple_list},
+        corpus::{Corpus, InMemoryCorpus, Testcase},
+        events::NopEventManager,
+        executors::{ExitKind, InProcessExecutor},
+        feedbacks::ConstFeedback,
+        inputs::BytesInput,
+        mutators::BitFlipMutator,
+        schedulers::RandScheduler,
+        stages::{Stage, StdMutationalStage},
+        state::StdState,
+        StdFuzzer,
+    };
+
+    #[test]
+    fn test_mutational_max_iterations() {
+        let mut corpus = InMemoryCorpus::<BytesInput>::new();
+        let corpus_idx = corpus.add(Testcase::new(vec![0; 4].into())).unwrap();
+        let mut feedback = ConstFeedback::new(false);
+        let mut objective = ConstFeedback::new(false);
+        let mut state = StdState::new(
+            StdRand::with_seed(0),
+            corpus,
+            InMemoryCorpus::<BytesInput>::new(),
+            &mut feedback,
+            &mut objective,
+        )
+        .unwrap();
+        let mut manager = NopEventManager::new();
+        let mut fuzzer = StdFuzzer::new(RandScheduler::new(), feedback, objective);
+
+        let runs = Cell::new(0);
+        let mut harness = |_input: &BytesInput| {
+            runs.set(runs.get() + 1);
+            ExitKind::Ok
+        };
+        let mut executor = InProcessExecutor::new(
+            &mut harness,
+            tuple_list!(),
+            &mut fuzzer,
+            &mut state,
+            &mut manager,
+        )
+        .unwrap();
+
+        let stage_with = |max_iterations| {
+            StdMutationalStage::with_max_iterations(BitFlipMutator::new(), max_iterations)
+        };
+        assert!(stage_with(0).is_err());
+
+        let mut stage = stage_with(3).unwrap();
+        let mut seen = [false; 3];
+        for _ in 0..100 {
+            let before = runs.get();
+            stage
+                .perform(
+                    &mut fuzzer,
+                    &mut executor,
+                    &mut state,
+                    &mut manager,
+                    corpus_idx,
+                )
+                .unwrap();
+            let iterations = runs.get() - before;
+            assert!((1..=3).contains(&iterations));
+            seen[iterations - 1] = true;
+        }
+        // The bound is reached, but never exceeded
+        assert_eq!(seen, [true; 3]);
+    }
+}
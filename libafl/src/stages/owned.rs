@@ -5,8 +5,8 @@ use alloc::{boxed::Box, vec::Vec};
 use crate::{
     bolts::anymap::AsAny,
     corpus::CorpusId,
-    stages::{Stage, StagesTuple},
-    state::UsesState,
+    stages::{perform_tracked, Stage, StagesTuple},
+    state::{HasMetadata, UsesState},
     Error,
 };
 
@@ -34,19 +34,29 @@ where
 impl<E, EM, Z> StagesTuple<E, EM, E::State, Z> for StagesOwnedList<E, EM, Z>
 where
     E: UsesState,
+    E::State: HasMetadata,
     EM: UsesState<State = E::State>,
     Z: UsesState<State = E::State>,
 {
-    fn perform_all(
+    fn perform_from(
         &mut self,
         fuzzer: &mut Z,
         executor: &mut E,
         state: &mut E::State,
         manager: &mut EM,
         corpus_idx: CorpusId,
+        idx: usize,
     ) -> Result<(), Error> {
-        for s in &mut self.list {
-            s.perform(fuzzer, executor, state, manager, corpus_idx)?;
+        for (i, s) in self.list.iter_mut().enumerate() {
+            perform_tracked(
+                s.as_mut(),
+                fuzzer,
+                executor,
+                state,
+                manager,
+                corpus_idx,
+                idx + i,
+            )?;
         }
         Ok(())
     }
@@ -7,6 +7,7 @@ use std::{
     time::SystemTime,
 };
 
+use hashbrown::HashSet;
 use serde::{Deserialize, Serialize};
 
 use crate::{
@@ -24,8 +25,11 @@ use crate::{
 /// Metadata used to store information about disk sync time
 #[derive(Serialize, Deserialize, Debug)]
 pub struct SyncFromDiskMetadata {
-    /// The last time the sync was done
+    /// The modification time of the newest file loaded so far
     pub last_time: SystemTime,
+    /// The files modified at `last_time` that were already loaded.
+    /// Files written later can have the same modification time, on filesystems with a coarse one.
+    pub last_files: HashSet<PathBuf>,
 }
 
 crate::impl_serdeany!(SyncFromDiskMetadata);
@@ -33,8 +37,11 @@ crate::impl_serdeany!(SyncFromDiskMetadata);
 impl SyncFromDiskMetadata {
     /// Create a new [`struct@SyncFromDiskMetadata`]
     #[must_use]
-    pub fn new(last_time: SystemTime) -> Self {
-        Self { last_time }
+    pub fn new(last_time: SystemTime, last_files: HashSet<PathBuf>) -> Self {
+        Self {
+            last_time,
+            last_files,
+        }
     }
 }
 
@@ -70,25 +77,32 @@ where
         manager: &mut EM,
         _corpus_idx: CorpusId,
     ) -> Result<(), Error> {
-        let last = state
+        let (last, last_files) = state
             .metadata_map()
             .get::<SyncFromDiskMetadata>()
-            .map(|m| m.last_time);
+            .map_or((None, HashSet::new()), |m| {
+                (Some(m.last_time), m.last_files.clone())
+            });
         let path = self.sync_dir.clone();
-        if let Some(max_time) =
-            self.load_from_directory(&path, &last, fuzzer, executor, state, manager)?
-        {
-            if last.is_none() {
-                state
-                    .metadata_map_mut()
-                    .insert(SyncFromDiskMetadata::new(max_time));
-            } else {
-                state
-                    .metadata_map_mut()
-                    .get_mut::<SyncFromDiskMetadata>()
-                    .unwrap()
-                    .last_time = max_time;
+        let mut newest = None;
+        self.load_from_directory(
+            &path,
+            last,
+            &last_files,
+            &mut newest,
+            fuzzer,
+            executor,
+            state,
+            manager,
+        )?;
+        if let Some((max_time, mut max_files)) = newest {
+            // Only new files with the same time as the last ones were loaded, keep both
+            if last == Some(max_time) {
+                max_files.extend(last_files);
             }
+            state
+                .metadata_map_mut()
+                .insert(SyncFromDiskMetadata::new(max_time, max_files));
         }
 
         #[cfg(feature = "introspection")]
@@ -116,16 +130,20 @@ where
         }
     }
 
+    /// Loads the files in `in_dir` that were not loaded before, recursively.
+    /// Keeps the newest modification time of the loaded files in `newest`, with the files modified then.
+    #[allow(clippy::too_many_arguments)]
     fn load_from_directory(
         &mut self,
         in_dir: &Path,
-        last: &Option<SystemTime>,
+        last: Option<SystemTime>,
+        last_files: &HashSet<PathBuf>,
+        newest: &mut Option<(SystemTime, HashSet<PathBuf>)>,
         fuzzer: &mut Z,
         executor: &mut E,
         state: &mut Z::State,
         manager: &mut EM,
-    ) -> Result<Option<SystemTime>, Error> {
-        let mut max_time = None;
+    ) -> Result<(), Error> {
         for entry in fs::read_dir(in_dir)? {
             let entry = entry?;
             let path = entry.path();
@@ -139,25 +157,31 @@ where
 
             if attr.is_file() && attr.len() > 0 {
                 if let Ok(time) = attr.modified() {
+                    // Older files were loaded in an earlier sync, as were some with the same time
                     if let Some(l) = last {
-                        if time.duration_since(*l).is_err() {
+                        if time < l || (time == l && last_files.contains(&path)) {
                             continue;
                         }
                     }
-                    max_time = Some(max_time.map_or(time, |t: SystemTime| t.max(time)));
                     let input = (self.load_callback)(fuzzer, state, &path)?;
                     fuzzer.evaluate_input(state, executor, manager, input)?;
+
+                    match newest {
+                        Some((max_time, max_files)) if time == *max_time => {
+                            max_files.insert(path);
+                        }
+                        Some((max_time, _)) if time < *max_time => {}
+                        _ => *newest = Some((time, [path].into_iter().collect())),
+                    }
                 }
             } else if attr.is_dir() {
-                let dir_max_time =
-                    self.load_from_directory(&path, last, fuzzer, executor, state, manager)?;
-                if let Some(time) = dir_max_time {
-                    max_time = Some(max_time.map_or(time, |t: SystemTime| t.max(time)));
-                }
+                self.load_from_directory(
+                    &path, last, last_files, newest, fuzzer, executor, state, manager,
+                )?;
             }
         }
 
-        Ok(max_time)
+        Ok(())
     }
 }
 
@@ -275,6 +299,7 @@ where
                     Event::NewTestcase {
                         input,
                         observers_buf: None,
+                        coverage_hash: None,
                         exit_kind: ExitKind::Ok,
                         corpus_size: 0, // TODO choose if sending 0 or the actual real value
                         client_config: EventConfig::AlwaysUnique,
@@ -322,3 +347,92 @@ where
         Self { client }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use core::time::Duration;
+    use std::{
+        fs,
+        path::Path,
+        time::{SystemTime, UNIX_EPOCH},
+    };
+
+    use crate::{
+        bolts::{rands::StdRand, tuples::tuple_list},
+        corpus::{Corpus, CorpusId, InMemoryCorpus},
+        events::NopEventManager,
+        executors::{ExitKind, InProcessExecutor},
+        feedbacks::{ConstFeedback, CrashFeedback},
+        inputs::{BytesInput, Input},
+        schedulers::RandScheduler,
+        stages::{Stage, SyncFromDiskStage},
+        state::{HasCorpus, StdState},
+        StdFuzzer,
+    };
+
+    /// Writes `content` to `path`, modified at `time`
+    fn write_file(path: &Path, content: u8, time: SystemTime) {
+        BytesInput::new(vec![content]).to_file(path).unwrap();
+        fs::File::options()
+            .write(true)
+            .open(path)
+            .unwrap()
+            .set_modified(time)
+            .unwrap();
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn test_sync_same_modification_time() {
+        let dir = std::env::temp_dir().join("libafl_test_sync_same_mtime");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+
+        // Every loaded input is added to the corpus
+        let mut feedback = ConstFeedback::new(true);
+        let mut objective = CrashFeedback::new();
+        let mut state = StdState::new(
+            StdRand::with_seed(0),
+            InMemoryCorpus::<BytesInput>::new(),
+            InMemoryCorpus::new(),
+            &mut feedback,
+            &mut objective,
+        )
+        .unwrap();
+        let mut manager = NopEventManager::new();
+        let mut fuzzer = StdFuzzer::new(RandScheduler::new(), feedback, objective);
+        let mut harness = |_input: &BytesInput| ExitKind::Ok;
+        let mut executor = InProcessExecutor::new(
+            &mut harness,
+            tuple_list!(),
+            &mut fuzzer,
+            &mut state,
+            &mut manager,
+        )
+        .unwrap();
+        let mut stage = SyncFromDiskStage::with_from_file(dir.clone());
+        let mut sync = |state: &mut _| {
+            stage
+                .perform(
+                    &mut fuzzer,
+                    &mut executor,
+                    state,
+                    &mut manager,
+                    CorpusId::from(0_usize),
+                )
+                .unwrap();
+        };
+
+        // The second file is written after the first sync, but with the same, coarse, time
+        let time = UNIX_EPOCH + Duration::from_secs(1_000_000);
+        write_file(&dir.join("first"), 1, time);
+        sync(&mut state);
+        assert_eq!(state.corpus().count(), 1);
+
+        write_file(&dir.join("second"), 2, time);
+        sync(&mut state);
+        sync(&mut state);
+        fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(state.corpus().count(), 2);
+    }
+}
@@ -111,7 +111,6 @@ where
                 // TODO replace if process_execution adds a return value for solution index
                 let solution_count = state.solutions().count();
                 let corpus_count = state.corpus().count();
-                *state.executions_mut() += 1;
                 let (_, corpus_idx) = fuzzer.process_execution(
                     state,
                     manager,
@@ -154,7 +153,6 @@ where
         if base_hash != new_hash {
             let exit_kind = fuzzer.execute_input(state, executor, manager, &base)?;
             let observers = executor.observers();
-            *state.executions_mut() += 1;
             // assumption: this input should not be marked interesting because it was not
             // marked as interesting above; similarly, it should not trigger objectives
             fuzzer
@@ -354,7 +352,9 @@ where
     {
         let obs = observers
             .match_name::<M>(self.observer_name())
-            .expect("Should have been provided valid observer name.");
+            .ok_or_else(|| {
+                Error::key_not_found(format!("MapObserver {} not found", self.observer_name()))
+            })?;
         Ok(obs.hash() == self.orig_hash)
     }
 }
@@ -15,6 +15,8 @@ use std::{
 
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 
+#[cfg(feature = "std")]
+use crate::bolts::fs::write_file_atomic;
 #[cfg(test)]
 use crate::bolts::rands::StdRand;
 use crate::{
@@ -38,7 +40,27 @@ pub const DEFAULT_MAX_SIZE: usize = 1_048_576;
 /// The [`State`] of the fuzzer.
 /// Contains all important information about the current run.
 /// Will be used to restart the fuzzing process at any time.
-pub trait State: UsesInput + Serialize + DeserializeOwned {}
+pub trait State: UsesInput + Serialize + DeserializeOwned {
+    /// Write this state to the file at `path`, to resume the campaign later using [`State::load_from`].
+    /// On-disk corpora only store the paths to their testcases, so these have to be kept as well.
+    /// Given a `state_file`, the [`crate::events::RestartingMgr`] does this on every restart.
+    #[cfg(feature = "std")]
+    fn save_to<P>(&self, path: P) -> Result<(), Error>
+    where
+        P: AsRef<Path>,
+    {
+        write_file_atomic(path, &postcard::to_allocvec(self)?)
+    }
+
+    /// Load a state previously written with [`State::save_to`]
+    #[cfg(feature = "std")]
+    fn load_from<P>(path: P) -> Result<Self, Error>
+    where
+        P: AsRef<Path>,
+    {
+        Ok(postcard::from_bytes(&fs::read(path)?)?)
+    }
+}
 
 /// Structs which implement this trait are aware of the state. This is used for type enforcement.
 pub trait UsesState: UsesInput<Input = <Self::State as UsesInput>::Input> {
@@ -518,9 +540,8 @@ where
         self.continue_loading_initial_inputs_custom(fuzzer, executor, manager, forced, loader)
     }
 
-    /// Loads initial inputs from the passed-in `in_dirs`.
+    /// Loads the remaining initial inputs, listed by a previous `load_initial_inputs` call.
     /// If `forced` is true, will add all testcases, no matter what.
-    /// This method takes a list of files.
     fn continue_loading_initial_inputs_custom<E, EM, Z>(
         &mut self,
         fuzzer: &mut Z,
@@ -562,9 +583,8 @@ where
         Ok(())
     }
 
-    /// Loads all intial inputs, even if they are not considered `interesting`.
-    /// This is rarely the right method, use `load_initial_inputs`,
-    /// and potentially fix your `Feedback`, instead.
+    /// Loads initial inputs from the passed-in list of files.
+    /// Only inputs considered `interesting` by the feedbacks are added to the corpus.
     /// This method takes a list of files, instead of folders.
     pub fn load_initial_inputs_by_filenames<E, EM, Z>(
         &mut self,
@@ -588,7 +608,7 @@ where
         )
     }
 
-    /// Loads all intial inputs, even if they are not considered `interesting`.
+    /// Loads all initial inputs, even if they are not considered `interesting`.
     /// This is rarely the right method, use `load_initial_inputs`,
     /// and potentially fix your `Feedback`, instead.
     pub fn load_initial_inputs_forced<E, EM, Z>(
@@ -613,8 +633,8 @@ where
         )
     }
 
-    /// Loads initial inputs from the passed-in `in_dirs`.
-    /// If `forced` is true, will add all testcases, no matter what.
+    /// Loads all initial inputs from the passed-in list of files,
+    /// even if they are not considered `interesting`.
     /// This method takes a list of files, instead of folders.
     pub fn load_initial_inputs_by_filenames_forced<E, EM, Z>(
         &mut self,
@@ -1020,3 +1040,100 @@ pub mod pybind {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        bolts::rands::StdRand,
+        corpus::{Corpus, InMemoryCorpus, Testcase},
+        inputs::BytesInput,
+        state::{HasCorpus, HasExecutions, HasMaxSize, HasSolutions, StdState},
+    };
+
+    type TestState =
+        StdState<BytesInput, InMemoryCorpus<BytesInput>, StdRand, InMemoryCorpus<BytesInput>>;
+
+    #[test]
+    fn test_std_state_serde_roundtrip() {
+        let mut state: TestState = StdState::new(
+            StdRand::with_seed(0),
+            InMemoryCorpus::new(),
+            InMemoryCorpus::new(),
+            &mut (),
+            &mut (),
+        )
+        .unwrap();
+        state
+            .corpus_mut()
+            .add(Testcase::new(BytesInput::new(vec![1, 2, 3])))
+            .unwrap();
+        *state.executions_mut() = 42;
+        state.set_max_size(1337);
+
+        let serialized = postcard::to_allocvec(&state).unwrap();
+        let restored: TestState = postcard::from_bytes(&serialized).unwrap();
+
+        assert_eq!(restored.corpus().count(), 1);
+        assert_eq!(restored.solutions().count(), 0);
+        assert_eq!(*restored.executions(), 42);
+        assert_eq!(restored.max_size(), 1337);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_visit_initial_directory() {
+        use std::{fs, path::PathBuf};
+
+        let dir = PathBuf::from("target/.test/initial_inputs");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("nested")).unwrap();
+        fs::write(dir.join("seed"), b"seed").unwrap();
+        fs::write(dir.join("nested").join("nested_seed"), b"seed").unwrap();
+        fs::write(dir.join(".hidden"), b"seed").unwrap();
+        fs::write(dir.join("empty"), b"").unwrap();
+
+        let mut files = vec![];
+        TestState::visit_initial_directory(&mut files, &dir).unwrap();
+        files.sort();
+        assert_eq!(
+            files,
+            vec![dir.join("nested").join("nested_seed"), dir.join("seed")]
+        );
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_std_state_save_load() {
+        use std::{fs, path::PathBuf};
+
+        use crate::state::State;
+
+        let dir = PathBuf::from("target/.test/state");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+
+        let mut state: TestState = StdState::new(
+            StdRand::with_seed(0),
+            InMemoryCorpus::new(),
+            InMemoryCorpus::new(),
+            &mut (),
+            &mut (),
+        )
+        .unwrap();
+        state
+            .corpus_mut()
+            .add(Testcase::new(BytesInput::new(vec![1, 2, 3])))
+            .unwrap();
+        *state.executions_mut() = 42;
+
+        let path = dir.join("state");
+        state.save_to(&path).unwrap();
+        let restored = TestState::load_from(&path).unwrap();
+        assert_eq!(restored.corpus().count(), 1);
+        assert_eq!(*restored.executions(), 42);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -235,7 +235,12 @@ where
             }
             if !options.dont_instrument.is_empty() {
                 for (module_name, offset) in options.dont_instrument.clone() {
-                    let module_details = ModuleDetails::with_name(module_name).unwrap();
+                    let Some(module_details) = ModuleDetails::with_name(module_name.clone()) else {
+                        log::warn!(
+                            "Module {module_name} to not instrument is not loaded, ignoring it"
+                        );
+                        continue;
+                    };
                     let lib_start = module_details.range().base_address().0 as usize;
                     // log::info!("removing address: {:#x}", lib_start + offset);
                     helper
@@ -178,9 +178,8 @@ where
     match meta.map.entry((src, dest)) {
         Entry::Occupied(e) => {
             let id = *e.get();
-            let nxt = (id as usize + 1) & (EDGES_MAP_SIZE - 1);
             unsafe {
-                MAX_EDGES_NUM = max(MAX_EDGES_NUM, nxt);
+                MAX_EDGES_NUM = max(MAX_EDGES_NUM, id as usize + 1);
             }
             Some(id)
         }
@@ -188,8 +187,9 @@ where
             let id = meta.current_id;
             e.insert(id);
             meta.current_id = (id + 1) & (EDGES_MAP_SIZE as u64 - 1);
+            // Never shrink the map, not even once `current_id` wrapped around.
             unsafe {
-                MAX_EDGES_NUM = meta.current_id as usize;
+                MAX_EDGES_NUM = max(MAX_EDGES_NUM, id as usize + 1);
             }
             // GuestAddress is u32 for 32 bit guests
             #[allow(clippy::unnecessary_cast)]
@@ -131,6 +131,15 @@ fn main() {
             .compile("forkserver");
     }
 
+    #[cfg(unix)]
+    {
+        println!("cargo:rerun-if-changed=src/recovery.c");
+
+        cc::Build::new()
+            .file(src_dir.join("recovery.c"))
+            .compile("recovery");
+    }
+
     #[cfg(windows)]
     {
         println!("cargo:rerun-if-changed=src/windows_asan.c");
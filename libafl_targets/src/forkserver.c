@@ -180,8 +180,15 @@ static void map_input_shared_memory() {
       exit(1);
     }
 
-    map = (uint8_t *)mmap(0, MAX_FILE + sizeof(uint32_t), PROT_READ, MAP_SHARED,
-                          shm_fd, 0);
+    /* the fuzzer picks the maximum input size, map all of it */
+    struct stat shm_stat;
+    if (fstat(shm_fd, &shm_stat) == -1) {
+      fprintf(stderr, "fstat() failed for fuzz\n");
+      send_forkserver_error(FS_ERROR_SHM_OPEN);
+      exit(1);
+    }
+
+    map = (uint8_t *)mmap(0, shm_stat.st_size, PROT_READ, MAP_SHARED, shm_fd, 0);
 
 #else
     uint32_t shm_id = atoi(id_str);
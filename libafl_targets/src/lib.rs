@@ -110,3 +110,8 @@ pub use windows_asan::*;
 pub mod forkserver;
 #[cfg(unix)]
 pub use forkserver::*;
+
+#[cfg(unix)]
+pub mod recovery;
+#[cfg(unix)]
+pub use recovery::*;
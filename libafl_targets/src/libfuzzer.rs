@@ -9,7 +9,7 @@ extern "C" {
     fn LLVMFuzzerTestOneInput(data: *const u8, size: usize) -> i32;
 
     // libafl_targets_libfuzzer_init calls LLVMFUzzerInitialize()
-    fn libafl_targets_libfuzzer_init(argc: *const i32, argv: *const *const *const u8) -> i32;
+    fn libafl_targets_libfuzzer_init(argc: *mut i32, argv: *mut *const *const u8) -> i32;
 }
 
 /// Calls the (native) libfuzzer initialize function.
@@ -20,13 +20,19 @@ extern "C" {
 #[allow(clippy::must_use_candidate)] // nobody uses that return code...
 pub fn libfuzzer_initialize(args: &[String]) -> i32 {
     let args: Vec<String> = args.iter().map(|x| x.clone() + "\0").collect();
-    let argv: Vec<*const u8> = args.iter().map(|x| x.as_bytes().as_ptr()).collect();
+    let mut argv: Vec<*const u8> = args.iter().map(|x| x.as_bytes().as_ptr()).collect();
     assert!(argv.len() < i32::MAX as usize);
     #[allow(clippy::cast_possible_wrap)]
-    let argc = argv.len() as i32;
+    let mut argc = argv.len() as i32;
+    // Like for `main`, harnesses may expect `argv[argc]` to be `NULL`.
+    argv.push(core::ptr::null());
     unsafe {
-        let argv_ptr = argv.as_ptr();
-        libafl_targets_libfuzzer_init(core::ptr::addr_of!(argc), core::ptr::addr_of!(argv_ptr))
+        // `LLVMFuzzerInitialize` is allowed to modify both `argc` and `argv`.
+        let mut argv_ptr = argv.as_ptr();
+        libafl_targets_libfuzzer_init(
+            core::ptr::addr_of_mut!(argc),
+            core::ptr::addr_of_mut!(argv_ptr),
+        )
     }
 }
 
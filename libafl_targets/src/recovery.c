@@ -0,0 +1,27 @@
+#include "common.h"
+
+#include <setjmp.h>
+
+// The jump point of the harness currently running in `libafl_run_recoverable`
+static sigjmp_buf recovery_jmp;
+static volatile int recovery_armed;
+
+// Runs `harness(data)`, returns 0 if it returned, or the signal number if the
+// crash handler jumped back here using `libafl_recover`
+int libafl_run_recoverable(void (*harness)(void *), void *data) {
+  int sig = sigsetjmp(recovery_jmp, 1);
+  if (sig) {
+    recovery_armed = 0;
+    return sig;
+  }
+
+  recovery_armed = 1;
+  harness(data);
+  recovery_armed = 0;
+  return 0;
+}
+
+// Jumps back into `libafl_run_recoverable`, returns if no harness is running
+void libafl_recover(int sig) {
+  if (recovery_armed) { siglongjmp(recovery_jmp, sig ? sig : -1); }
+}
@@ -0,0 +1,131 @@
+//! Recover from crashes of in-process targets, instead of exiting.
+//!
+//! The harness is run behind a `sigsetjmp` jump point, and the crash handler of the
+//! `InProcessExecutor` jumps back to it using `siglongjmp`.
+//! This is only sound if the crashing target can keep running afterwards, and if the frames
+//! between the harness and the crash have nothing to drop.
+
+use core::ffi::{c_int, c_void};
+
+use libafl::executors::{inprocess::set_crash_recovery, ExitKind};
+
+extern "C" {
+    fn libafl_run_recoverable(
+        harness: unsafe extern "C" fn(*mut c_void),
+        data: *mut c_void,
+    ) -> c_int;
+    fn libafl_recover(sig: c_int);
+}
+
+/// Calls the closure behind `data`
+unsafe extern "C" fn call_harness<F>(data: *mut c_void)
+where
+    F: FnMut(),
+{
+    (*(data as *mut F))();
+}
+
+/// Make the crash handler of the `InProcessExecutor` jump back into [`run_recoverable`],
+/// instead of exiting the process.
+///
+/// # Safety
+///
+/// The crash handler will skip the frames of the target on crashes, see the module docs.
+pub unsafe fn setup_crash_recovery() {
+    set_crash_recovery(Some(libafl_recover));
+}
+
+/// Runs `harness`, returning [`ExitKind::Crash`] if it crashed and the crash handler jumped
+/// back here, see [`setup_crash_recovery`].
+/// Only one harness may run at a time.
+///
+/// # Safety
+///
+/// On a crash, the frames between `harness` and the crash are skipped, without running their drops.
+/// There must be nothing to drop in them, no locks held, and no state left half-updated that the
+/// next run depends on, which is usually only true for C targets.
+pub unsafe fn run_recoverable<F>(mut harness: F) -> ExitKind
+where
+    F: FnMut(),
+{
+    let sig = libafl_run_recoverable(call_harness::<F>, (&mut harness as *mut F).cast());
+    if sig == 0 {
+        ExitKind::Ok
+    } else {
+        ExitKind::Crash
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use core::ffi::c_int;
+
+    use libafl::{
+        bolts::{rands::StdRand, tuples::tuple_list},
+        corpus::InMemoryCorpus,
+        events::NopEventManager,
+        executors::{inprocess::set_crash_recovery, Executor, ExitKind, InProcessExecutor},
+        feedbacks::ConstFeedback,
+        inputs::{BytesInput, HasBytesVec},
+        schedulers::RandScheduler,
+        state::StdState,
+        StdFuzzer,
+    };
+
+    use crate::recovery::{run_recoverable, setup_crash_recovery};
+
+    extern "C" {
+        fn raise(sig: c_int) -> c_int;
+    }
+
+    /// `SIGSEGV` on all unixes
+    const SIGSEGV: c_int = 11;
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn test_run_recoverable() {
+        let mut feedback = ConstFeedback::new(false);
+        let mut objective = ConstFeedback::new(false);
+        let mut state = StdState::new(
+            StdRand::with_seed(0),
+            InMemoryCorpus::<BytesInput>::new(),
+            InMemoryCorpus::new(),
+            &mut feedback,
+            &mut objective,
+        )
+        .unwrap();
+        let mut manager = NopEventManager::new();
+        let mut fuzzer = StdFuzzer::new(RandScheduler::new(), feedback, objective);
+
+        let mut harness = |input: &BytesInput| unsafe {
+            run_recoverable(|| {
+                if input.bytes()[0] == 0 {
+                    raise(SIGSEGV);
+                }
+            })
+        };
+        let mut executor = InProcessExecutor::new(
+            &mut harness,
+            tuple_list!(),
+            &mut fuzzer,
+            &mut state,
+            &mut manager,
+        )
+        .unwrap();
+
+        unsafe { setup_crash_recovery() };
+        // The crash is reported in-process, and the next input still runs
+        for (byte, expected) in [(0, ExitKind::Crash), (1, ExitKind::Ok)] {
+            let exit_kind = executor
+                .run_target(
+                    &mut fuzzer,
+                    &mut state,
+                    &mut manager,
+                    &BytesInput::new(vec![byte]),
+                )
+                .unwrap();
+            assert_eq!(exit_kind, expected);
+        }
+        unsafe { set_crash_recovery(None) };
+    }
+}
@@ -8,9 +8,11 @@
 #include "cmplog.h"
 #endif
 
-void __sanitizer_cov_trace_cmp1(uint8_t arg1, uint8_t arg2) {
+// The hooks take `RETADDR` themselves and hand it on, so that a hook calling another
+// (like `trace_const_cmp*`) doesn't report its own address for every comparison.
+static inline void __libafl_targets_trace_cmp1(uintptr_t k, uint8_t arg1,
+                                                uint8_t arg2) {
 
-  uintptr_t k = RETADDR;
   k = (k >> 4) ^ (k << 8);
 
 #ifdef SANCOV_VALUE_PROFILE
@@ -24,9 +26,9 @@ void __sanitizer_cov_trace_cmp1(uint8_t arg1, uint8_t arg2) {
 
 }
 
-void __sanitizer_cov_trace_cmp2(uint16_t arg1, uint16_t arg2) {
+static inline void __libafl_targets_trace_cmp2(uintptr_t k, uint16_t arg1,
+                                                uint16_t arg2) {
 
-  uintptr_t k = RETADDR;
   k = (k >> 4) ^ (k << 8);
 
 #ifdef SANCOV_VALUE_PROFILE
@@ -40,9 +42,9 @@ void __sanitizer_cov_trace_cmp2(uint16_t arg1, uint16_t arg2) {
 
 }
 
-void __sanitizer_cov_trace_cmp4(uint32_t arg1, uint32_t arg2) {
+static inline void __libafl_targets_trace_cmp4(uintptr_t k, uint32_t arg1,
+                                                uint32_t arg2) {
 
-  uintptr_t k = RETADDR;
   k = (k >> 4) ^ (k << 8);
 
 #ifdef SANCOV_VALUE_PROFILE
@@ -56,9 +58,9 @@ void __sanitizer_cov_trace_cmp4(uint32_t arg1, uint32_t arg2) {
 
 }
 
-void __sanitizer_cov_trace_cmp8(uint64_t arg1, uint64_t arg2) {
+static inline void __libafl_targets_trace_cmp8(uintptr_t k, uint64_t arg1,
+                                                uint64_t arg2) {
 
-  uintptr_t k = RETADDR;
   k = (k >> 4) ^ (k << 8);
 
 #ifdef SANCOV_VALUE_PROFILE
@@ -72,6 +74,22 @@ void __sanitizer_cov_trace_cmp8(uint64_t arg1, uint64_t arg2) {
 
 }
 
+void __sanitizer_cov_trace_cmp1(uint8_t arg1, uint8_t arg2) {
+  __libafl_targets_trace_cmp1(RETADDR, arg1, arg2);
+}
+
+void __sanitizer_cov_trace_cmp2(uint16_t arg1, uint16_t arg2) {
+  __libafl_targets_trace_cmp2(RETADDR, arg1, arg2);
+}
+
+void __sanitizer_cov_trace_cmp4(uint32_t arg1, uint32_t arg2) {
+  __libafl_targets_trace_cmp4(RETADDR, arg1, arg2);
+}
+
+void __sanitizer_cov_trace_cmp8(uint64_t arg1, uint64_t arg2) {
+  __libafl_targets_trace_cmp8(RETADDR, arg1, arg2);
+}
+
 void __sanitizer_cov_trace_switch(uint64_t val, uint64_t *cases) {
 
   uintptr_t rt = RETADDR;
@@ -111,17 +129,17 @@ void __sanitizer_cov_trace_switch(uint64_t val, uint64_t *cases) {
 }
 
 void __sanitizer_cov_trace_const_cmp1(uint8_t arg1, uint8_t arg2) {
-  __sanitizer_cov_trace_cmp1(arg1, arg2);
+  __libafl_targets_trace_cmp1(RETADDR, arg1, arg2);
 }
 
 void __sanitizer_cov_trace_const_cmp2(uint16_t arg1, uint16_t arg2) {
-  __sanitizer_cov_trace_cmp2(arg1, arg2);
+  __libafl_targets_trace_cmp2(RETADDR, arg1, arg2);
 }
 
 void __sanitizer_cov_trace_const_cmp4(uint32_t arg1, uint32_t arg2) {
-  __sanitizer_cov_trace_cmp4(arg1, arg2);
+  __libafl_targets_trace_cmp4(RETADDR, arg1, arg2);
 }
 
 void __sanitizer_cov_trace_const_cmp8(uint64_t arg1, uint64_t arg2) {
-    __sanitizer_cov_trace_cmp8(arg1, arg2);
+  __libafl_targets_trace_cmp8(RETADDR, arg1, arg2);
 }
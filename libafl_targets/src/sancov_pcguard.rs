@@ -57,7 +57,9 @@ pub unsafe extern "C" fn __sanitizer_cov_trace_pc_guard_init(mut start: *mut u32
         EDGES_MAP_PTR_NUM = EDGES_MAP.len();
     }
 
-    if start == stop || *start != 0 {
+    // This may be called multiple times for the same module, only hand out ids once.
+    // The very first guard legitimately gets id `0`, so also look at the last guard of the range.
+    if start == stop || *start != 0 || *stop.offset(-1) != 0 {
         return;
     }
 